@@ -0,0 +1,380 @@
+// src/app/command.rs
+//! The `:` command line: parsing, tab completion and history.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{
+    config::{expand_home, setting_names},
+    ui::{
+        keybindings::{NavigationAction, ACTION_NAMES},
+        theme::bundled_names,
+    },
+};
+
+/// Commands taking an argument, with its usage. Every action name (e.g.
+/// `next_track`) is a command too, taking an optional count.
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("cd", "<directory>"),
+    ("add", "<file, directory or playlist>"),
+    ("volume", "<percent>, +<step> or -<step>"),
+    ("seek", "<[h:]m:ss>, +<time> or -<time>"),
+    ("save", "<playlist>"),
+    ("set", "<setting>=<value>"),
+    ("theme", "<name>"),
+];
+
+/// Most commands kept in the history.
+const HISTORY_LEN: usize = 100;
+
+/// A value given outright or as a signed change of the current one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Amount {
+    To(u64),
+    By(i64),
+}
+
+impl Amount {
+    /// Parse `60`, `+5` or `-5`, each part read by `value`.
+    fn parse(text: &str, value: impl Fn(&str) -> Option<u64>) -> Option<Self> {
+        let signed = |rest| value(rest).and_then(|v| i64::try_from(v).ok());
+        if let Some(rest) = text.strip_prefix('+') {
+            signed(rest).map(Amount::By)
+        } else if let Some(rest) = text.strip_prefix('-') {
+            signed(rest).map(|v| Amount::By(-v))
+        } else {
+            value(text).map(Amount::To)
+        }
+    }
+
+    /// The new value given the `current` one, never below zero.
+    pub fn apply(self, current: u64) -> u64 {
+        match self {
+            Amount::To(v) => v,
+            Amount::By(d) => current.saturating_add_signed(d),
+        }
+    }
+}
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Cd(String),
+    Add(String),
+    /// Volume in percent
+    Volume(Amount),
+    /// Position in seconds
+    Seek(Amount),
+    Save(String),
+    Set(String, String),
+    Theme(String),
+    Action(NavigationAction, Option<usize>),
+}
+
+impl Command {
+    /// Parse a command line (without the leading `:`).
+    pub fn parse(line: &str) -> Result<Self> {
+        let line = line.trim();
+        let (name, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let arg = arg.trim();
+        if let Some((_, usage)) = COMMANDS.iter().find(|(n, _)| *n == name) {
+            let usage = || anyhow!("usage: :{} {}", name, usage);
+            if arg.is_empty() {
+                return Err(usage());
+            }
+            return Ok(match name {
+                "cd" => Command::Cd(arg.to_string()),
+                "add" => Command::Add(arg.to_string()),
+                "volume" => {
+                    Command::Volume(Amount::parse(arg, |v| v.parse().ok()).ok_or_else(usage)?)
+                }
+                "seek" => Command::Seek(Amount::parse(arg, parse_time).ok_or_else(usage)?),
+                "save" => Command::Save(arg.to_string()),
+                "set" => {
+                    let (key, value) = arg
+                        .split_once('=')
+                        .or_else(|| arg.split_once(' '))
+                        .ok_or_else(usage)?;
+                    Command::Set(key.trim().to_string(), value.trim().to_string())
+                }
+                _ => Command::Theme(arg.to_string()),
+            });
+        }
+        match NavigationAction::from_name(name) {
+            Some(NavigationAction::None) | None => bail!("unknown command \"{}\"", name),
+            Some(action) if arg.is_empty() => Ok(Command::Action(action, None)),
+            Some(action) => match arg.parse() {
+                Ok(count) => Ok(Command::Action(action, Some(count))),
+                Err(_) => bail!("usage: :{} [count]", name),
+            },
+        }
+    }
+}
+
+/// Parse `90`, `1:30` or `1:02:03` into seconds. Minutes and seconds
+/// after a colon must be below 60.
+fn parse_time(text: &str) -> Option<u64> {
+    let parts: Vec<&str> = text.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let (first, rest) = parts.split_first()?;
+    rest.iter()
+        .try_fold(first.parse::<u64>().ok()?, |secs, part| {
+            let value = part.parse::<u64>().ok().filter(|v| *v < 60)?;
+            secs.checked_mul(60)?.checked_add(value)
+        })
+}
+
+/// `path` as typed at the command line: `~` expanded, relative to `dir`.
+pub fn resolve_path(dir: &Path, path: &str) -> PathBuf {
+    dir.join(expand_home(path))
+}
+
+/// Add `line` to the end of `history`, dropping an older copy of it.
+pub fn remember(history: &mut Vec<String>, line: &str) {
+    history.retain(|l| l != line);
+    history.push(line.to_string());
+    if history.len() > HISTORY_LEN {
+        history.drain(..history.len() - HISTORY_LEN);
+    }
+}
+
+/// Candidates Tab is cycling through.
+#[derive(Debug, Clone)]
+struct Completion {
+    /// Input before the word being completed
+    base: String,
+    candidates: Vec<String>,
+    index: usize,
+}
+
+/// Text typed at the `:` prompt.
+#[derive(Debug, Default, Clone)]
+pub struct CommandLine {
+    /// Current input, without the `:`
+    pub input: String,
+    /// History entry shown, while browsing the history
+    history_pos: Option<usize>,
+    /// Input typed before browsing the history
+    draft: String,
+    /// Tab completion in progress
+    completion: Option<Completion>,
+}
+
+impl CommandLine {
+    /// Type `c`.
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+        self.stop_browsing();
+    }
+
+    /// Delete the last character.
+    pub fn pop(&mut self) {
+        self.input.pop();
+        self.stop_browsing();
+    }
+
+    fn stop_browsing(&mut self) {
+        self.history_pos = None;
+        self.completion = None;
+    }
+
+    /// Show the history entry before (`direction` = -1) or after (1) the
+    /// one shown; moving past the newest brings back the typed input.
+    pub fn recall(&mut self, history: &[String], direction: i32) {
+        self.completion = None;
+        let pos = match (self.history_pos, direction < 0) {
+            (None, true) => history.len().checked_sub(1),
+            (None, false) => return,
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) => Some(pos + 1).filter(|&p| p < history.len()),
+        };
+        if self.history_pos.is_none() {
+            self.draft = self.input.clone();
+        }
+        match pos {
+            Some(pos) => {
+                self.input = history[pos].clone();
+                self.history_pos = Some(pos);
+            }
+            None => {
+                self.input = std::mem::take(&mut self.draft);
+                self.history_pos = None;
+            }
+        }
+    }
+
+    /// Complete the word before the cursor: a unique candidate is filled in,
+    /// otherwise their common start, and further presses cycle through them
+    /// forward (`direction` = 1) or backward (-1). Paths are relative to `dir`.
+    pub fn complete(&mut self, dir: &Path, direction: i32) {
+        self.history_pos = None;
+        if let Some(c) = &mut self.completion {
+            let len = c.candidates.len() as i32;
+            c.index = (c.index as i32 + direction).rem_euclid(len) as usize;
+            self.input = format!("{}{}", c.base, c.candidates[c.index]);
+            return;
+        }
+        let (start, candidates) = candidates(&self.input, dir);
+        let base = self.input[..start].to_string();
+        let word = &self.input[start..];
+        let common = common_prefix(&candidates);
+        if candidates.len() == 1 || common.len() > word.len() {
+            self.input = format!("{}{}", base, common);
+        } else if !candidates.is_empty() {
+            let index = if direction < 0 {
+                candidates.len() - 1
+            } else {
+                0
+            };
+            self.input = format!("{}{}", base, candidates[index]);
+            self.completion = Some(Completion {
+                base,
+                candidates,
+                index,
+            });
+        }
+    }
+
+    /// Position and count of the candidates Tab is cycling through.
+    pub fn completion_status(&self) -> Option<(usize, usize)> {
+        self.completion
+            .as_ref()
+            .map(|c| (c.index + 1, c.candidates.len()))
+    }
+}
+
+/// Where the word being completed starts in `input`, and what it can become.
+fn candidates(input: &str, dir: &Path) -> (usize, Vec<String>) {
+    let Some((name, arg)) = input.split_once(' ') else {
+        let mut names: Vec<String> = COMMANDS
+            .iter()
+            .map(|(n, _)| format!("{} ", n))
+            .chain(
+                ACTION_NAMES
+                    .iter()
+                    .filter(|(n, _)| *n != "none")
+                    .map(|(n, _)| n.to_string()),
+            )
+            .filter(|n| n.starts_with(input))
+            .collect();
+        names.sort();
+        return (0, names);
+    };
+    let start = input.len() - arg.len();
+    let mut names: Vec<String> = match name {
+        "cd" => path_candidates(arg, dir, true),
+        "add" | "save" => path_candidates(arg, dir, false),
+        "set" if !arg.contains('=') => setting_names()
+            .filter(|n| n.starts_with(arg))
+            .map(|n| format!("{}=", n))
+            .collect(),
+        "theme" => bundled_names()
+            .into_iter()
+            .filter(|n| n.starts_with(arg))
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    names.sort();
+    (start, names)
+}
+
+/// Entries of the directory part of `arg` starting with its last part,
+/// directories with a trailing `/`. Hidden entries need a typed `.`.
+fn path_candidates(arg: &str, dir: &Path, dirs_only: bool) -> Vec<String> {
+    let (parent, prefix) = match arg.rfind('/') {
+        Some(i) => arg.split_at(i + 1),
+        None => ("", arg),
+    };
+    let Ok(entries) = fs::read_dir(resolve_path(dir, parent)) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            // Follow symlinks to tell directories apart
+            let is_dir = e.path().is_dir();
+            if is_dir {
+                Some(format!("{}{}/", parent, name))
+            } else if dirs_only {
+                None
+            } else {
+                Some(format!("{}{}", parent, name))
+            }
+        })
+        .collect()
+}
+
+/// Longest common start of `words`.
+fn common_prefix(words: &[String]) -> String {
+    let Some(first) = words.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for word in &words[1..] {
+        len = first
+            .char_indices()
+            .zip(word.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(word.len()), |((i, _), _)| i.min(len));
+    }
+    first[..len].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("90"), Some(90));
+        assert_eq!(parse_time("1:30"), Some(90));
+        assert_eq!(parse_time("1:02:03"), Some(3723));
+        assert_eq!(parse_time("0:59"), Some(59));
+    }
+
+    #[test]
+    fn rejects_malformed_times() {
+        for text in [
+            "", "1:", ":30", "1:75", "1:60:00", "1:2:3:4", "1,5", "-1", "1.5",
+        ] {
+            assert_eq!(parse_time(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_overflowing_times() {
+        assert_eq!(parse_time("99999999999999999999"), None);
+        assert_eq!(parse_time("999999999999999999:00:00"), None);
+        assert_eq!(parse_time(&format!("{}:00", u64::MAX / 60 + 1)), None);
+        assert!(parse_time(&format!("{}:00", u64::MAX / 60)).is_some());
+    }
+
+    #[test]
+    fn parses_seek_amounts() {
+        assert_eq!(
+            Command::parse("seek 1:30").unwrap(),
+            Command::Seek(Amount::To(90))
+        );
+        assert_eq!(
+            Command::parse("seek +10").unwrap(),
+            Command::Seek(Amount::By(10))
+        );
+        assert_eq!(
+            Command::parse("seek -1:00").unwrap(),
+            Command::Seek(Amount::By(-60))
+        );
+        assert!(Command::parse("seek 1:75").is_err());
+        assert!(Command::parse(&format!("seek +{}", u64::MAX)).is_err());
+    }
+}
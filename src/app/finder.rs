@@ -0,0 +1,112 @@
+// src/app/finder.rs
+//! State of the global fuzzy finder popup.
+
+use std::{
+    path::PathBuf,
+    sync::mpsc::{Receiver, TryRecvError},
+};
+
+use crate::search::{fuzzy_match, spawn_candidates, FinderItem, FuzzyMatch};
+
+/// Maximum number of ranked results kept for display.
+const MAX_RESULTS: usize = 500;
+
+/// Fuzzy finder over every track in the music roots.
+pub struct Finder {
+    /// Current query text
+    pub query: String,
+    /// All candidates received so far
+    pub items: Vec<FinderItem>,
+    /// Best matches as (item index, match), highest score first
+    pub results: Vec<(usize, FuzzyMatch)>,
+    /// Selected row in `results`
+    pub selected: usize,
+    /// Candidate stream; `None` once the walk has finished
+    rx: Option<Receiver<Vec<FinderItem>>>,
+}
+
+impl Finder {
+    /// Open the finder and start streaming candidates.
+    pub fn new(index_path: Option<PathBuf>, roots: Vec<PathBuf>) -> Self {
+        Self {
+            query: String::new(),
+            items: Vec::new(),
+            results: Vec::new(),
+            selected: 0,
+            rx: Some(spawn_candidates(index_path, roots)),
+        }
+    }
+
+    /// True while candidates are still being discovered.
+    pub fn is_loading(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    /// Pull newly discovered candidates and rank them.
+    pub fn poll(&mut self) {
+        let Some(rx) = &self.rx else {
+            return;
+        };
+        let mut changed = false;
+        loop {
+            match rx.try_recv() {
+                Ok(batch) => {
+                    let start = self.items.len();
+                    self.items.extend(batch);
+                    for i in start..self.items.len() {
+                        if let Some(m) = fuzzy_match(&self.query, &self.items[i].haystack) {
+                            self.results.push((i, m));
+                        }
+                    }
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.rx = None;
+                    break;
+                }
+            }
+        }
+        if changed {
+            self.rank();
+        }
+    }
+
+    /// Replace the query and re-rank every candidate.
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.results = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| fuzzy_match(&self.query, &item.haystack).map(|m| (i, m)))
+            .collect();
+        self.selected = 0;
+        self.rank();
+    }
+
+    /// Move the selection by `delta` rows.
+    pub fn move_selection(&mut self, delta: i32) {
+        let max = self.results.len().saturating_sub(1) as i32;
+        self.selected = (self.selected as i32 + delta).clamp(0, max) as usize;
+    }
+
+    /// The selected candidate, if any.
+    pub fn selected_item(&self) -> Option<&FinderItem> {
+        self.results
+            .get(self.selected)
+            .map(|(i, _)| &self.items[*i])
+    }
+
+    /// Sort by score (ties: shorter text first) and keep the best results.
+    fn rank(&mut self) {
+        let items = &self.items;
+        self.results.sort_by(|(a, ma), (b, mb)| {
+            mb.score
+                .cmp(&ma.score)
+                .then(items[*a].haystack.len().cmp(&items[*b].haystack.len()))
+        });
+        self.results.truncate(MAX_RESULTS);
+        self.selected = self.selected.min(self.results.len().saturating_sub(1));
+    }
+}
@@ -0,0 +1,292 @@
+// src/app/help.rs
+//! State of the help overlay, built from the keymap in use.
+
+use crate::ui::{
+    keybindings::{
+        NavigationAction, PromptKeys, COMMAND_LINE_KEYS, CONFIRMED_SEARCH_KEYS, FINDER_KEYS,
+        HELP_FILTER_KEYS, HELP_KEYS, SAVE_PROMPT_KEYS,
+    },
+    keymap::{format_keys, KeyMode, Keymap},
+};
+
+use NavigationAction as A;
+
+/// Sections of the help overlay: title, the mode whose keys are shown and
+/// the actions listed. Actions bound elsewhere in a mode are added under
+/// "Other" so remapped keys always show up.
+const SECTIONS: &[(&str, KeyMode, &[NavigationAction])] = &[
+    (
+        "Moving around",
+        KeyMode::Browser,
+        &[
+            A::Up,
+            A::Down,
+            A::PageUp,
+            A::PageDown,
+            A::Top,
+            A::Bottom,
+            A::Enter,
+            A::Open,
+            A::Back,
+        ],
+    ),
+    (
+        "Browsing",
+        KeyMode::Browser,
+        &[
+            A::CycleBrowseMode,
+            A::Search,
+            A::Find,
+            A::CommandLine,
+            A::CycleColumns,
+            A::ToggleColumn(1),
+            A::ToggleColumn(2),
+            A::ToggleColumn(3),
+            A::ToggleColumn(4),
+            A::ToggleHidden,
+            A::ToggleAllFiles,
+            A::PreviewImage,
+            A::CycleSort,
+            A::ReverseSort,
+            A::ToggleDirsFirst,
+            A::RescanLibrary,
+        ],
+    ),
+    (
+        "Playback",
+        KeyMode::Browser,
+        &[
+            A::TogglePause,
+            A::Stop,
+            A::NextTrack,
+            A::PreviousTrack,
+            A::NextChapter,
+            A::PreviousChapter,
+            A::ChapterList,
+            A::VolumeUp,
+            A::VolumeDown,
+            A::CycleRepeat,
+            A::ToggleShuffle,
+        ],
+    ),
+    (
+        "Queue",
+        KeyMode::Browser,
+        &[A::Enqueue, A::SaveQueue, A::ShowQueue],
+    ),
+    (
+        "Queue view",
+        KeyMode::Queue,
+        &[
+            A::RemoveFromQueue,
+            A::MoveUpInQueue,
+            A::MoveDownInQueue,
+            A::ClearQueue,
+        ],
+    ),
+    (
+        "Panels",
+        KeyMode::Browser,
+        &[
+            A::FocusNext,
+            A::FocusPrevious,
+            A::FocusPane(1),
+            A::FocusPane(2),
+            A::FocusPane(3),
+            A::FocusPane(4),
+            A::ToggleSection(1),
+            A::ToggleSection(2),
+            A::ToggleSection(3),
+            A::ToggleSection(4),
+            A::GrowWidth,
+            A::ShrinkWidth,
+            A::GrowHeight,
+            A::ShrinkHeight,
+            A::ResetLayout,
+            A::ToggleMiniPlayer,
+            A::CycleTheme,
+            A::Help,
+            A::Quit,
+        ],
+    ),
+    (
+        "Search prompt",
+        KeyMode::Search,
+        &[A::Confirm, A::Cancel, A::DeleteChar, A::Down, A::Up],
+    ),
+    (
+        "Chapter list",
+        KeyMode::Chapters,
+        &[A::Down, A::Up, A::Top, A::Bottom, A::Enter, A::Cancel],
+    ),
+    ("Resume offer", KeyMode::Resume, &[A::Confirm, A::Cancel]),
+];
+
+/// Sections for the prompts and overlays whose keys are fixed rather than
+/// taken from the keymap, listed from the tables their handlers use.
+const PROMPT_SECTIONS: &[(&str, PromptKeys)] = &[
+    ("Confirmed search", CONFIRMED_SEARCH_KEYS),
+    ("Finder", FINDER_KEYS),
+    ("Command line", COMMAND_LINE_KEYS),
+    ("Save prompt", SAVE_PROMPT_KEYS),
+    ("Help", HELP_KEYS),
+    ("Help filter", HELP_FILTER_KEYS),
+];
+
+/// What `action` does in `mode`, where that differs from the browser.
+fn describe(mode: KeyMode, action: NavigationAction) -> &'static str {
+    match (mode, action) {
+        (KeyMode::Chapters, A::Enter) => "Jump to the chapter",
+        (KeyMode::Chapters, A::Cancel) => "Close the chapter list",
+        (KeyMode::Resume, A::Confirm) => "Resume where the track was left",
+        (KeyMode::Resume, A::Cancel) => "Play the track from the start",
+        _ => action.description(),
+    }
+}
+
+/// A line of the help overlay.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HelpLine {
+    Heading(String),
+    /// Keys (empty when unbound), description and config name of an
+    /// action; the name is empty for fixed keys
+    Entry {
+        keys: String,
+        description: &'static str,
+        name: &'static str,
+    },
+}
+
+impl HelpLine {
+    /// True if the entry mentions `query` (lowercase) in its keys,
+    /// description or name.
+    fn matches(&self, query: &str) -> bool {
+        match self {
+            HelpLine::Heading(_) => false,
+            HelpLine::Entry {
+                keys,
+                description,
+                name,
+            } => [keys.as_str(), description, name]
+                .iter()
+                .any(|text| text.to_lowercase().contains(query)),
+        }
+    }
+}
+
+/// The help overlay: every action with its keys, grouped by context.
+#[derive(Debug, Clone)]
+pub struct HelpView {
+    lines: Vec<HelpLine>,
+    /// Filter typed after `/`
+    pub query: String,
+    /// True while the filter is receiving keystrokes
+    pub editing: bool,
+    /// Lines shown: all of them, or the matches under their headings
+    pub shown: Vec<usize>,
+    /// First shown line on screen
+    pub scroll: usize,
+    /// Lines that fit on screen at the last draw
+    pub height: usize,
+}
+
+impl HelpView {
+    /// Help for the bindings of `keymap`.
+    pub fn new(keymap: &Keymap) -> Self {
+        let mut lines = Vec::new();
+        for mode in KeyMode::ALL {
+            let mut sections: Vec<(String, Vec<NavigationAction>)> = SECTIONS
+                .iter()
+                .filter(|(_, m, _)| *m == mode)
+                .map(|(title, _, actions)| (title.to_string(), actions.to_vec()))
+                .collect();
+            let mut other = Vec::new();
+            for binding in keymap.bindings(mode) {
+                let listed = sections.iter().any(|(_, a)| a.contains(&binding.action));
+                if !listed && binding.action != A::None && !other.contains(&binding.action) {
+                    other.push(binding.action);
+                }
+            }
+            if !other.is_empty() {
+                sections.push((format!("Other {} keys", mode.name()), other));
+            }
+            for (title, actions) in sections {
+                lines.push(HelpLine::Heading(title));
+                for action in actions {
+                    let keys: Vec<String> = keymap
+                        .keys_for(mode, action)
+                        .into_iter()
+                        .map(format_keys)
+                        .collect();
+                    lines.push(HelpLine::Entry {
+                        keys: keys.join(", "),
+                        description: describe(mode, action),
+                        name: action.name(),
+                    });
+                }
+            }
+        }
+        for (title, entries) in PROMPT_SECTIONS {
+            lines.push(HelpLine::Heading(title.to_string()));
+            for (keys, _, description) in entries.iter() {
+                lines.push(HelpLine::Entry {
+                    keys: keys.join(", "),
+                    description,
+                    name: "",
+                });
+            }
+        }
+        let mut view = Self {
+            lines,
+            query: String::new(),
+            editing: false,
+            shown: Vec::new(),
+            scroll: 0,
+            height: 1,
+        };
+        view.update();
+        view
+    }
+
+    /// Line at `index` of `shown`.
+    pub fn line(&self, index: usize) -> &HelpLine {
+        &self.lines[self.shown[index]]
+    }
+
+    /// Width of the widest key list, for aligning descriptions.
+    pub fn keys_width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| match line {
+                HelpLine::Entry { keys, .. } => keys.chars().count(),
+                HelpLine::Heading(_) => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Recompute the shown lines for the query and go back to the top.
+    pub fn update(&mut self) {
+        let query = self.query.to_lowercase();
+        self.shown.clear();
+        let mut heading = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            if let HelpLine::Heading(_) = line {
+                heading = Some(i);
+            } else if query.is_empty() || line.matches(&query) {
+                // Headings only show above a shown entry
+                if let Some(h) = heading.take() {
+                    self.shown.push(h);
+                }
+                self.shown.push(i);
+            }
+        }
+        self.scroll = 0;
+    }
+
+    /// Scroll by `delta` lines, keeping the screen filled.
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.shown.len().saturating_sub(self.height);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+}
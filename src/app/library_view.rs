@@ -0,0 +1,121 @@
+// src/app/library_view.rs
+//! Navigation state for the tag-based library browser.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::library::{BrowseFilter, BrowseMode, Field, LibraryIndex, Track};
+
+/// One row in the library browser.
+#[derive(Debug, Clone)]
+pub enum LibraryNode {
+    /// A group of tracks sharing `value` for `field`.
+    Group { field: Field, value: Option<String> },
+    /// A single track.
+    Track(Track),
+}
+
+impl LibraryNode {
+    /// Text shown in the list.
+    pub fn label(&self) -> String {
+        match self {
+            LibraryNode::Group { field, value } => field.display(value.as_deref()),
+            LibraryNode::Track(t) => {
+                let title = t.display_title();
+                let secs = t.tags.duration_secs;
+                match t.tags.track_number {
+                    Some(n) => format!("{:02}. {} ({}:{:02})", n, title, secs / 60, secs % 60),
+                    None => format!("{} ({}:{:02})", title, secs / 60, secs % 60),
+                }
+            }
+        }
+    }
+}
+
+/// Current position in a browse hierarchy.
+#[derive(Debug, Default)]
+pub struct LibraryView {
+    /// Active browse mode
+    pub mode: BrowseMode,
+    /// Constraints chosen on the way down, one per level entered
+    pub filter: BrowseFilter,
+    /// Rows at the current level
+    pub nodes: Vec<LibraryNode>,
+    /// Selection to restore when going back up, one per level entered
+    selection_stack: Vec<usize>,
+}
+
+impl LibraryView {
+    /// Switch to `mode` and reset to its top level.
+    pub fn set_mode(&mut self, mode: BrowseMode, index: &LibraryIndex) -> Result<()> {
+        self.mode = mode;
+        self.filter.clear();
+        self.selection_stack.clear();
+        self.reload(index)
+    }
+
+    /// Re-query the rows of the current level.
+    pub fn reload(&mut self, index: &LibraryIndex) -> Result<()> {
+        self.nodes = match self.mode.levels().get(self.filter.len()) {
+            Some(&field) => index
+                .group_values(field, &self.filter)?
+                .into_iter()
+                .map(|value| LibraryNode::Group { field, value })
+                .collect(),
+            None => index
+                .tracks_matching(&self.filter)?
+                .into_iter()
+                .map(LibraryNode::Track)
+                .collect(),
+        };
+        Ok(())
+    }
+
+    /// Enter the group at `selected`. Returns false if it is a track.
+    pub fn descend(&mut self, selected: usize, index: &LibraryIndex) -> Result<bool> {
+        let Some(LibraryNode::Group { field, value }) = self.nodes.get(selected).cloned() else {
+            return Ok(false);
+        };
+        self.filter.push((field, value));
+        self.selection_stack.push(selected);
+        self.reload(index)?;
+        Ok(true)
+    }
+
+    /// Go back up one level, returning the selection to restore.
+    pub fn ascend(&mut self, index: &LibraryIndex) -> Result<Option<usize>> {
+        if self.filter.pop().is_none() {
+            return Ok(None);
+        }
+        self.reload(index)?;
+        Ok(self.selection_stack.pop())
+    }
+
+    /// All track paths below the node at `selected`.
+    pub fn tracks_of(&self, selected: usize, index: &LibraryIndex) -> Result<Vec<PathBuf>> {
+        Ok(match self.nodes.get(selected) {
+            Some(LibraryNode::Group { field, value }) => {
+                let mut filter = self.filter.clone();
+                filter.push((*field, value.clone()));
+                index
+                    .tracks_matching(&filter)?
+                    .into_iter()
+                    .map(|t| t.path)
+                    .collect()
+            }
+            Some(LibraryNode::Track(t)) => vec![t.path.clone()],
+            None => Vec::new(),
+        })
+    }
+
+    /// Title describing the current position, e.g. `Artists / Foo / Bar`.
+    pub fn title(&self) -> String {
+        let mut title = self.mode.to_string();
+        for (field, value) in &self.filter {
+            title.push_str(" / ");
+            title.push_str(&field.display(value.as_deref()));
+        }
+        title
+    }
+}
@@ -0,0 +1,66 @@
+// src/app/list_filter.rs
+//! Incremental `/` search state for the browser list.
+
+use crate::search::fuzzy_match;
+
+/// A row kept by the filter: index into the full list and matched char indices.
+pub type FilterRow = (usize, Vec<usize>);
+
+/// Query typed in the search prompt and the rows it matches.
+#[derive(Debug, Default, Clone)]
+pub struct ListFilter {
+    /// Current query text
+    pub query: String,
+    /// True while the prompt is receiving keystrokes
+    pub editing: bool,
+    /// Matching rows, in list order
+    pub rows: Vec<FilterRow>,
+}
+
+impl ListFilter {
+    /// Start a new, empty search.
+    pub fn new() -> Self {
+        Self {
+            editing: true,
+            ..Default::default()
+        }
+    }
+
+    /// Recompute the matching rows for `labels` (one per list entry).
+    pub fn update(&mut self, labels: &[String]) {
+        self.rows = labels
+            .iter()
+            .enumerate()
+            .filter_map(|(i, label)| fuzzy_match(&self.query, label).map(|m| (i, m.indices)))
+            .collect();
+    }
+
+    /// Position of the list index `selected` among the matching rows.
+    pub fn position_of(&self, selected: usize) -> Option<usize> {
+        self.rows.iter().position(|(i, _)| *i == selected)
+    }
+
+    /// List index of the match after (`direction` = 1) or before (-1) `selected`,
+    /// wrapping around.
+    pub fn step(&self, selected: usize, direction: i32) -> Option<usize> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        let len = self.rows.len() as i32;
+        let pos = match self.position_of(selected) {
+            Some(p) => (p as i32 + direction).rem_euclid(len),
+            // Not on a match: pick the nearest one in the requested direction
+            None if direction > 0 => self
+                .rows
+                .iter()
+                .position(|(i, _)| *i > selected)
+                .unwrap_or(0) as i32,
+            None => self
+                .rows
+                .iter()
+                .rposition(|(i, _)| *i < selected)
+                .unwrap_or(self.rows.len() - 1) as i32,
+        };
+        Some(self.rows[pos as usize].0)
+    }
+}
@@ -1,6 +1,14 @@
 // src/app/mod.rs
 //! Application module - contains application state and logic.
 
+pub mod command;
+pub mod finder;
+pub mod help;
+pub mod library_view;
+pub mod list_filter;
+pub mod playlist_view;
+pub mod queue;
+pub mod session;
 pub mod state;
 
 // Re-export the App struct
@@ -0,0 +1,78 @@
+// src/app/playlist_view.rs
+//! A playlist file opened as a virtual directory in the browser.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::playlist::{load_playlist, mark_missing, PlaylistEntry};
+
+/// Entries of an opened playlist.
+#[derive(Debug)]
+pub struct PlaylistView {
+    /// Playlist file being shown
+    pub path: PathBuf,
+    /// Its tracks, in playlist order
+    pub entries: Vec<PlaylistEntry>,
+    /// Browser selection to restore when leaving the playlist
+    pub return_selection: usize,
+    /// Whether this shows the play queue rather than a playlist file
+    pub is_queue: bool,
+}
+
+impl PlaylistView {
+    /// Load the playlist at `path`.
+    pub fn open(path: &Path, return_selection: usize) -> Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries: load_playlist(path)?,
+            return_selection,
+            is_queue: false,
+        })
+    }
+
+    /// Show `entries` (e.g. the CUE tracks of an audio file) as if read from `path`.
+    pub fn from_entries(
+        path: PathBuf,
+        mut entries: Vec<PlaylistEntry>,
+        return_selection: usize,
+    ) -> Self {
+        mark_missing(&mut entries);
+        Self {
+            path,
+            entries,
+            return_selection,
+            is_queue: false,
+        }
+    }
+
+    /// Show the play queue's tracks.
+    pub fn queue(tracks: &[PathBuf], return_selection: usize) -> Self {
+        let mut entries: Vec<PlaylistEntry> =
+            tracks.iter().cloned().map(PlaylistEntry::new).collect();
+        mark_missing(&mut entries);
+        Self {
+            path: PathBuf::new(),
+            entries,
+            return_selection,
+            is_queue: true,
+        }
+    }
+
+    /// Text shown in the list for `entry`.
+    pub fn label(entry: &PlaylistEntry) -> String {
+        match entry.duration_secs {
+            Some(secs) => format!("{} ({}:{:02})", entry.display_title(), secs / 60, secs % 60),
+            None => entry.display_title(),
+        }
+    }
+
+    /// Paths of the entries whose files exist, in order.
+    pub fn playable_paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .filter(|e| !e.missing)
+            .map(|e| e.path.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,183 @@
+// src/app/queue.rs
+//! Play queue built from library nodes or enqueued files.
+
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::BuildHasher,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// What happens when a track ends.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatMode {
+    /// Stop after the last track
+    #[default]
+    Off,
+    /// Start over after the last track
+    All,
+    /// Play the same track again
+    One,
+}
+
+impl RepeatMode {
+    /// Following mode in the off → all → one cycle.
+    pub fn next(self) -> Self {
+        match self {
+            RepeatMode::Off => RepeatMode::All,
+            RepeatMode::All => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+        }
+    }
+}
+
+impl fmt::Display for RepeatMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RepeatMode::Off => "off",
+            RepeatMode::All => "all",
+            RepeatMode::One => "one",
+        })
+    }
+}
+
+/// Random index below `len`, avoiding `exclude` when there is another choice.
+pub fn random_index(len: usize, exclude: Option<usize>) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let pick = |n: usize| (next_random() % n as u64) as usize;
+    Some(match exclude.filter(|&i| i < len && len > 1) {
+        Some(skip) => (skip + 1 + pick(len - 1)) % len,
+        None => pick(len),
+    })
+}
+
+/// Next number of a xorshift64* generator, seeded once per thread from the
+/// randomly keyed std hasher. Good enough for shuffling, not for secrets.
+fn next_random() -> u64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(SystemTime::now()) | 1);
+    }
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    })
+}
+
+/// Ordered list of tracks to play, with the position of the current one.
+#[derive(Debug, Default, Clone)]
+pub struct PlayQueue {
+    /// Queued track paths
+    pub tracks: Vec<PathBuf>,
+    /// Index of the track currently playing from the queue (if any)
+    pub current: Option<usize>,
+}
+
+impl PlayQueue {
+    /// Replace the queue contents and make `start` the current track.
+    pub fn replace(&mut self, tracks: Vec<PathBuf>, start: usize) -> Option<&Path> {
+        self.tracks = tracks;
+        self.current = None;
+        self.jump(start)
+    }
+
+    /// Append tracks to the end of the queue.
+    pub fn append(&mut self, tracks: impl IntoIterator<Item = PathBuf>) {
+        self.tracks.extend(tracks);
+    }
+
+    /// Remove the track at `index`. Removing the current track stops
+    /// following the queue; otherwise `current` stays on its track.
+    pub fn remove(&mut self, index: usize) -> Option<PathBuf> {
+        if index >= self.tracks.len() {
+            return None;
+        }
+        self.current = match self.current {
+            Some(c) if c == index => None,
+            Some(c) if c > index => Some(c - 1),
+            other => other,
+        };
+        Some(self.tracks.remove(index))
+    }
+
+    /// Move the track at `from` to position `to`, keeping `current` on its track.
+    pub fn move_track(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.tracks.len() || to >= self.tracks.len() {
+            return false;
+        }
+        let track = self.tracks.remove(from);
+        self.tracks.insert(to, track);
+        self.current = self.current.map(|c| match c {
+            c if c == from => to,
+            c if from < c && c <= to => c - 1,
+            c if to <= c && c < from => c + 1,
+            c => c,
+        });
+        true
+    }
+
+    /// Empty the queue.
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+        self.current = None;
+    }
+
+    /// Make `index` the current track and return its path.
+    pub fn jump(&mut self, index: usize) -> Option<&Path> {
+        if index < self.tracks.len() {
+            self.current = Some(index);
+            Some(&self.tracks[index])
+        } else {
+            None
+        }
+    }
+
+    /// True if playback is currently driven by the queue.
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Stop following the queue without clearing it.
+    pub fn detach(&mut self) {
+        self.current = None;
+    }
+
+    /// Jump to a random track other than the current one.
+    pub fn shuffle_step(&mut self) -> Option<&Path> {
+        let next = random_index(self.tracks.len(), self.current)?;
+        self.jump(next)
+    }
+
+    /// Advance by `steps` (negative to go back). Wraps around when `wrap` is
+    /// set, otherwise returns `None` past either end.
+    pub fn step(&mut self, steps: i32, wrap: bool) -> Option<&Path> {
+        let len = self.tracks.len() as i32;
+        if len == 0 {
+            return None;
+        }
+        let next = match self.current {
+            Some(i) => (i as i32).saturating_add(steps),
+            None if steps > 0 => 0,
+            None => len - 1,
+        };
+        let next = if wrap {
+            next.rem_euclid(len)
+        } else if (0..len).contains(&next) {
+            next
+        } else {
+            return None;
+        };
+        self.jump(next as usize)
+    }
+}
@@ -0,0 +1,146 @@
+// src/app/session.rs
+//! Session state saved on quit and restored on the next launch.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::queue::RepeatMode;
+use crate::ui::layout::SectionVisibility;
+
+/// Everything needed to pick up where the last run left off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Last browsed directory
+    pub dir: Option<PathBuf>,
+    /// Name of the selected entry in `dir`
+    pub selected: Option<String>,
+    /// Play queue contents
+    pub queue: Vec<PathBuf>,
+    /// Index of the queue track that was playing, if the queue drove playback
+    pub queue_current: Option<usize>,
+    /// Track that was playing or paused
+    pub track: Option<PathBuf>,
+    /// Position within `track`, in milliseconds
+    pub position_ms: u64,
+    /// Output volume
+    pub volume: f32,
+    /// Repeat mode
+    pub repeat: RepeatMode,
+    /// Whether shuffle was on
+    pub shuffle: bool,
+    /// Panel visibility
+    pub visibility: SectionVisibility,
+    /// Lines run at the command line, oldest first
+    pub command_history: Vec<String>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            selected: None,
+            queue: Vec::new(),
+            queue_current: None,
+            track: None,
+            position_ms: 0,
+            volume: 1.0,
+            repeat: RepeatMode::default(),
+            shuffle: false,
+            visibility: SectionVisibility::default(),
+            command_history: Vec::new(),
+        }
+    }
+}
+
+impl Session {
+    /// Read the session saved at `path`. A missing or unreadable file gives
+    /// `None`, so a damaged session never blocks startup.
+    pub fn load(path: &Path) -> Option<Self> {
+        let text = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// Write the session to `path`, creating its directory if needed. The
+    /// file is written next to `path` and renamed over it, so a crash
+    /// mid-write leaves the previous session intact.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        if let Err(e) = fs::rename(&temp, path) {
+            let _ = fs::remove_file(&temp);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+}
+
+/// Default location of the session file (`$XDG_STATE_HOME/chirpy/session.json`,
+/// or the data directory on platforms without a state directory).
+pub fn default_session_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_dir)
+        .map(|d| d.join("chirpy").join("session.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_sessions_load_back() {
+        let dir = std::env::temp_dir().join(format!("chirpy-session-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("state").join("session.json");
+        let session = Session {
+            dir: Some(PathBuf::from("/music")),
+            selected: Some("album".to_string()),
+            queue: vec![PathBuf::from("/music/a.mp3"), PathBuf::from("/music/b.mp3")],
+            queue_current: Some(1),
+            track: Some(PathBuf::from("/music/b.mp3")),
+            position_ms: 61_500,
+            volume: 0.5,
+            repeat: RepeatMode::default(),
+            shuffle: true,
+            visibility: SectionVisibility {
+                artwork: false,
+                ..SectionVisibility::default()
+            },
+            command_history: vec!["cd /music".to_string()],
+        };
+        session.save(&path).unwrap();
+        // Saving again replaces the file in place
+        session.save(&path).unwrap();
+
+        let loaded = Session::load(&path).unwrap();
+        assert_eq!(loaded.dir, session.dir);
+        assert_eq!(loaded.selected, session.selected);
+        assert_eq!(loaded.queue, session.queue);
+        assert_eq!(loaded.queue_current, Some(1));
+        assert_eq!(loaded.track, session.track);
+        assert_eq!(loaded.position_ms, 61_500);
+        assert_eq!(loaded.volume, 0.5);
+        assert!(loaded.shuffle);
+        assert!(!loaded.visibility.artwork && loaded.visibility.files);
+        assert_eq!(loaded.command_history, session.command_history);
+        let names: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["session.json"]);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(Session::load(&path).is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,38 +2,155 @@
 //! Application state management.
 
 use std::{
-    path::PathBuf,
-    sync::mpsc::{Receiver, Sender},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
     thread,
+    time::{Duration, Instant},
 };
 
 use anyhow::Result;
 use image::DynamicImage;
-use ratatui::{widgets::ListState, Frame};
-use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui::{
+    layout::{Margin, Position, Rect},
+    widgets::ListState,
+    Frame,
+};
+use ratatui_image::{
+    picker::{Picker, ProtocolType},
+    protocol::StatefulProtocol,
+};
 
 use crate::{
-    audio::{MusicPlayer, TrackMetadata, Visualizer},
-    fs::{load_entries, tail_path, FileCategory},
+    audio::{chapters::chapter_at, Chapter, MusicPlayer, TrackMetadata, Visualizer},
+    cli::{tracks_of, Startup},
+    config::{expand_home, Config},
+    fs::{
+        describe_io_error, load_entries, sort_entries, tail_path, watcher::DirWatcher, DirEntry,
+        DirLoad, FileCategory, ListOptions, LoadEvent, SortSpec,
+    },
+    library::{self, spawn_scan, BrowseMode, LibraryIndex, ResumePoint, ScanEvent},
+    playlist::{
+        cue::{embedded_cue, CueSheet},
+        load_playlist, save_playlist, PlaylistEntry, PlaylistFormat,
+    },
     ui::{
-        keybindings::{key_to_action, NavigationAction},
-        layout::{compute_layout, SectionVisibility},
-        widgets::{render_artwork, render_file_list, render_player_panel, render_spectrum},
+        keybindings::{
+            NavigationAction, PromptAction, COMMAND_LINE_KEYS, CONFIRMED_SEARCH_KEYS, FINDER_KEYS,
+            HELP_FILTER_KEYS, HELP_KEYS, SAVE_PROMPT_KEYS,
+        },
+        keymap::{format_keys, prompt_action, KeyChord, KeyInput, KeyMode, KeyResult, MAX_COUNT},
+        layout::{
+            compute_layout, resize, LayoutNode, Pane, SectionVisibility, SplitDirection,
+            RESIZE_STEP,
+        },
+        theme::{bundled_names, Theme},
+        widgets::{
+            render_artwork, render_chapter_list, render_file_list, render_finder, render_help, render_library_list, render_player_panel,
+            file_list::format_duration, render_mini_player, render_playlist, render_spectrum, FileColumns, FileListView, PlayerControl,
+            PlayerInfo,
+        },
     },
 };
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+
+use super::{
+    command::{remember, resolve_path, Amount, Command, CommandLine},
+    finder::Finder,
+    help::HelpView,
+    library_view::{LibraryNode, LibraryView},
+    list_filter::ListFilter,
+    playlist_view::PlaylistView,
+    queue::{random_index, PlayQueue, RepeatMode},
+    session::{default_session_path, Session},
+};
+
+/// How often the position of a long track is saved while it plays.
+const RESUME_SAVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often the session is saved while running, so that it survives
+/// being killed.
+const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How far into a track the offer to resume it stays open.
+const RESUME_OFFER_TIME: Duration = Duration::from_secs(20);
+
+/// Longest gap between the clicks of a double-click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// Rows one notch of the mouse wheel scrolls.
+const WHEEL_ROWS: i32 = 3;
+
+/// Seconds left/right seek in the player panel.
+const SEEK_STEP: usize = 5;
 
 /// Main application state.
 pub struct App {
+    /// User settings
+    pub config: Config,
     /// Current directory being browsed
     pub current_dir: PathBuf,
-    /// Directory entries (name, is_dir, category, mime)
-    pub entries: Vec<(String, bool, FileCategory, String)>,
+    /// Directory entries
+    pub entries: Vec<DirEntry>,
+    /// Why `current_dir` couldn't be listed, if it couldn't
+    pub entries_error: Option<String>,
+    /// Background load of `current_dir`, while one is running
+    dir_load: Option<DirLoad>,
+    /// Entry name to select once the pending listing arrives
+    pending_selection: Option<String>,
     /// List widget state
     pub state: ListState,
     /// Currently selected index
     pub selected: usize,
+    /// Directory listing selection saved while a library mode is shown
+    file_selection: usize,
+    /// Tag-based library browser state
+    pub library_view: LibraryView,
+    /// Play queue
+    pub queue: PlayQueue,
+    /// What happens when a track ends
+    pub repeat: RepeatMode,
+    /// Pick following tracks at random
+    pub shuffle: bool,
+    /// Active `/` search over the browser list, if any
+    pub search: Option<ListFilter>,
+    /// Global fuzzy finder popup, if open
+    pub finder: Option<Finder>,
+    /// Playlist file opened as a virtual directory, if any
+    pub playlist_view: Option<PlaylistView>,
+    /// File name typed at the "save queue as" prompt, while it is open
+    pub save_prompt: Option<String>,
+    /// Selected row of the chapter list popup, while it is open
+    pub chapter_list: Option<usize>,
+    /// The `:` command line, while it is open
+    pub command: Option<CommandLine>,
+    /// Lines run at the command line, oldest first
+    pub command_history: Vec<String>,
+    /// Help overlay, while it is open
+    pub help: Option<HelpView>,
+    /// Count and key sequence typed so far
+    pub key_input: KeyInput,
+    /// Rows visible in the browser list at the last draw
+    list_height: usize,
+    /// Terminal area at the last draw, for mouse hit-testing
+    screen: Rect,
+    /// Where the player panel drew its controls at the last draw
+    player_controls: Vec<(Rect, PlayerControl)>,
+    /// First line of the track details shown in the player panel
+    player_scroll: usize,
+    /// Lines of track details that fit in the player panel at the last draw
+    player_page: usize,
+    /// Time and list index of the last click on a row, to spot double-clicks
+    last_click: Option<(Instant, usize)>,
+    /// Position (seconds) the progress gauge is being dragged to
+    seek_drag: Option<u64>,
+    /// Detail columns shown in the file list
+    pub columns: FileColumns,
+    /// Sort order chosen for each directory (others use the default)
+    pub sort_specs: HashMap<PathBuf, SortSpec>,
+    /// Whether hidden entries and non-audio files are listed
+    pub list_options: ListOptions,
 
     /// Music player instance
     pub player: MusicPlayer,
@@ -43,13 +160,36 @@ pub struct App {
     pub duration: u64,
     /// Index of currently playing track in entries (if any)
     pub current_track_index: Option<usize>,
+    /// Path of the track currently playing (if any)
+    pub now_playing: Option<PathBuf>,
+    /// Shortest track length (seconds) whose playback position is remembered
+    pub resume_min_secs: u64,
+    /// Remembered position offered when the playing track started, until answered
+    pub resume_prompt: Option<Duration>,
+    /// When the playing track's position was last saved
+    resume_saved_at: Instant,
+    /// When the session was last saved
+    session_saved_at: Instant,
+    /// Tracks in a row that couldn't be played, to stop skipping past
+    /// them once every track has failed
+    failed_in_row: usize,
+    /// Location of the session file
+    session_path: Option<PathBuf>,
+    /// Reopen the last track (paused at its position) when restoring a session
+    pub restore_playback: bool,
 
     /// Image picker for artwork rendering
-    #[allow(dead_code)]
     picker: Picker,
     /// Current artwork image
     #[allow(dead_code)]
     pub artwork: Option<DynamicImage>,
+    /// Image file shown in the artwork panel instead of track artwork
+    preview: Option<(String, StatefulProtocol)>,
+    /// Channel of the image file being decoded for preview, if any
+    preview_rx: Option<Receiver<(String, Option<DynamicImage>)>>,
+    /// Channel of the audio file being checked for an embedded CUE sheet
+    /// before it is opened, if any
+    cue_rx: Option<Receiver<(PathBuf, Option<CueSheet>)>>,
 
     /// Metadata channel sender (background loader -> UI)
     pub meta_tx: Sender<TrackMetadata>,
@@ -61,11 +201,38 @@ pub struct App {
 
     /// Section visibility state
     pub visibility: SectionVisibility,
+    /// Panel with the highlighted border
+    pub focus: Pane,
+    /// Show the mini-player instead of the panels
+    pub mini: bool,
+    /// Configured panel layout, restored by `reset_layout`
+    initial_layout: LayoutNode,
+    /// Colors, borders and icons in use
+    pub theme: Theme,
+
+    /// Persistent library index (if the database could be opened)
+    pub library: Option<LibraryIndex>,
+    /// Location of the library database
+    library_path: Option<PathBuf>,
+    /// Music roots scanned into the library
+    pub library_roots: Vec<PathBuf>,
+    /// Progress channel of the running library scan, if any
+    scan_rx: Option<Receiver<ScanEvent>>,
+    /// Short human-readable library status for the UI
+    pub library_status: Option<String>,
+    /// A library change arrived while a scan was running
+    rescan_pending: bool,
+
+    /// Filesystem watcher for the browsed directory and library roots
+    watcher: Option<DirWatcher>,
+    /// One-off message shown in the browser title (e.g. a removed directory)
+    pub notice: Option<String>,
 }
 
 impl App {
-    /// Create a new application instance.
-    pub fn new() -> Result<Self> {
+    /// Create a new application instance from the user's settings, then
+    /// carry out what the command line asked for.
+    pub fn new(config: Config, startup: Startup) -> Result<Self> {
         let cwd = std::env::current_dir()?;
         let mut state = ListState::default();
         state.select(Some(0));
@@ -77,246 +244,2473 @@ impl App {
 
         let (meta_tx, meta_rx) = std::sync::mpsc::channel::<TrackMetadata>();
 
-        Ok(Self {
+        let library_path = library::default_index_path();
+        let library = library_path
+            .as_deref()
+            .and_then(|p| LibraryIndex::open(p).ok());
+
+        let mut app = Self {
             current_dir: cwd.clone(),
-            entries: load_entries(&cwd),
+            entries: Vec::new(),
+            entries_error: None,
+            dir_load: None,
+            pending_selection: None,
             state,
             selected: 0,
+            file_selection: 0,
+            library_view: LibraryView::default(),
+            queue: PlayQueue::default(),
+            repeat: RepeatMode::default(),
+            shuffle: false,
+            search: None,
+            finder: None,
+            playlist_view: None,
+            save_prompt: None,
+            chapter_list: None,
+            command: None,
+            command_history: Vec::new(),
+            help: None,
+            key_input: KeyInput::default(),
+            list_height: 0,
+            screen: Rect::default(),
+            player_controls: Vec::new(),
+            player_scroll: 0,
+            player_page: 0,
+            last_click: None,
+            seek_drag: None,
+            columns: FileColumns::default(),
+            sort_specs: HashMap::new(),
+            list_options: ListOptions::default(),
 
-            player: MusicPlayer::new(),
+            player: MusicPlayer::new(&config),
             elapsed: 0,
             duration: 1,
             current_track_index: None,
+            now_playing: None,
+            resume_min_secs: config.general.resume_min_minutes * 60,
+            resume_prompt: None,
+            resume_saved_at: Instant::now(),
+            session_saved_at: Instant::now(),
+            failed_in_row: 0,
+            session_path: default_session_path(),
+            // Tracks given on the command line replace the saved one
+            restore_playback: config.general.restore_playback && startup.tracks.is_empty(),
 
             picker,
             artwork: None,
+            preview: None,
+            preview_rx: None,
+            cue_rx: None,
             meta_tx,
             meta_rx,
-            visibility: SectionVisibility::default(),
-            visualizer: Visualizer::new(),
-        })
+            visibility: config.layout.visibility(),
+            focus: Pane::Files,
+            mini: false,
+            initial_layout: config.layout.root.clone(),
+            theme: Theme::from_config(&config.theme),
+            visualizer: Visualizer::new(&config),
+
+            library,
+            library_path,
+            library_roots: if config.library.roots.is_empty() {
+                library::default_roots()
+            } else {
+                config.library.roots.clone()
+            },
+            scan_rx: None,
+            library_status: None,
+            rescan_pending: false,
+            watcher: None,
+            notice: None,
+            config,
+        };
+        let session = app.session_path.as_deref().and_then(Session::load);
+        // The command history is kept even when the session isn't restored
+        if let Some(session) = &session {
+            app.command_history = session.command_history.clone();
+        }
+        let mut selection = match session {
+            Some(session) if app.config.general.restore_session => app.restore_session(session),
+            _ => None,
+        };
+        // A configured start directory wins over the session's
+        if let Some(dir) = app.config.general.start_dir.clone().filter(|d| d.is_dir()) {
+            app.current_dir = dir;
+            selection = None;
+        }
+        if let Some(dir) = startup.dir {
+            app.current_dir = dir;
+            selection = None;
+        }
+        app.shuffle |= startup.shuffle;
+        if let Some(repeat) = startup.repeat {
+            app.repeat = repeat;
+        }
+        if let Some(volume) = startup.volume {
+            app.player.set_volume(volume as f32 / 100.0);
+        }
+        if !startup.tracks.is_empty() {
+            let len = startup.tracks.len();
+            let start = if app.shuffle { random_index(len, None).unwrap_or(0) } else { 0 };
+            if let Some(path) = app.queue.replace(startup.tracks, start).map(Path::to_path_buf) {
+                app.start_playback(&path, None);
+            }
+        }
+        app.start_dir_load(selection);
+        app.watcher = DirWatcher::new(&app.library_roots).ok();
+        if let Some(watcher) = &mut app.watcher {
+            watcher.watch_dir(&app.current_dir);
+        }
+        app.rescan_library();
+        Ok(app)
+    }
+
+    /// Apply a saved session: directory, queue, modes and panels, and with
+    /// `restore_playback` the last track, paused at its position. Returns the
+    /// entry name to select once the directory is listed.
+    fn restore_session(&mut self, session: Session) -> Option<String> {
+        if let Some(dir) = session.dir.filter(|d| d.is_dir()) {
+            self.current_dir = dir;
+        }
+        self.queue.tracks = session.queue;
+        self.player.set_volume(session.volume);
+        self.repeat = session.repeat;
+        self.shuffle = session.shuffle;
+        self.visibility = session.visibility;
+        self.keep_focus_visible();
+
+        if self.restore_playback
+            && let Some(track) = session.track.filter(|t| t.is_file())
+        {
+            self.start_playback(&track, None);
+            self.player.pause();
+            let position = Duration::from_millis(session.position_ms);
+            if !position.is_zero() {
+                self.player.seek(position);
+            }
+            self.elapsed = position.as_secs();
+            self.resume_prompt = None;
+            // Keep following the queue if it was driving playback
+            self.queue.current = session
+                .queue_current
+                .filter(|&i| self.queue.tracks.get(i) == Some(&track));
+        }
+        session.selected
+    }
+
+    /// Remember where playback is, save the session and stop playing.
+    /// Called however the event loop ends.
+    pub fn shutdown(&mut self) {
+        self.remember_position();
+        self.save_session();
+        self.player.stop();
+    }
+
+    /// Save the session for the next launch.
+    fn save_session(&mut self) {
+        self.session_saved_at = Instant::now();
+        let Some(path) = &self.session_path else {
+            return;
+        };
+        // The directory selection, wherever it is parked
+        let selected = if self.in_library_mode() {
+            self.file_selection
+        } else if let Some(view) = &self.playlist_view {
+            view.return_selection
+        } else {
+            self.selected
+        };
+        let session = Session {
+            dir: Some(self.current_dir.clone()),
+            selected: self.entries.get(selected).map(|e| e.name.clone()),
+            queue: self.queue.tracks.clone(),
+            queue_current: self.queue.current,
+            track: self.now_playing.clone(),
+            position_ms: self.player.position().as_millis() as u64,
+            volume: self.player.volume(),
+            repeat: self.repeat,
+            shuffle: self.shuffle,
+            visibility: self.visibility,
+            command_history: self.command_history.clone(),
+        };
+        let _ = session.save(path);
     }
 
     /// Handle a key event and return true if the app should quit.
     pub fn on_key(&mut self, key: KeyEvent) -> bool {
-        let action = key_to_action(&key);
+        self.notice = None;
+        if self.finder.is_some() {
+            self.on_finder_key(key);
+            self.sync_list_state();
+            return false;
+        }
 
-        match action {
-            NavigationAction::ToggleSection(d) => {
-                self.visibility.toggle(d);
+        if self.help.is_some() {
+            self.on_help_key(key);
+            return false;
+        }
+
+        if self.save_prompt.is_some() {
+            self.on_save_prompt_key(key);
+            return false;
+        }
+
+        if self.command.is_some() {
+            let quit = self.on_command_key(key);
+            self.sync_list_state();
+            return quit;
+        }
+
+        if self.chapter_list.is_some() {
+            self.on_chapter_list_key(key);
+            return false;
+        }
+
+        if self.search.as_ref().is_some_and(|s| s.editing) {
+            let quit = self.on_search_key(key);
+            self.sync_list_state();
+            return quit;
+        }
+
+        // A confirmed search keeps filtering: n/N cycle matches, Esc clears
+        // it. The mini-player hides the list, so only Esc applies there.
+        if let Some(search) = &self.search {
+            let jump = match prompt_action(CONFIRMED_SEARCH_KEYS, &key) {
+                Some(PromptAction::Cancel) => {
+                    self.search = None;
+                    self.sync_list_state();
+                    return false;
+                }
+                _ if self.is_mini() => None,
+                Some(PromptAction::Next) => search.step(self.selected, 1),
+                Some(PromptAction::Previous) => search.step(self.selected, -1),
+                _ => None,
+            };
+            if let Some(i) = jump {
+                self.selected = i;
+                self.sync_list_state();
+                return false;
             }
-            NavigationAction::Down => {
-                if self.selected + 1 < self.entries.len() {
-                    self.selected += 1;
+        }
+
+        let mode = if self.in_queue_view() {
+            KeyMode::Queue
+        } else {
+            KeyMode::Browser
+        };
+        // An open resume offer takes its keys; other keys work as usual
+        let modes: &[KeyMode] = if self.resume_prompt.is_some() {
+            &[KeyMode::Resume, mode]
+        } else {
+            &[mode]
+        };
+        match self.config.keys.feed_layered(modes, &mut self.key_input, &key) {
+            KeyResult::Action(action @ (NavigationAction::Confirm | NavigationAction::Cancel), _)
+                if let Some(position) = self.resume_prompt =>
+            {
+                self.resume_prompt = None;
+                if action == NavigationAction::Confirm {
+                    self.player.seek(position);
+                    self.elapsed = position.as_secs();
                 }
+                false
+            }
+            KeyResult::Action(action, count) => self.perform(action, count),
+            KeyResult::Pending | KeyResult::Unbound => false,
+        }
+    }
+
+    /// Carry out a bound action; `count` (e.g. the 5 of `5j`) repeats it or
+    /// picks the row for `top`/`bottom`. The focused panel gets the first go
+    /// at it, or the player while the mini-player hides the panels. Returns
+    /// true if the app should quit.
+    fn perform(&mut self, action: NavigationAction, count: Option<usize>) -> bool {
+        let count = count
+            .filter(|_| action.takes_count())
+            .map(|n| n.min(MAX_COUNT));
+        let focus = if self.is_mini() {
+            Pane::Player
+        } else {
+            self.focus
+        };
+        let handled = match focus {
+            Pane::Files => self.perform_in_files(action, count),
+            Pane::Player => self.perform_in_player(action, count),
+            Pane::Artwork | Pane::Visualizer => false,
+        };
+        let quit = !handled && self.perform_global(action, count);
+        self.sync_list_state();
+        quit
+    }
+
+    /// Carry out `action` in the file list (or the playlist, queue or
+    /// library view shown there). Returns false if the list has no such action.
+    fn perform_in_files(&mut self, action: NavigationAction, count: Option<usize>) -> bool {
+        if self.in_queue_view() && self.perform_in_queue(action, count) {
+            return true;
+        }
+        let times = count.unwrap_or(1).max(1);
+        let page = self
+            .page_size()
+            .saturating_mul(times)
+            .min(i32::MAX as usize) as i32;
+        match action {
+            NavigationAction::Down => {
+                self.move_selection(times as i32);
             }
             NavigationAction::Up => {
-                if self.selected > 0 {
-                    self.selected -= 1;
+                self.move_selection(-(times as i32));
+            }
+            NavigationAction::PageDown => {
+                self.move_selection(page);
+            }
+            NavigationAction::PageUp => {
+                self.move_selection(-page);
+            }
+            NavigationAction::Top => {
+                self.select_row(count.map_or(0, |n| n.saturating_sub(1)));
+            }
+            NavigationAction::Bottom => {
+                self.select_row(count.map_or(usize::MAX, |n| n.saturating_sub(1)));
+            }
+            NavigationAction::Search => {
+                self.search = Some(ListFilter::new());
+                self.update_search();
+            }
+            NavigationAction::Enter => {
+                if self.in_library_mode() {
+                    self.play_library_node();
+                } else if self.playlist_view.is_some() {
+                    self.play_playlist_entry();
+                } else {
+                    self.open_entry();
+                }
+            }
+            NavigationAction::Open => {
+                if self.in_library_mode() {
+                    self.descend_library_node();
+                } else if self.playlist_view.is_some() {
+                    self.play_playlist_entry();
+                } else {
+                    self.open_entry();
+                }
+            }
+            NavigationAction::Back => {
+                if self.in_library_mode() {
+                    self.ascend_library();
+                } else if self.playlist_view.is_some() {
+                    self.close_playlist();
+                } else if let Some(parent) = self.current_dir.parent() {
+                    self.change_dir(parent.to_path_buf());
+                }
+            }
+            NavigationAction::Enqueue => {
+                self.enqueue_selected();
+                self.refresh_queue_view();
+            }
+            NavigationAction::CycleBrowseMode => {
+                self.cycle_browse_mode();
+            }
+            NavigationAction::CycleColumns => {
+                self.columns = self.columns.cycle();
+            }
+            NavigationAction::ToggleColumn(n) => {
+                self.columns = self.columns.toggle(n);
+            }
+            NavigationAction::ToggleHidden => {
+                self.list_options.show_hidden = !self.list_options.show_hidden;
+                self.refresh_entries();
+            }
+            NavigationAction::ToggleAllFiles => {
+                self.list_options.show_all = !self.list_options.show_all;
+                self.refresh_entries();
+            }
+            NavigationAction::PreviewImage => {
+                self.preview_selected_image();
+            }
+            NavigationAction::CycleSort => {
+                self.change_sort(|spec| spec.key = spec.key.next());
+            }
+            NavigationAction::ReverseSort => {
+                self.change_sort(|spec| spec.descending = !spec.descending);
+            }
+            NavigationAction::ToggleDirsFirst => {
+                self.change_sort(|spec| spec.dirs_first = !spec.dirs_first);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Carry out `action` in the queue view: remove and reorder tracks.
+    /// Returns false if the queue has no such action.
+    fn perform_in_queue(&mut self, action: NavigationAction, count: Option<usize>) -> bool {
+        let times = count.unwrap_or(1).max(1);
+        match action {
+            NavigationAction::RemoveFromQueue => {
+                for _ in 0..times {
+                    if self.queue.remove(self.selected).is_none() {
+                        break;
+                    }
+                }
+            }
+            NavigationAction::MoveUpInQueue => {
+                let to = self.selected.saturating_sub(times);
+                if self.queue.move_track(self.selected, to) {
+                    self.selected = to;
+                }
+            }
+            NavigationAction::MoveDownInQueue => {
+                let to = self
+                    .selected
+                    .saturating_add(times)
+                    .min(self.queue.tracks.len().saturating_sub(1));
+                if self.queue.move_track(self.selected, to) {
+                    self.selected = to;
                 }
             }
+            NavigationAction::ClearQueue => {
+                self.queue.clear();
+            }
+            _ => return false,
+        }
+        self.refresh_queue_view();
+        true
+    }
+
+    /// Carry out `action` in the player panel: scroll the track details,
+    /// seek and pause. Returns false if the player has no such action.
+    fn perform_in_player(&mut self, action: NavigationAction, count: Option<usize>) -> bool {
+        let times = count.unwrap_or(1).max(1);
+        let page = self.player_page.max(1).saturating_mul(times);
+        let seek = i64::try_from(SEEK_STEP.saturating_mul(times)).unwrap_or(i64::MAX);
+        match action {
+            NavigationAction::Down => {
+                self.player_scroll = self.player_scroll.saturating_add(times);
+            }
+            NavigationAction::Up => {
+                self.player_scroll = self.player_scroll.saturating_sub(times);
+            }
+            NavigationAction::PageDown => {
+                self.player_scroll = self.player_scroll.saturating_add(page);
+            }
+            NavigationAction::PageUp => {
+                self.player_scroll = self.player_scroll.saturating_sub(page);
+            }
+            NavigationAction::Top => {
+                self.player_scroll = 0;
+            }
+            NavigationAction::Bottom => {
+                self.player_scroll = usize::MAX;
+            }
+            NavigationAction::Open => {
+                self.seek(Amount::By(seek));
+            }
+            NavigationAction::Back => {
+                self.seek(Amount::By(-seek));
+            }
             NavigationAction::Enter => {
-                if !self.entries.is_empty() {
-                    let (name, is_dir, category, _) = &self.entries[self.selected];
-                    let path = self.current_dir.join(name);
-
-                    if *is_dir {
-                        self.current_dir.push(name);
-                        self.entries = load_entries(&self.current_dir);
-                        self.selected = 0;
-                    } else if *category == FileCategory::Audio {
-                        if self.player.play(&path).is_ok() {
-                            // Clear any prior metadata while background loader runs
-                            self.player.metadata = None;
-                            self.elapsed = 0;
-                            self.duration = 1;
-                            self.artwork = None;
-                            self.current_track_index = Some(self.selected);
-
-                            // Spawn a background thread to load metadata
-                            let tx = self.meta_tx.clone();
-                            let path_clone = path.clone();
-                            thread::spawn(move || {
-                                if let Ok(meta) = MusicPlayer::load_metadata(path_clone) {
-                                    let _ = tx.send(meta);
-                                }
-                            });
-                        }
+                self.perform_global(NavigationAction::TogglePause, None);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Carry out an action that doesn't depend on the focused panel.
+    /// Returns true if the app should quit.
+    fn perform_global(&mut self, action: NavigationAction, count: Option<usize>) -> bool {
+        let times = count.unwrap_or(1).max(1);
+        // Sizes are at most 100%, so more steps than that change nothing
+        let resize = RESIZE_STEP * times.min(100) as i32;
+        match action {
+            NavigationAction::ToggleSection(d) => {
+                self.visibility.toggle(d);
+                self.keep_focus_visible();
+            }
+            NavigationAction::GrowWidth => {
+                self.resize_focus(SplitDirection::Columns, resize);
+            }
+            NavigationAction::ShrinkWidth => {
+                self.resize_focus(SplitDirection::Columns, -resize);
+            }
+            NavigationAction::GrowHeight => {
+                self.resize_focus(SplitDirection::Rows, resize);
+            }
+            NavigationAction::ShrinkHeight => {
+                self.resize_focus(SplitDirection::Rows, -resize);
+            }
+            NavigationAction::ResetLayout => {
+                self.config.layout.root = self.initial_layout.clone();
+            }
+            NavigationAction::ToggleMiniPlayer => {
+                if self.screen.height < self.config.layout.mini_below {
+                    self.notice = Some("the terminal is too short for the panels".to_string());
+                } else {
+                    self.mini = !self.mini;
+                }
+            }
+            NavigationAction::FocusNext => {
+                self.cycle_focus(times as isize);
+            }
+            NavigationAction::FocusPrevious => {
+                self.cycle_focus(-(times as isize));
+            }
+            NavigationAction::FocusPane(n) => {
+                if let Some(pane) = Pane::from_number(n) {
+                    if self.visibility.shows(pane) {
+                        self.focus = pane;
+                    } else {
+                        self.notice = Some(format!("the {} panel is hidden", pane));
                     }
                 }
             }
+            NavigationAction::Find => {
+                let roots = if self.library_roots.is_empty() {
+                    vec![self.current_dir.clone()]
+                } else {
+                    self.library_roots.clone()
+                };
+                self.finder = Some(Finder::new(self.library_path.clone(), roots));
+            }
+            NavigationAction::Help => {
+                self.help = Some(HelpView::new(&self.config.keys));
+            }
+            NavigationAction::CommandLine => {
+                self.command = Some(CommandLine::default());
+            }
+            NavigationAction::SaveQueue => {
+                self.save_prompt = Some(String::new());
+            }
+            NavigationAction::ShowQueue => {
+                self.toggle_queue_view();
+            }
             NavigationAction::TogglePause => {
                 if self.player.is_paused() {
                     self.player.resume();
                 } else {
+                    self.remember_position();
                     self.player.pause();
                 }
             }
             NavigationAction::Stop => {
+                self.remember_position();
                 self.player.stop();
                 self.elapsed = 0;
                 self.current_track_index = None;
+                self.now_playing = None;
+                self.queue.detach();
             }
             NavigationAction::NextTrack => {
-                self.play_adjacent_track(1);
+                self.skip_track(times as i32);
             }
             NavigationAction::PreviousTrack => {
-                self.play_adjacent_track(-1);
+                self.skip_track(-(times as i32));
             }
-            NavigationAction::Back => {
-                if self.current_dir.pop() {
-                    self.entries = load_entries(&self.current_dir);
-                    self.selected = 0;
+            NavigationAction::NextChapter => {
+                self.skip_chapter(times as i32);
+            }
+            NavigationAction::PreviousChapter => {
+                self.skip_chapter(-(times as i32));
+            }
+            NavigationAction::ChapterList => {
+                if self.chapters().is_empty() {
+                    self.notice = Some("no chapters".to_string());
+                } else {
+                    self.chapter_list = Some(self.current_chapter().unwrap_or(0));
                 }
             }
+            NavigationAction::RescanLibrary => {
+                self.rescan_library();
+            }
+            NavigationAction::VolumeUp => {
+                let step = (self.config.audio.volume_step as usize * times) as f32 / 100.0;
+                self.player.set_volume(self.player.volume() + step);
+            }
+            NavigationAction::VolumeDown => {
+                let step = (self.config.audio.volume_step as usize * times) as f32 / 100.0;
+                self.player.set_volume(self.player.volume() - step);
+            }
+            NavigationAction::CycleRepeat => {
+                self.repeat = self.repeat.next();
+            }
+            NavigationAction::ToggleShuffle => {
+                self.shuffle = !self.shuffle;
+            }
+            NavigationAction::CycleTheme => {
+                self.theme = self.theme.next(&self.config.theme);
+                self.notice = Some(format!("theme: {}", self.theme.name));
+            }
             NavigationAction::Quit => {
-                self.player.stop();
                 return true; // Signal to quit
             }
-            NavigationAction::None => {}
+            // Queue editing outside the queue view
+            NavigationAction::RemoveFromQueue
+            | NavigationAction::MoveUpInQueue
+            | NavigationAction::MoveDownInQueue
+            | NavigationAction::ClearQueue
+                if self.focus == Pane::Files && !self.is_mini() =>
+            {
+                self.notice = Some(format!("{} works in the queue view", action.name()));
+            }
+            // List actions while another panel has the focus
+            NavigationAction::Up
+            | NavigationAction::Down
+            | NavigationAction::PageUp
+            | NavigationAction::PageDown
+            | NavigationAction::Top
+            | NavigationAction::Bottom
+            | NavigationAction::Enter
+            | NavigationAction::Open
+            | NavigationAction::Back
+            | NavigationAction::Enqueue
+            | NavigationAction::RemoveFromQueue
+            | NavigationAction::MoveUpInQueue
+            | NavigationAction::MoveDownInQueue
+            | NavigationAction::ClearQueue
+            | NavigationAction::CycleBrowseMode
+            | NavigationAction::Search
+            | NavigationAction::CycleColumns
+            | NavigationAction::ToggleColumn(_)
+            | NavigationAction::ToggleHidden
+            | NavigationAction::ToggleAllFiles
+            | NavigationAction::PreviewImage
+            | NavigationAction::CycleSort
+            | NavigationAction::ReverseSort
+            | NavigationAction::ToggleDirsFirst => {
+                let place = if self.is_mini() {
+                    "the mini-player".to_string()
+                } else {
+                    format!("the {} panel", self.focus)
+                };
+                self.notice = Some(format!("{} isn't available in {}", action.name(), place));
+            }
+            // Prompt editing, handled by the prompts themselves
+            NavigationAction::Confirm
+            | NavigationAction::Cancel
+            | NavigationAction::DeleteChar
+            | NavigationAction::None => {}
         }
-
-        self.state.select(Some(self.selected));
         false
     }
 
-    /// Draw the application UI.
-    pub fn draw(&mut self, f: &mut Frame<'_>) {
-        let area = f.area();
-        let layout = compute_layout(area, &self.visibility);
+    /// Handle a mouse event: select or play rows of the browser list,
+    /// click the player's controls, seek on the progress gauge, and
+    /// scroll lists, popups or the volume with the wheel.
+    pub fn on_mouse(&mut self, mouse: MouseEvent) {
+        let wheel = match mouse.kind {
+            MouseEventKind::ScrollDown => 1,
+            MouseEventKind::ScrollUp => -1,
+            _ => 0,
+        };
 
-        // Render visible columns in order
-        let mut col_index = 0usize;
+        // Popups only scroll; clicks don't reach what's under them
+        if let Some(help) = &mut self.help {
+            help.scroll_by((wheel * WHEEL_ROWS) as isize);
+            return;
+        }
+        if let Some(finder) = &mut self.finder {
+            if wheel != 0 {
+                finder.move_selection(wheel);
+            }
+            return;
+        }
+        if let Some(selected) = self.chapter_list {
+            let last = self.chapters().len().saturating_sub(1) as i32;
+            self.chapter_list = Some((selected as i32 + wheel).clamp(0, last) as usize);
+            return;
+        }
+        if self.save_prompt.is_some() || self.command.is_some() {
+            return;
+        }
 
-        for section in layout.section_order.iter() {
-            match *section {
-                "files" => {
-                    if col_index < layout.columns.len() {
-                        let title = format!("1:  {}", tail_path(&self.current_dir, 3));
-                        render_file_list(
-                            f,
-                            layout.columns[col_index],
-                            &title,
-                            &self.entries,
-                            &mut self.state,
-                        );
-                    }
-                    col_index += 1;
-                }
-                "player" => {
-                    if col_index < layout.columns.len() {
-                        render_player_panel(
-                            f,
-                            layout.columns[col_index],
-                            self.player.metadata.as_ref(),
-                            self.elapsed,
-                            self.duration,
-                            self.player.is_playing(),
-                            self.player.is_paused(),
-                        );
+        // A drag on the gauge only seeks once the button is released
+        if let Some(position) = self.seek_drag {
+            match mouse.kind {
+                MouseEventKind::Drag(MouseButton::Left) => {
+                    if let Some(position) = self.gauge_position(mouse.column) {
+                        self.seek_drag = Some(position);
+                        self.elapsed = position;
                     }
-                    col_index += 1;
                 }
-                "artwork" => {
-                    if col_index < layout.columns.len() {
-                        render_artwork(f, layout.columns[col_index]);
-                    }
-                    col_index += 1;
+                MouseEventKind::Up(_) => {
+                    self.seek_drag = None;
+                    self.player.seek(Duration::from_secs(position));
+                    self.elapsed = position;
                 }
                 _ => {}
             }
+            return;
+        }
+
+        let at = Position::new(mouse.column, mouse.row);
+        // The mini-player takes the whole screen and leaves the focus alone
+        let mini = self.is_mini();
+        let layout = compute_layout(self.screen, &self.visibility, &self.config.layout);
+        let target = if mini {
+            Some((Pane::Player, self.screen))
+        } else {
+            layout.pane_at(at)
+        };
+        let Some((pane, area)) = target else {
+            return;
+        };
+        if !mini && let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+            self.focus = pane;
+        }
+        match (pane, mouse.kind) {
+            (Pane::Files, MouseEventKind::Down(MouseButton::Left)) => self.click_row(area, at),
+            (Pane::Files, _) if wheel != 0 => self.move_selection(wheel * WHEEL_ROWS),
+            (Pane::Player, MouseEventKind::Down(MouseButton::Left)) => {
+                let control = self
+                    .player_controls
+                    .iter()
+                    .find(|(rect, _)| rect.contains(at))
+                    .map(|(_, control)| *control);
+                let action = match control {
+                    Some(PlayerControl::Previous) => NavigationAction::PreviousTrack,
+                    Some(PlayerControl::Stop) => NavigationAction::Stop,
+                    Some(PlayerControl::PlayPause) => NavigationAction::TogglePause,
+                    Some(PlayerControl::Next) => NavigationAction::NextTrack,
+                    Some(PlayerControl::Repeat) => NavigationAction::CycleRepeat,
+                    Some(PlayerControl::Shuffle) => NavigationAction::ToggleShuffle,
+                    Some(PlayerControl::Progress) => {
+                        if self.now_playing.is_some()
+                            && let Some(position) = self.gauge_position(at.x)
+                        {
+                            self.seek_drag = Some(position);
+                            self.elapsed = position;
+                        }
+                        NavigationAction::None
+                    }
+                    None => NavigationAction::None,
+                };
+                self.perform(action, None);
+            }
+            (Pane::Player, _) if wheel > 0 => {
+                self.perform(NavigationAction::VolumeDown, None);
+            }
+            (Pane::Player, _) if wheel < 0 => {
+                self.perform(NavigationAction::VolumeUp, None);
+            }
+            _ => {}
         }
+        self.sync_list_state();
+    }
 
-        // Bottom pane: audio spectrum visualizer
-        if let Some(visualizer_area) = layout.visualizer_area {
-            render_spectrum(f, visualizer_area, &self.visualizer);
+    /// Move the focus `steps` shown panels on, in layout order.
+    fn cycle_focus(&mut self, steps: isize) {
+        let layout = compute_layout(self.screen, &self.visibility, &self.config.layout);
+        let panes: Vec<Pane> = layout.panes.iter().map(|(pane, _)| *pane).collect();
+        if panes.is_empty() {
+            return;
         }
+        let current = panes.iter().position(|&p| p == self.focus).unwrap_or(0);
+        let next = (current as isize + steps).rem_euclid(panes.len() as isize);
+        self.focus = panes[next as usize];
     }
 
-    /// Update the visualizer with new audio samples.
-    pub fn update_visualizer(&mut self) {
-        self.visualizer.update(&self.player.sample_buffer);
+    /// Move the focus to the first shown panel if its panel was hidden.
+    fn keep_focus_visible(&mut self) {
+        if !self.visibility.shows(self.focus) {
+            let layout = compute_layout(self.screen, &self.visibility, &self.config.layout);
+            if let Some((pane, _)) = layout.panes.first() {
+                self.focus = *pane;
+            }
+        }
     }
 
-    /// Process any pending metadata from background loader.
-    pub fn process_metadata(&mut self) {
-        if let Ok(meta) = self.meta_rx.try_recv() {
-            self.player.metadata = Some(meta);
-            self.duration = self
-                .player
-                .metadata
-                .as_ref()
-                .map(|m| m.duration_secs.max(1))
-                .unwrap_or(1);
+    /// Grow (positive `step`) or shrink the focused panel by `step` percent
+    /// of its split in `direction`.
+    fn resize_focus(&mut self, direction: SplitDirection, step: i32) {
+        let layout = compute_layout(self.screen, &self.visibility, &self.config.layout);
+        if layout.stacked {
+            self.notice = Some("panels are stacked, widen the terminal to resize them".into());
+        } else if !resize(
+            &mut self.config.layout.root,
+            self.screen,
+            &self.visibility,
+            self.focus,
+            direction,
+            step,
+        ) {
+            self.notice = Some(format!("the {} panel can't be resized that way", self.focus));
         }
     }
 
-    /// Update elapsed time if playing.
-    pub fn tick_elapsed(&mut self) {
-        if self.player.is_playing() && !self.player.is_paused() {
-            self.elapsed = (self.elapsed + 1).min(self.duration);
+    /// Select the browser row under `at`, or act on it like Enter when it
+    /// was just clicked.
+    fn click_row(&mut self, area: Rect, at: Position) {
+        let list = area.inner(Margin::new(1, 1));
+        if !list.contains(at) {
+            return;
+        }
+        let row = self.state.offset() + (at.y - list.y) as usize;
+        let index = match &self.search {
+            Some(search) => search.rows.get(row).map(|(i, _)| *i),
+            None => (row < self.list_len()).then_some(row),
+        };
+        let Some(index) = index else {
+            return;
+        };
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|(time, i)| i == index && now - time <= DOUBLE_CLICK_TIME);
+        self.selected = index;
+        self.sync_list_state();
+        if double {
+            self.last_click = None;
+            self.perform(NavigationAction::Enter, None);
+        } else {
+            self.last_click = Some((now, index));
         }
     }
 
-    /// Play the next or previous audio track relative to current position.
-    /// `direction`: 1 for next, -1 for previous.
-    fn play_adjacent_track(&mut self, direction: i32) {
-        // Get audio file indices
-        let audio_indices: Vec<usize> = self
-            .entries
-            .iter()
-            .enumerate()
-            .filter(|(_, (_, is_dir, cat, _))| !is_dir && *cat == FileCategory::Audio)
-            .map(|(i, _)| i)
-            .collect();
+    /// Seek the track playing to `amount` seconds into the file.
+    fn seek(&mut self, amount: Amount) {
+        if self.now_playing.is_some() {
+            let position = amount.apply(self.elapsed).min(self.duration);
+            self.player.seek(Duration::from_secs(position));
+            self.elapsed = position;
+        } else {
+            self.notice = Some("nothing playing".to_string());
+        }
+    }
 
-        if audio_indices.is_empty() {
-            return;
+    /// Position (seconds into the file) of column `x` on the progress gauge.
+    fn gauge_position(&self, x: u16) -> Option<u64> {
+        let (gauge, _) = self
+            .player_controls
+            .iter()
+            .find(|(_, control)| *control == PlayerControl::Progress)?;
+        if gauge.width == 0 {
+            return None;
         }
+        let ratio = (x.saturating_sub(gauge.x) as f64 / gauge.width as f64).clamp(0.0, 1.0);
+        let (start, length) = self.progress_span();
+        Some(start + (length as f64 * ratio) as u64)
+    }
 
-        // Find current position in audio files
-        let current_audio_pos = self
-            .current_track_index
-            .and_then(|idx| audio_indices.iter().position(|&i| i == idx));
+    /// Start and length (seconds) of what the progress gauge shows: the cue
+    /// track playing, or the whole file.
+    fn progress_span(&self) -> (u64, u64) {
+        match self.current_cue() {
+            Some((i, sheet)) => {
+                let start = sheet.tracks[i].start.as_secs();
+                let end = sheet.end_of(i).map_or(self.duration, |e| e.as_secs());
+                (start, end.saturating_sub(start).max(1))
+            }
+            None => (0, self.duration),
+        }
+    }
 
-        let next_audio_pos = match current_audio_pos {
-            Some(pos) => {
-                let new_pos = pos as i32 + direction;
-                if new_pos < 0 {
-                    audio_indices.len() - 1 // Wrap to last
-                } else if new_pos >= audio_indices.len() as i32 {
-                    0 // Wrap to first
-                } else {
-                    new_pos as usize
+    /// Handle a key while the finder popup is open.
+    fn on_finder_key(&mut self, key: KeyEvent) {
+        let Some(finder) = &mut self.finder else {
+            return;
+        };
+        match prompt_action(FINDER_KEYS, &key) {
+            Some(PromptAction::Cancel) => self.finder = None,
+            Some(PromptAction::Confirm) => {
+                if let Some(path) = finder.selected_item().map(|i| i.path.clone()) {
+                    self.finder = None;
+                    self.queue.detach();
+                    self.start_playback(&path, None);
+                }
+            }
+            Some(PromptAction::Enqueue) => {
+                if let Some(item) = finder.selected_item() {
+                    self.queue.append([item.path.clone()]);
+                }
+            }
+            Some(PromptAction::Reveal) => {
+                if let Some(path) = finder.selected_item().map(|i| i.path.clone()) {
+                    self.finder = None;
+                    self.reveal_in_browser(&path);
                 }
             }
+            Some(PromptAction::Next) => finder.move_selection(1),
+            Some(PromptAction::Previous) => finder.move_selection(-1),
+            Some(PromptAction::DeleteChar) => {
+                let mut query = finder.query.clone();
+                query.pop();
+                finder.set_query(query);
+            }
+            Some(_) => {}
             None => {
-                // No track playing, start from first or last based on direction
-                if direction > 0 { 0 } else { audio_indices.len() - 1 }
+                if let Some(c) = KeyChord::from_event(&key).char() {
+                    let query = format!("{}{}", finder.query, c);
+                    finder.set_query(query);
+                }
             }
-        };
+        }
+    }
 
-        let entry_idx = audio_indices[next_audio_pos];
-        let (name, _, _, _) = &self.entries[entry_idx];
-        let path = self.current_dir.join(name);
-
-        if self.player.play(&path).is_ok() {
-            self.player.metadata = None;
-            self.elapsed = 0;
-            self.duration = 1;
-            self.artwork = None;
-            self.current_track_index = Some(entry_idx);
-            self.selected = entry_idx;
-            self.state.select(Some(entry_idx));
+    /// Show `path` in the directory browser, selecting its entry.
+    fn reveal_in_browser(&mut self, path: &Path) {
+        let Some(dir) = path.parent() else {
+            return;
+        };
+        if self.in_library_mode() {
+            self.library_view.mode = BrowseMode::Files;
+        }
+        self.change_dir(dir.to_path_buf());
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+        self.selected = self
+            .entries
+            .iter()
+            .position(|e| Some(&e.name) == name.as_ref())
+            .unwrap_or(0);
+    }
 
-            // Spawn background metadata loader
-            let tx = self.meta_tx.clone();
-            let path_clone = path.clone();
-            thread::spawn(move || {
-                if let Ok(meta) = MusicPlayer::load_metadata(path_clone) {
-                    let _ = tx.send(meta);
+    /// Handle a key while the search prompt is being edited; unbound
+    /// characters are typed into the query. Returns true if the app should quit.
+    fn on_search_key(&mut self, key: KeyEvent) -> bool {
+        let result = self.config.keys.feed(KeyMode::Search, &mut self.key_input, &key);
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+        // Keys of a sequence that broke off were meant as text
+        let abandoned: String = self
+            .key_input
+            .abandoned()
+            .iter()
+            .filter_map(KeyChord::char)
+            .collect();
+        if !abandoned.is_empty() {
+            search.query.push_str(&abandoned);
+            self.update_search();
+        }
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+        match result {
+            KeyResult::Unbound => {
+                if let Some(c) = KeyChord::from_event(&key).char() {
+                    search.query.push(c);
+                    self.update_search();
+                }
+            }
+            KeyResult::Action(NavigationAction::DeleteChar, _) => {
+                search.query.pop();
+                self.update_search();
+            }
+            KeyResult::Action(NavigationAction::Confirm, _) => {
+                // Keep the filter, but hand keys back to normal navigation
+                if search.rows.is_empty() || search.query.is_empty() {
+                    self.search = None;
+                } else {
+                    search.editing = false;
                 }
+            }
+            KeyResult::Action(NavigationAction::Cancel, _) => {
+                self.search = None;
+            }
+            KeyResult::Action(NavigationAction::Down, _) => self.move_selection(1),
+            KeyResult::Action(NavigationAction::Up, _) => self.move_selection(-1),
+            KeyResult::Action(action, count) => return self.perform(action, count),
+            KeyResult::Pending => {}
+        }
+        false
+    }
+
+    /// Re-run the search query and keep the selection on a match.
+    fn update_search(&mut self) {
+        let labels = self.list_labels();
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.update(&labels);
+        if search.position_of(self.selected).is_none()
+            && let Some((first, _)) = search.rows.first()
+        {
+            self.selected = *first;
+        }
+    }
+
+    /// Move the selection by `delta` rows, staying on matches while filtering.
+    fn move_selection(&mut self, delta: i32) {
+        if let Some(search) = &self.search {
+            let Some(pos) = search.position_of(self.selected) else {
+                return;
+            };
+            let new_pos = (pos as i32).saturating_add(delta).clamp(0, search.rows.len() as i32 - 1);
+            self.selected = search.rows[new_pos as usize].0;
+        } else {
+            let max = self.list_len().saturating_sub(1) as i32;
+            self.selected = (self.selected as i32).saturating_add(delta).clamp(0, max) as usize;
+        }
+    }
+
+    /// Select the `row`th row of the list (among the matches while
+    /// filtering), or the last one if there are fewer rows.
+    fn select_row(&mut self, row: usize) {
+        if let Some(search) = &self.search {
+            if let Some(last) = search.rows.len().checked_sub(1) {
+                self.selected = search.rows[row.min(last)].0;
+            }
+        } else {
+            self.selected = row.min(self.list_len().saturating_sub(1));
+        }
+    }
+
+    /// Rows moved by one page up or down.
+    fn page_size(&self) -> usize {
+        self.list_height.saturating_sub(1).max(1)
+    }
+
+    /// Point the list widget at the selection (its row among the matches when filtering).
+    fn sync_list_state(&mut self) {
+        let row = match &self.search {
+            Some(search) => search.position_of(self.selected),
+            None => Some(self.selected),
+        };
+        self.state.select(row);
+    }
+
+    /// Text of each row in the active browser list, as matched by search.
+    fn list_labels(&self) -> Vec<String> {
+        if self.in_library_mode() {
+            self.library_view.nodes.iter().map(LibraryNode::label).collect()
+        } else if let Some(view) = &self.playlist_view {
+            view.entries.iter().map(PlaylistView::label).collect()
+        } else {
+            self.entries.iter().map(|e| e.name.clone()).collect()
+        }
+    }
+
+    /// True if the browser shows a library hierarchy instead of directories.
+    fn in_library_mode(&self) -> bool {
+        self.library_view.mode != BrowseMode::Files
+    }
+
+    /// True if the browser shows the play queue.
+    fn in_queue_view(&self) -> bool {
+        self.playlist_view.as_ref().is_some_and(|v| v.is_queue)
+    }
+
+    /// True if the browser lists the entries of `current_dir`.
+    fn in_directory(&self) -> bool {
+        !self.in_library_mode() && self.playlist_view.is_none()
+    }
+
+    /// Number of rows in the active browser list.
+    fn list_len(&self) -> usize {
+        if self.in_library_mode() {
+            self.library_view.nodes.len()
+        } else if let Some(view) = &self.playlist_view {
+            view.entries.len()
+        } else {
+            self.entries.len()
+        }
+    }
+
+    /// Enter the selected directory or playlist, or play the selected audio file.
+    fn open_entry(&mut self) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let entry = &self.entries[self.selected];
+        let path = self.current_dir.join(&entry.name);
+
+        if entry.is_dir {
+            self.change_dir(path);
+        } else if entry.is_playlist() {
+            self.open_playlist(&path);
+        } else if entry.is_audio() {
+            // Reading the tags takes a while; `process_cue` opens the file
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                let sheet = embedded_cue(&path);
+                let _ = tx.send((path, sheet));
             });
+            self.cue_rx = Some(rx);
+        }
+    }
+
+    /// Open the audio file checked for an embedded CUE sheet once the check
+    /// is done: browse its CUE tracks if it has some, play it otherwise.
+    pub fn process_cue(&mut self) {
+        let Some(rx) = &self.cue_rx else {
+            return;
+        };
+        let (path, sheet) = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.cue_rx = None;
+                return;
+            }
+        };
+        self.cue_rx = None;
+        // Skip it if the browser moved on meanwhile
+        let index = self
+            .entries
+            .iter()
+            .position(|e| self.current_dir.join(&e.name) == path)
+            .filter(|_| self.in_directory());
+        let Some(index) = index else {
+            return;
+        };
+        match sheet {
+            Some(sheet) => {
+                // Single-file album: browse its CUE tracks
+                self.playlist_view = Some(PlaylistView::from_entries(path, sheet.entries(), index));
+                self.selected = 0;
+                self.search = None;
+            }
+            None => {
+                self.queue.detach();
+                self.start_playback(&path, Some(index));
+            }
+        }
+    }
+
+    /// Switch the browser to `dir`, selecting its first entry.
+    fn change_dir(&mut self, dir: PathBuf) {
+        self.current_dir = dir;
+        self.playlist_view = None;
+        self.entries.clear();
+        self.entries_error = None;
+        self.selected = 0;
+        self.search = None;
+        self.current_track_index = None;
+        self.start_dir_load(None);
+        if let Some(watcher) = &mut self.watcher {
+            watcher.watch_dir(&self.current_dir);
+        }
+    }
+
+    /// Start (or restart) loading `current_dir` in the background, cancelling
+    /// any previous load. `select` names the entry to select once listed.
+    fn start_dir_load(&mut self, select: Option<String>) {
+        self.pending_selection = select;
+        self.dir_load = Some(DirLoad::spawn(
+            self.current_dir.clone(),
+            self.list_options,
+            self.library_path.clone(),
+        ));
+    }
+
+    /// Apply progress from the background directory load.
+    pub fn process_dir_load(&mut self) {
+        let Some(load) = &self.dir_load else {
+            return;
+        };
+        let mut events = Vec::new();
+        while let Some(event) = load.try_next() {
+            let last = matches!(event, LoadEvent::Done | LoadEvent::Failed(_));
+            events.push(event);
+            if last {
+                break;
+            }
+        }
+        if events.is_empty() {
+            return;
+        }
+
+        // Keep the selection on the same name while entries shift around.
+        // In a library mode, the directory selection is parked in `file_selection`;
+        // inside a playlist, in the view's `return_selection`.
+        let in_files = self.in_directory();
+        let current = match &self.playlist_view {
+            _ if in_files => self.selected,
+            Some(view) => view.return_selection,
+            None => self.file_selection,
+        };
+        let mut keep = self.entries.get(current).map(|e| e.name.clone());
+        for event in events {
+            match event {
+                LoadEvent::Listed(list) => {
+                    self.entries = list;
+                    self.entries_error = None;
+                    if let Some(name) = self.pending_selection.take() {
+                        keep = Some(name);
+                    }
+                }
+                LoadEvent::Refined(batch) => {
+                    for (entry, shown) in batch {
+                        let pos = self.entries.iter().position(|e| e.name == entry.name);
+                        match (pos, shown) {
+                            (Some(i), true) => self.entries[i] = entry,
+                            (Some(i), false) => {
+                                self.entries.remove(i);
+                            }
+                            (None, true) => self.entries.push(entry),
+                            (None, false) => {}
+                        }
+                    }
+                }
+                LoadEvent::Done => self.dir_load = None,
+                LoadEvent::Failed(e) => {
+                    self.entries.clear();
+                    self.entries_error = Some(describe_io_error(&e));
+                    self.dir_load = None;
+                }
+            }
+        }
+        let spec = self.sort_spec();
+        sort_entries(&mut self.entries, spec);
+
+        let selected = keep
+            .and_then(|name| self.entries.iter().position(|e| e.name == name))
+            .unwrap_or(current)
+            .min(self.entries.len().saturating_sub(1));
+        self.current_track_index = self.now_playing_index();
+        if in_files {
+            self.selected = selected;
+            if self.search.is_some() {
+                self.update_search();
+            }
+            self.sync_list_state();
+        } else if let Some(view) = &mut self.playlist_view {
+            view.return_selection = selected;
+        } else {
+            self.file_selection = selected;
+        }
+    }
+
+    /// Sort order of the current directory.
+    fn sort_spec(&self) -> SortSpec {
+        self.sort_specs.get(&self.current_dir).copied().unwrap_or_default()
+    }
+
+    /// Change the current directory's sort order and re-sort its entries,
+    /// keeping the selection on the same entry.
+    fn change_sort(&mut self, change: impl FnOnce(&mut SortSpec)) {
+        if !self.in_directory() {
+            return;
+        }
+        let mut spec = self.sort_spec();
+        change(&mut spec);
+        self.sort_specs.insert(self.current_dir.clone(), spec);
+
+        let selected_name = self.entries.get(self.selected).map(|e| e.name.clone());
+        sort_entries(&mut self.entries, spec);
+        if let Some(name) = selected_name {
+            self.selected = self.entries.iter().position(|e| e.name == name).unwrap_or(0);
+        }
+        self.current_track_index = self.now_playing_index();
+        if self.search.is_some() {
+            self.update_search();
+        }
+        self.sync_list_state();
+    }
+
+    /// Show the selected image file in the artwork panel, decoding it in the
+    /// background. On anything else, go back to track artwork.
+    fn preview_selected_image(&mut self) {
+        let image = self
+            .entries
+            .get(self.selected)
+            .filter(|e| self.in_directory() && !e.is_dir && e.category == FileCategory::Image);
+        let Some(entry) = image else {
+            self.preview = None;
+            self.preview_rx = None;
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let name = entry.name.clone();
+        let path = self.current_dir.join(&entry.name);
+        thread::spawn(move || {
+            let _ = tx.send((name, image::open(path).ok()));
+        });
+        self.preview_rx = Some(rx);
+        self.visibility.artwork = true;
+    }
+
+    /// Install a decoded preview image once it is ready.
+    pub fn process_preview(&mut self) {
+        let Some(rx) = &self.preview_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok((name, Some(image))) => {
+                self.preview = Some((name, self.picker.new_resize_protocol(image)));
+                self.preview_rx = None;
+            }
+            Ok((name, None)) => {
+                self.notice = Some(format!("can't preview {}", name));
+                self.preview_rx = None;
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.preview_rx = None,
         }
     }
+
+    /// Position of the playing track among the current entries, if listed there.
+    fn now_playing_index(&self) -> Option<usize> {
+        let playing = self.now_playing.as_ref()?;
+        if playing.parent() != Some(self.current_dir.as_path()) {
+            return None;
+        }
+        let name = playing.file_name()?.to_string_lossy();
+        self.entries.iter().position(|e| e.name == name)
+    }
+
+    /// Reload the current directory in place, keeping the selection on the
+    /// same file name. If the directory vanished, move to its nearest
+    /// existing ancestor.
+    fn refresh_entries(&mut self) {
+        if !self.current_dir.is_dir() {
+            let mut dir = self.current_dir.clone();
+            while !dir.is_dir() && dir.pop() {}
+            self.notice = Some(format!("{} was removed", tail_path(&self.current_dir, 2)));
+            self.change_dir(dir);
+            return;
+        }
+
+        // Current entries stay visible until the new listing arrives
+        let selected_name = self.entries.get(self.selected).map(|e| e.name.clone());
+        self.start_dir_load(selected_name);
+    }
+
+    /// Apply debounced filesystem changes to the browser and library.
+    pub fn process_fs_events(&mut self) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        let changes = watcher.poll();
+        if !changes.any() {
+            return;
+        }
+        if changes.dir_changed {
+            self.refresh_entries();
+        }
+        if changes.library_changed {
+            if self.scan_rx.is_some() {
+                self.rescan_pending = true;
+            } else {
+                self.rescan_library();
+            }
+        }
+    }
+
+    /// Switch to the next browse mode, falling back to files without a library.
+    fn cycle_browse_mode(&mut self) {
+        let next = self.library_view.mode.next();
+        if self.library.is_none() {
+            return;
+        }
+        if !self.in_library_mode() {
+            self.close_playlist();
+            self.file_selection = self.selected;
+        }
+        let Some(index) = &self.library else {
+            return;
+        };
+        self.search = None;
+        if next == BrowseMode::Files {
+            self.library_view.mode = BrowseMode::Files;
+            self.selected = self.file_selection.min(self.entries.len().saturating_sub(1));
+        } else if self.library_view.set_mode(next, index).is_ok() {
+            self.selected = 0;
+        }
+    }
+
+    /// Descend into the selected library group, or play the selected track.
+    fn descend_library_node(&mut self) {
+        let Some(index) = &self.library else {
+            return;
+        };
+        match self.library_view.descend(self.selected, index) {
+            Ok(true) => {
+                self.selected = 0;
+                self.search = None;
+            }
+            Ok(false) => self.play_library_node(),
+            Err(_) => {}
+        }
+    }
+
+    /// Go back up one library level, restoring the previous selection.
+    fn ascend_library(&mut self) {
+        let Some(index) = &self.library else {
+            return;
+        };
+        if let Ok(Some(selected)) = self.library_view.ascend(index) {
+            self.selected = selected;
+            self.search = None;
+        }
+    }
+
+    /// Replace the queue with the selected node's tracks and start playing.
+    /// On a track row, the whole track list is queued starting at that track.
+    fn play_library_node(&mut self) {
+        let Some(index) = &self.library else {
+            return;
+        };
+        let on_track = matches!(
+            self.library_view.nodes.get(self.selected),
+            Some(LibraryNode::Track(_))
+        );
+        let (tracks, start) = if on_track {
+            let tracks = self
+                .library_view
+                .nodes
+                .iter()
+                .filter_map(|n| match n {
+                    LibraryNode::Track(t) => Some(t.path.clone()),
+                    LibraryNode::Group { .. } => None,
+                })
+                .collect();
+            (tracks, self.selected)
+        } else {
+            match self.library_view.tracks_of(self.selected, index) {
+                Ok(tracks) => (tracks, 0),
+                Err(_) => return,
+            }
+        };
+
+        if let Some(path) = self.queue.replace(tracks, start).map(Path::to_path_buf) {
+            self.start_playback(&path, None);
+        }
+    }
+
+    /// Append the selected node, audio file, playlist, or directory's audio files
+    /// to the queue.
+    fn enqueue_selected(&mut self) {
+        let tracks = if let Some(view) = &self.playlist_view {
+            view.entries
+                .get(self.selected)
+                .map(|e| e.path.clone())
+                .into_iter()
+                .collect()
+        } else if self.in_library_mode() {
+            match &self.library {
+                Some(index) => self
+                    .library_view
+                    .tracks_of(self.selected, index)
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        } else {
+            match self.entries.get(self.selected) {
+                Some(entry) if entry.is_dir => {
+                    let dir = self.current_dir.join(&entry.name);
+                    load_entries(&dir)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(DirEntry::is_audio)
+                        .map(|e| dir.join(e.name))
+                        .collect()
+                }
+                Some(entry) if entry.is_audio() => vec![self.current_dir.join(&entry.name)],
+                Some(entry) if entry.is_playlist() => {
+                    match load_playlist(&self.current_dir.join(&entry.name)) {
+                        Ok(entries) => entries.into_iter().map(|e| e.path).collect(),
+                        Err(e) => {
+                            self.notice = Some(e.to_string());
+                            Vec::new()
+                        }
+                    }
+                }
+                _ => Vec::new(),
+            }
+        };
+        self.queue.append(tracks);
+    }
+
+    /// Open the playlist at `path` as a virtual directory.
+    fn open_playlist(&mut self, path: &Path) {
+        match PlaylistView::open(path, self.selected) {
+            Ok(view) => {
+                self.playlist_view = Some(view);
+                self.selected = 0;
+                self.search = None;
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+    }
+
+    /// Show the play queue in the browser, or leave it if it is shown.
+    fn toggle_queue_view(&mut self) {
+        if self.in_queue_view() {
+            self.close_playlist();
+            return;
+        }
+        if self.in_library_mode() {
+            self.library_view.mode = BrowseMode::Files;
+            self.selected = self.file_selection.min(self.entries.len().saturating_sub(1));
+        }
+        self.close_playlist();
+        self.playlist_view = Some(PlaylistView::queue(&self.queue.tracks, self.selected));
+        self.selected = self.queue.current.unwrap_or(0);
+        self.search = None;
+    }
+
+    /// Show the queue's current contents after it changed.
+    fn refresh_queue_view(&mut self) {
+        if let Some(view) = &mut self.playlist_view
+            && view.is_queue
+        {
+            *view = PlaylistView::queue(&self.queue.tracks, view.return_selection);
+            self.selected = self.selected.min(self.queue.tracks.len().saturating_sub(1));
+            if self.search.is_some() {
+                self.update_search();
+            }
+        }
+    }
+
+    /// Leave the opened playlist, restoring the directory selection.
+    fn close_playlist(&mut self) {
+        if let Some(view) = self.playlist_view.take() {
+            self.selected = view.return_selection.min(self.entries.len().saturating_sub(1));
+            self.search = None;
+        }
+    }
+
+    /// Replace the queue with the opened playlist and play from the selected entry.
+    fn play_playlist_entry(&mut self) {
+        let Some(view) = &self.playlist_view else {
+            return;
+        };
+        let Some(entry) = view.entries.get(self.selected) else {
+            return;
+        };
+        if entry.missing {
+            self.notice = Some(format!("{} is missing", tail_path(&entry.path, 1)));
+            return;
+        }
+        // Missing entries are left out of the queue
+        let start = entry.start;
+        let mut paths = view.playable_paths();
+        let mut index = view.entries[..self.selected]
+            .iter()
+            .filter(|e| !e.missing)
+            .count();
+        if start.is_some() {
+            // CUE tracks share files: queue each file once and seek within it
+            index = paths[..=index.min(paths.len().saturating_sub(1))]
+                .windows(2)
+                .filter(|w| w[0] != w[1])
+                .count();
+            paths.dedup();
+        }
+        let Some(path) = self.queue.replace(paths, index).map(Path::to_path_buf) else {
+            return;
+        };
+        self.start_playback(&path, None);
+        if let Some(start) = start.filter(|s| !s.is_zero()) {
+            self.resume_prompt = None;
+            self.player.seek(start);
+            self.elapsed = start.as_secs();
+        }
+    }
+
+    /// CUE sheet of the playing file and the index of the cue track at the
+    /// current position, if the file has one.
+    fn current_cue(&self) -> Option<(usize, &CueSheet)> {
+        let sheet = self.player.metadata.as_ref()?.cue.as_ref()?;
+        Some((sheet.track_at(self.player.position())?, sheet))
+    }
+
+    /// Move between the CUE tracks of the playing file by seeking. Previous
+    /// restarts the current cue track unless it just began. Returns false
+    /// past either end of the sheet.
+    fn skip_cue_track(&mut self, steps: i32) -> bool {
+        let Some((index, sheet)) = self.current_cue() else {
+            return false;
+        };
+        let into = self
+            .player
+            .position()
+            .saturating_sub(sheet.tracks[index].start);
+        let Some(start) = skip_target(index, sheet.tracks.len(), steps, into)
+            .and_then(|target| sheet.tracks.get(target))
+            .map(|t| t.start)
+        else {
+            return false;
+        };
+        self.player.seek(start);
+        self.elapsed = start.as_secs();
+        true
+    }
+
+    /// Chapters of the playing track (empty if it has none).
+    fn chapters(&self) -> &[Chapter] {
+        self.player
+            .metadata
+            .as_ref()
+            .map(|m| m.chapters.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Index of the chapter at the current position, if the track has chapters.
+    fn current_chapter(&self) -> Option<usize> {
+        chapter_at(self.chapters(), self.player.position())
+    }
+
+    /// Seek to the start of chapter `index` of the playing track.
+    fn seek_to_chapter(&mut self, index: usize) {
+        if let Some(start) = self.chapters().get(index).map(|c| c.start) {
+            self.player.seek(start);
+            self.elapsed = start.as_secs();
+        }
+    }
+
+    /// Move between chapters of the playing track. Like cue tracks, previous
+    /// restarts the current chapter unless it just began.
+    fn skip_chapter(&mut self, steps: i32) {
+        let Some(index) = self.current_chapter() else {
+            // Before the first chapter, next jumps to it
+            if steps > 0 && !self.chapters().is_empty() {
+                self.seek_to_chapter(0);
+            }
+            return;
+        };
+        let into = self
+            .player
+            .position()
+            .saturating_sub(self.chapters()[index].start);
+        match skip_target(index, self.chapters().len(), steps, into) {
+            Some(target) => self.seek_to_chapter(target),
+            None if steps < 0 => self.seek_to_chapter(0),
+            None => {}
+        }
+    }
+
+    /// Handle a key while the chapter list popup is open.
+    fn on_chapter_list_key(&mut self, key: KeyEvent) {
+        let Some(selected) = self.chapter_list else {
+            return;
+        };
+        let KeyResult::Action(action, count) =
+            self.config.keys.feed(KeyMode::Chapters, &mut self.key_input, &key)
+        else {
+            return;
+        };
+        let last = self.chapters().len().saturating_sub(1);
+        let times = count.unwrap_or(1).max(1);
+        match action {
+            NavigationAction::Cancel => self.chapter_list = None,
+            NavigationAction::Down => {
+                self.chapter_list = Some(selected.saturating_add(times).min(last))
+            }
+            NavigationAction::Up => self.chapter_list = Some(selected.saturating_sub(times)),
+            NavigationAction::Top => {
+                self.chapter_list = Some(count.map_or(0, |n| n.saturating_sub(1)).min(last));
+            }
+            NavigationAction::Bottom => {
+                self.chapter_list = Some(count.map_or(last, |n| n.saturating_sub(1)).min(last));
+            }
+            NavigationAction::Enter => {
+                self.chapter_list = None;
+                self.seek_to_chapter(selected);
+            }
+            _ => {}
+        }
+    }
+
+    /// Remember where the playing track is, if it is long enough to resume.
+    /// Positions near either end forget the track instead.
+    fn remember_position(&mut self) {
+        // Until an offer is answered, the position is still the old one
+        if self.resume_prompt.is_none() {
+            let point = ResumePoint::new(self.player.position(), self.duration);
+            self.save_resume_point(point);
+        }
+    }
+
+    /// Store `point` for the playing track if it is long enough, and update
+    /// its marker in the browser.
+    fn save_resume_point(&mut self, point: Option<ResumePoint>) {
+        self.resume_saved_at = Instant::now();
+        let Some(path) = &self.now_playing else {
+            return;
+        };
+        if self.duration < self.resume_min_secs {
+            return;
+        }
+        if let Some(index) = &self.library {
+            let _ = index.set_resume_point(path, point);
+        }
+        if path.parent() == Some(self.current_dir.as_path())
+            && let Some(entry) = self
+                .entries
+                .iter_mut()
+                .find(|e| path.file_name() == Some(e.name.as_ref()))
+        {
+            entry.resume = point;
+        }
+    }
+
+    /// Handle a key while the "save queue as" prompt is open.
+    fn on_save_prompt_key(&mut self, key: KeyEvent) {
+        let Some(name) = &mut self.save_prompt else {
+            return;
+        };
+        match prompt_action(SAVE_PROMPT_KEYS, &key) {
+            Some(PromptAction::DeleteChar) => {
+                name.pop();
+            }
+            Some(PromptAction::Confirm) => {
+                let name = std::mem::take(name);
+                self.save_prompt = None;
+                if !name.trim().is_empty() {
+                    self.save_queue(name.trim());
+                }
+            }
+            Some(PromptAction::Cancel) => self.save_prompt = None,
+            Some(_) => {}
+            None => {
+                if let KeyCode::Char(c) = key.code {
+                    name.push(c);
+                }
+            }
+        }
+    }
+
+    /// Handle a key while the help overlay is open: scroll, or filter
+    /// after `/`.
+    fn on_help_key(&mut self, key: KeyEvent) {
+        let Some(help) = &mut self.help else {
+            return;
+        };
+        let page = help.height.saturating_sub(1).max(1) as isize;
+        if help.editing {
+            match prompt_action(HELP_FILTER_KEYS, &key) {
+                Some(PromptAction::Cancel) => {
+                    help.query.clear();
+                    help.editing = false;
+                    help.update();
+                }
+                Some(PromptAction::Confirm) => help.editing = false,
+                Some(PromptAction::DeleteChar) => {
+                    help.query.pop();
+                    help.update();
+                }
+                Some(PromptAction::Next) => help.scroll_by(1),
+                Some(PromptAction::Previous) => help.scroll_by(-1),
+                Some(_) => {}
+                None => {
+                    if let Some(c) = KeyChord::from_event(&key).char() {
+                        help.query.push(c);
+                        help.update();
+                    }
+                }
+            }
+            return;
+        }
+        match prompt_action(HELP_KEYS, &key) {
+            Some(PromptAction::Cancel) if !help.query.is_empty() => {
+                help.query.clear();
+                help.update();
+            }
+            Some(PromptAction::Cancel | PromptAction::Close) => self.help = None,
+            Some(PromptAction::Filter) => help.editing = true,
+            Some(PromptAction::Next) => help.scroll_by(1),
+            Some(PromptAction::Previous) => help.scroll_by(-1),
+            Some(PromptAction::PageDown) => help.scroll_by(page),
+            Some(PromptAction::PageUp) => help.scroll_by(-page),
+            Some(PromptAction::Top) => help.scroll = 0,
+            Some(PromptAction::Bottom) => help.scroll_by(isize::MAX),
+            _ => {}
+        }
+    }
+
+    /// Handle a key while the command line is open. Returns true if the
+    /// command run quits the app.
+    fn on_command_key(&mut self, key: KeyEvent) -> bool {
+        let Some(command) = &mut self.command else {
+            return false;
+        };
+        match prompt_action(COMMAND_LINE_KEYS, &key) {
+            Some(PromptAction::Cancel) => self.command = None,
+            Some(PromptAction::Confirm) => {
+                let line = command.input.trim().to_string();
+                self.command = None;
+                if !line.is_empty() {
+                    remember(&mut self.command_history, &line);
+                    return self.run_command(&line);
+                }
+            }
+            Some(PromptAction::Complete) => command.complete(&self.current_dir, 1),
+            Some(PromptAction::CompleteBack) => command.complete(&self.current_dir, -1),
+            Some(PromptAction::Previous) => command.recall(&self.command_history, -1),
+            Some(PromptAction::Next) => command.recall(&self.command_history, 1),
+            // Deleting past the start closes the prompt, as in vim
+            Some(PromptAction::DeleteChar) if command.input.is_empty() => self.command = None,
+            Some(PromptAction::DeleteChar) => command.pop(),
+            Some(_) => {}
+            None => {
+                if let Some(c) = KeyChord::from_event(&key).char() {
+                    command.push(c);
+                }
+            }
+        }
+        false
+    }
+
+    /// Run a command line, reporting the outcome as a notice. Returns true
+    /// if it quits the app.
+    fn run_command(&mut self, line: &str) -> bool {
+        let command = match Command::parse(line) {
+            Ok(command) => command,
+            Err(e) => {
+                self.notice = Some(e.to_string());
+                return false;
+            }
+        };
+        match command {
+            Command::Action(action, count) => return self.perform(action, count),
+            Command::Cd(path) => {
+                let dir = resolve_path(&self.current_dir, &path);
+                if dir.is_dir() {
+                    self.library_view.mode = BrowseMode::Files;
+                    self.change_dir(dir.canonicalize().unwrap_or(dir));
+                } else {
+                    self.notice = Some(format!("not a directory: {}", path));
+                }
+            }
+            Command::Add(path) => {
+                self.notice = Some(match tracks_of(&resolve_path(&self.current_dir, &path)) {
+                    Ok(tracks) if tracks.is_empty() => format!("no audio files in {}", path),
+                    Ok(tracks) => {
+                        let count = tracks.len();
+                        self.queue.append(tracks);
+                        self.refresh_queue_view();
+                        format!("queued {} tracks", count)
+                    }
+                    Err(e) => e.to_string(),
+                });
+            }
+            Command::Volume(amount) => {
+                let current = (self.player.volume() * 100.0).round() as u64;
+                let volume = amount.apply(current).min(100);
+                self.player.set_volume(volume as f32 / 100.0);
+                self.notice = Some(format!("volume: {}%", volume));
+            }
+            Command::Seek(amount) => self.seek(amount),
+            Command::Save(name) => {
+                self.save_queue(&expand_home(&name).to_string_lossy());
+            }
+            Command::Set(key, value) => {
+                self.notice = Some(match self.config.set(&key, &value) {
+                    Ok(()) => {
+                        self.apply_setting(&key);
+                        format!("{} = {}", key, value)
+                    }
+                    Err(e) => e.to_string(),
+                });
+            }
+            Command::Theme(name) => match Theme::named(&name, &self.config.theme) {
+                Some(theme) => {
+                    self.theme = theme;
+                    self.notice = Some(format!("theme: {}", self.theme.name));
+                }
+                None => {
+                    self.notice = Some(format!(
+                        "unknown theme \"{}\", expected one of: {}",
+                        name,
+                        bundled_names().join(", ")
+                    ));
+                }
+            },
+        }
+        self.sync_list_state();
+        false
+    }
+
+    /// Bring what depends on the setting `key` in line with a changed config.
+    fn apply_setting(&mut self, key: &str) {
+        let layout = self.config.layout.visibility();
+        match key {
+            "general.resume_min_minutes" => {
+                self.resume_min_secs = self.config.general.resume_min_minutes * 60;
+            }
+            "layout.show_files" => self.visibility.files = layout.files,
+            "layout.show_player" => self.visibility.player = layout.player,
+            "layout.show_artwork" => self.visibility.artwork = layout.artwork,
+            "layout.show_visualizer" => self.visibility.visualizer = layout.visualizer,
+            "layout.root" => self.initial_layout = self.config.layout.root.clone(),
+            "theme.name" => self.theme = Theme::from_config(&self.config.theme),
+            _ if key.starts_with("theme.") => {
+                self.theme = Theme::named(self.theme.name, &self.config.theme)
+                    .unwrap_or_else(|| Theme::from_config(&self.config.theme));
+            }
+            _ if key.starts_with("visualizer.") => {
+                self.visualizer = Visualizer::new(&self.config);
+            }
+            _ => {}
+        }
+    }
+
+    /// Write the queue to `name` (relative to the current directory). The
+    /// format follows the extension; without a known one, `.m3u8` is added.
+    fn save_queue(&mut self, name: &str) {
+        if self.queue.tracks.is_empty() {
+            self.notice = Some("queue is empty".to_string());
+            return;
+        }
+        let mut path = self.current_dir.join(name);
+        if PlaylistFormat::from_path(&path).is_none() {
+            path.as_mut_os_string().push(".m3u8");
+        }
+        let entries: Vec<PlaylistEntry> = self
+            .queue
+            .tracks
+            .iter()
+            .map(|track| {
+                let mut entry = PlaylistEntry::new(track.clone());
+                // Indexed tags give nicer #EXTINF titles than file names
+                if let Some(t) = self
+                    .library
+                    .as_ref()
+                    .and_then(|index| index.get(track).ok().flatten())
+                {
+                    entry.title = match (&t.tags.artist, &t.tags.title) {
+                        (Some(artist), Some(title)) => Some(format!("{} - {}", artist, title)),
+                        (_, title) => title.clone(),
+                    };
+                    entry.duration_secs = Some(t.tags.duration_secs);
+                }
+                entry
+            })
+            .collect();
+        self.notice = Some(match save_playlist(&path, &entries) {
+            Ok(()) => format!("saved {} tracks to {}", entries.len(), tail_path(&path, 1)),
+            Err(e) => format!("can't save {}: {}", tail_path(&path, 1), e),
+        });
+    }
+
+    /// Skip forward or backward: between the CUE tracks of the playing file,
+    /// then through the queue if it drives playback, otherwise through the
+    /// audio files of the current directory.
+    fn skip_track(&mut self, steps: i32) {
+        if self.skip_cue_track(steps) {
+            return;
+        }
+        if self.queue.is_active() {
+            let next = if self.shuffle && steps > 0 {
+                self.queue.shuffle_step()
+            } else {
+                self.queue.step(steps, true)
+            };
+            if let Some(path) = next.map(Path::to_path_buf) {
+                self.start_playback(&path, None);
+            }
+        } else {
+            self.play_adjacent_track(steps);
+        }
+    }
+
+    /// Draw the application UI.
+    pub fn draw(&mut self, f: &mut Frame<'_>) {
+        let area = f.area();
+        self.screen = area;
+        self.player_controls.clear();
+        if self.is_mini() {
+            let status = self.prompt_status();
+            let controls = render_mini_player(
+                f,
+                area,
+                &self.player_info(),
+                &self.visualizer,
+                status.trim_start(),
+                &self.theme,
+            );
+            self.player_controls = controls;
+        } else {
+            self.draw_panels(f, area);
+        }
+
+        // Popups are drawn last, over everything else
+        if let Some(finder) = &self.finder {
+            render_finder(f, area, finder, &self.theme);
+        }
+        if let Some(selected) = self.chapter_list {
+            let chapters = self.chapters();
+            let selected = selected.min(chapters.len().saturating_sub(1));
+            render_chapter_list(
+                f,
+                area,
+                chapters,
+                self.current_chapter(),
+                selected,
+                &self.theme,
+            );
+        }
+        if let Some(help) = &mut self.help {
+            render_help(f, area, help, &self.theme);
+        }
+    }
+
+    /// True when the UI is collapsed to the mini-player, by choice or
+    /// because the terminal is too short.
+    pub fn is_mini(&self) -> bool {
+        self.mini || self.screen.height < self.config.layout.mini_below
+    }
+
+    /// What the player panels show.
+    fn player_info(&self) -> PlayerInfo<'_> {
+        // Inside a CUE sheet, progress is per cue track
+        let (start, duration) = self.progress_span();
+        PlayerInfo {
+            path: self.now_playing.as_deref(),
+            metadata: self.player.metadata.as_ref(),
+            cue: self.current_cue(),
+            elapsed: self.elapsed.saturating_sub(start),
+            duration,
+            is_playing: self.player.is_playing(),
+            is_paused: self.player.is_paused(),
+            volume: self.player.volume(),
+            repeat: self.repeat,
+            shuffle: self.shuffle,
+        }
+    }
+
+    /// Notices, open prompts and the search, each starting with spaces.
+    fn prompt_status(&self) -> String {
+        let mut status = String::new();
+        if let Some(notice) = &self.notice {
+            status.push_str(&format!(" ({})", notice));
+        }
+        if let Some(name) = &self.save_prompt {
+            status.push_str(&format!(
+                "  save queue as: {}{}",
+                name, self.theme.icons.cursor
+            ));
+        }
+        if let Some(command) = &self.command {
+            status.push_str(&format!(
+                "  :{}{}",
+                command.input, self.theme.icons.cursor
+            ));
+            if let Some((index, count)) = command.completion_status() {
+                status.push_str(&format!(" ({}/{})", index, count));
+            }
+        }
+        let pending = self.key_input.text();
+        if !pending.is_empty() {
+            status.push_str(&format!("  {}", pending));
+        }
+        if let Some(position) = self.resume_prompt {
+            let key = |action| {
+                self.config
+                    .keys
+                    .keys_for(KeyMode::Resume, action)
+                    .first()
+                    .map_or("-".to_string(), |keys| format_keys(keys))
+            };
+            status.push_str(&format!(
+                "  resume at {}? ({}/{})",
+                format_duration(position.as_secs()),
+                key(NavigationAction::Confirm),
+                key(NavigationAction::Cancel)
+            ));
+        }
+        if let Some(search) = &self.search {
+            let cursor = if search.editing {
+                self.theme.icons.cursor
+            } else {
+                ""
+            };
+            status.push_str(&format!(
+                "  /{}{} ({})",
+                search.query,
+                cursor,
+                search.rows.len()
+            ));
+        }
+        status
+    }
+
+    /// Draw the panels of the layout.
+    fn draw_panels(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let layout = compute_layout(area, &self.visibility, &self.config.layout);
+        let focused = self.theme.focused();
+
+        for (pane, area) in layout.panes {
+            let theme = if pane == self.focus {
+                &focused
+            } else {
+                &self.theme
+            };
+            match pane {
+                Pane::Files => {
+                    self.list_height = area.height.saturating_sub(2) as usize;
+                    let mut status = self
+                        .library_status
+                        .as_ref()
+                        .map(|s| format!(" [{}]", s))
+                        .unwrap_or_default();
+                    if self.dir_load.is_some() && self.in_directory() {
+                        status.push_str(&format!(" {} loading…", self.theme.icons.loading));
+                    }
+                    // Prompts, including the search, live in the list title
+                    status.push_str(&self.prompt_status());
+                    let filter = self.search.as_ref().map(|search| search.rows.as_slice());
+                    if self.in_library_mode() {
+                        let title = format!("1:  {}{}", self.library_view.title(), status);
+                        render_library_list(
+                            f,
+                            area,
+                            &title,
+                            &self.library_view.nodes,
+                            filter,
+                            &mut self.state,
+                            theme,
+                        );
+                    } else if let Some(view) = &self.playlist_view {
+                        let title = if view.is_queue {
+                            format!("1:  Queue ({}){}", view.entries.len(), status)
+                        } else {
+                            format!("1:  {}{}", tail_path(&view.path, 3), status)
+                        };
+                        render_playlist(
+                            f,
+                            area,
+                            &title,
+                            &view.entries,
+                            filter,
+                            &mut self.state,
+                            theme,
+                        );
+                    } else {
+                        let mut shown = self.sort_spec().to_string();
+                        if self.list_options.show_hidden {
+                            shown.push_str(" +hidden");
+                        }
+                        if self.list_options.show_all {
+                            shown.push_str(" +all");
+                        }
+                        let title = format!(
+                            "1:  {} [{}]{}",
+                            tail_path(&self.current_dir, 3),
+                            shown,
+                            status
+                        );
+                        let view = FileListView {
+                            title: &title,
+                            entries: &self.entries,
+                            columns: self.columns,
+                            error: self.entries_error.as_deref(),
+                            filter,
+                        };
+                        render_file_list(f, area, &view, &mut self.state, theme);
+                    }
+                }
+                Pane::Player => {
+                    // Everything but the borders, controls and gauge
+                    self.player_page = area.height.saturating_sub(6) as usize;
+                    let mut scroll = self.player_scroll;
+                    let controls =
+                        render_player_panel(f, area, &self.player_info(), &mut scroll, theme);
+                    self.player_controls = controls;
+                    self.player_scroll = scroll;
+                }
+                Pane::Artwork => {
+                    render_artwork(
+                        f,
+                        area,
+                        self.preview.as_mut().map(|(name, image)| (name.as_str(), image)),
+                        theme,
+                    );
+                }
+                Pane::Visualizer => render_spectrum(f, area, &self.visualizer, theme),
+            }
+        }
+    }
+
+    /// Update the visualizer with new audio samples.
+    pub fn update_visualizer(&mut self) {
+        self.visualizer.update(&self.player.sample_buffer);
+    }
+
+    /// Process any pending metadata from background loader.
+    pub fn process_metadata(&mut self) {
+        if let Ok(meta) = self.meta_rx.try_recv() {
+            self.player.metadata = Some(meta);
+            self.player_scroll = 0;
+            self.duration = self
+                .player
+                .metadata
+                .as_ref()
+                .map(|m| m.duration_secs.max(1))
+                .unwrap_or(1);
+        }
+    }
+
+    /// Start an incremental scan of the library roots in the background.
+    /// Does nothing if a scan is already running or the index is unavailable.
+    pub fn rescan_library(&mut self) {
+        if self.scan_rx.is_some() || self.library_roots.is_empty() {
+            return;
+        }
+        if let Some(path) = &self.library_path
+            && self.library.is_some()
+        {
+            self.scan_rx = Some(spawn_scan(path.clone(), self.library_roots.clone()));
+            self.library_status = Some("scanning".into());
+        }
+    }
+
+    /// Process any pending progress from the library scanner.
+    pub fn process_library_events(&mut self) {
+        let Some(rx) = &self.scan_rx else {
+            return;
+        };
+        loop {
+            match rx.try_recv() {
+                Ok(ScanEvent::Progress { scanned, .. }) => {
+                    self.library_status = Some(format!("scanning {}", scanned));
+                }
+                Ok(ScanEvent::Finished { scanned, .. }) => {
+                    self.library_status = Some(format!("{} tracks", scanned));
+                    self.scan_rx = None;
+                    // Pick up new or changed tracks in the library browser
+                    if self.in_library_mode()
+                        && let Some(index) = &self.library
+                        && self.library_view.reload(index).is_ok()
+                    {
+                        self.selected = self.selected.min(self.list_len().saturating_sub(1));
+                        self.search = None;
+                        self.sync_list_state();
+                    }
+                    if std::mem::take(&mut self.rescan_pending) {
+                        self.rescan_library();
+                    }
+                    return;
+                }
+                Ok(ScanEvent::Failed(err)) => {
+                    self.library_status = Some(format!("scan failed: {}", err));
+                    self.scan_rx = None;
+                    return;
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => {
+                    self.scan_rx = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Pull newly streamed candidates into the finder, if open.
+    pub fn process_finder(&mut self) {
+        if let Some(finder) = &mut self.finder {
+            finder.poll();
+        }
+    }
+
+    /// Advance to the next track when the current one played to its end,
+    /// or couldn't be played at all.
+    pub fn process_playback(&mut self) {
+        if let Some(error) = self.player.take_error() {
+            let name = self.now_playing.as_deref().map(|p| tail_path(p, 1));
+            let name = name.unwrap_or_default();
+            self.notice = Some(format!("can't play {}: {}", name, error));
+            // Skip the broken track, but stop once every track has failed
+            self.failed_in_row += 1;
+            let tracks = if self.queue.is_active() {
+                self.queue.tracks.len()
+            } else {
+                self.entries.len()
+            };
+            if self.failed_in_row < tracks {
+                self.play_next();
+            } else {
+                self.failed_in_row = 0;
+                self.queue.detach();
+                self.current_track_index = None;
+                self.now_playing = None;
+            }
+            return;
+        }
+        if !self.player.take_finished() {
+            return;
+        }
+        self.failed_in_row = 0;
+        // Played to the end: nothing left to resume
+        self.save_resume_point(None);
+        if self.repeat == RepeatMode::One
+            && let Some(path) = self.now_playing.clone()
+        {
+            self.start_playback(&path, self.current_track_index);
+            return;
+        }
+        self.play_next();
+    }
+
+    /// Play what follows the current track in the queue or directory,
+    /// honouring shuffle and repeat all.
+    fn play_next(&mut self) {
+        if self.queue.is_active() {
+            let next = if self.shuffle {
+                self.queue.shuffle_step()
+            } else {
+                self.queue.step(1, self.repeat == RepeatMode::All)
+            };
+            if let Some(path) = next.map(Path::to_path_buf) {
+                self.start_playback(&path, None);
+            } else {
+                self.queue.detach();
+                self.now_playing = None;
+            }
+        } else if let Some(idx) = self.current_track_index {
+            // Continue with the next audio file of the directory, wrapping
+            // around only with repeat all
+            let audio: Vec<usize> = (0..self.entries.len())
+                .filter(|&i| self.entries[i].is_audio())
+                .collect();
+            let next = if self.shuffle {
+                random_index(audio.len(), audio.iter().position(|&i| i == idx)).map(|p| audio[p])
+            } else {
+                audio.iter().copied().find(|&i| i > idx).or_else(|| {
+                    audio
+                        .first()
+                        .copied()
+                        .filter(|_| self.repeat == RepeatMode::All)
+                })
+            }
+            .map(|i| (i, self.current_dir.join(&self.entries[i].name)));
+            match next {
+                Some((i, path)) => {
+                    self.start_playback(&path, Some(i));
+                }
+                None => {
+                    self.current_track_index = None;
+                    self.now_playing = None;
+                }
+            }
+        } else {
+            self.now_playing = None;
+        }
+    }
+
+    /// Update elapsed time from the player position if playing, and save
+    /// the session now and then.
+    pub fn tick_elapsed(&mut self) {
+        if self.session_saved_at.elapsed() >= SESSION_SAVE_INTERVAL {
+            self.save_session();
+        }
+        // While the gauge is dragged, it shows where the drag is
+        if self.player.is_playing() && !self.player.is_paused() && self.seek_drag.is_none() {
+            self.elapsed = self.player.position().as_secs().min(self.duration);
+            // Once the track plays on, the resume offer lapses
+            if self.player.position() >= RESUME_OFFER_TIME {
+                self.resume_prompt = None;
+            }
+            if self.resume_saved_at.elapsed() >= RESUME_SAVE_INTERVAL {
+                self.remember_position();
+            }
+        }
+    }
+
+    /// Play the audio track `steps` away from the current one (negative to
+    /// go back), wrapping around the directory.
+    fn play_adjacent_track(&mut self, steps: i32) {
+        // Get audio file indices
+        let audio_indices: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_audio())
+            .map(|(i, _)| i)
+            .collect();
+
+        if audio_indices.is_empty() {
+            return;
+        }
+
+        // Find current position in audio files
+        let current_audio_pos = self
+            .current_track_index
+            .and_then(|idx| audio_indices.iter().position(|&i| i == idx));
+
+        let next_audio_pos = match current_audio_pos {
+            Some(pos) if self.shuffle && steps > 0 => {
+                random_index(audio_indices.len(), Some(pos)).unwrap_or(pos)
+            }
+            Some(pos) => (pos as i32)
+                .saturating_add(steps)
+                .rem_euclid(audio_indices.len() as i32) as usize,
+            None => {
+                // No track playing, start from first or last based on direction
+                if steps > 0 { 0 } else { audio_indices.len() - 1 }
+            }
+        };
+
+        let entry_idx = audio_indices[next_audio_pos];
+        let path = self.current_dir.join(&self.entries[entry_idx].name);
+
+        self.start_playback(&path, Some(entry_idx));
+        if self.in_directory() {
+            self.selected = entry_idx;
+            self.state.select(Some(entry_idx));
+        }
+    }
+
+    /// Start playing `path` and spawn the background metadata loader.
+    /// `index` is the position of the track in `entries`, if it was started from there.
+    /// Files that can't be played are reported by `process_playback`.
+    fn start_playback(&mut self, path: &Path, index: Option<usize>) {
+        self.remember_position();
+        self.player.play(path);
+
+        // Clear any prior metadata while background loader runs
+        self.player.metadata = None;
+        self.elapsed = 0;
+        self.duration = 1;
+        self.artwork = None;
+        self.current_track_index = index;
+        self.now_playing = Some(path.to_path_buf());
+        self.chapter_list = None;
+        self.resume_prompt = self
+            .library
+            .as_ref()
+            .and_then(|index| index.resume_point(path).ok().flatten())
+            .map(|point| point.position);
+        self.resume_saved_at = Instant::now();
+
+        // Spawn a background thread to load metadata
+        let tx = self.meta_tx.clone();
+        let path_clone = path.to_path_buf();
+        thread::spawn(move || {
+            if let Ok(meta) = MusicPlayer::load_metadata(path_clone) {
+                let _ = tx.send(meta);
+            }
+        });
+    }
+}
+
+/// Index `steps` away from `index` among `len` cue tracks or chapters, where
+/// `into` is how far playback is into the current one. Going back, a restart
+/// of the current one counts as the first step unless it just began. Returns
+/// `None` when already at the last one going forward, or past the first
+/// going back.
+fn skip_target(index: usize, len: usize, steps: i32, into: Duration) -> Option<usize> {
+    let count = steps.unsigned_abs() as usize;
+    if steps > 0 {
+        (index + 1 < len).then(|| index.saturating_add(count).min(len - 1))
+    } else if into > Duration::from_secs(3) {
+        index.checked_sub(count - 1)
+    } else {
+        index.checked_sub(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_target_clamps_forward_and_stops_at_the_end() {
+        let into = Duration::ZERO;
+        assert_eq!(skip_target(0, 5, 1, into), Some(1));
+        assert_eq!(skip_target(1, 5, 9999, into), Some(4));
+        assert_eq!(skip_target(4, 5, 1, into), None);
+    }
+
+    #[test]
+    fn skip_target_counts_the_restart_as_a_step_back() {
+        let late = Duration::from_secs(10);
+        assert_eq!(skip_target(2, 5, -1, late), Some(2));
+        assert_eq!(skip_target(2, 5, -2, late), Some(1));
+        assert_eq!(skip_target(2, 5, -1, Duration::ZERO), Some(1));
+        assert_eq!(skip_target(0, 5, -1, Duration::ZERO), None);
+        assert_eq!(skip_target(2, 5, -9999, late), None);
+    }
 }
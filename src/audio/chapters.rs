@@ -0,0 +1,612 @@
+// src/audio/chapters.rs
+//! Chapter markers from ID3v2 `CHAP`/`CTOC` frames (MP3 podcasts), and Nero
+//! `chpl` atoms or QuickTime chapter tracks (M4A/M4B audiobooks).
+//!
+//! Lofty keeps these as opaque data, so they are read directly from the file.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    time::Duration,
+};
+
+/// Largest ID3v2 tag read into memory; chapter images can make tags big.
+const MAX_ID3_SIZE: usize = 32 << 20;
+
+/// Largest MP4 box payload read into memory.
+const MAX_BOX_SIZE: u64 = 16 << 20;
+
+/// Most chapters read from a QuickTime chapter track.
+const MAX_CHAPTERS: usize = 10_000;
+
+/// Most bytes of a QuickTime chapter title read.
+const MAX_TITLE: u64 = 1024;
+
+/// One chapter of a track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    /// Chapter title (or a numbered placeholder)
+    pub title: String,
+    /// Offset of the chapter within the track
+    pub start: Duration,
+}
+
+/// Index of the chapter playing at `position`.
+pub fn chapter_at(chapters: &[Chapter], position: Duration) -> Option<usize> {
+    chapters.iter().rposition(|c| c.start <= position)
+}
+
+/// Read the chapters of `path`, ordered by start time. Files without
+/// chapters, or that can't be read, give an empty list.
+pub fn load_chapters(path: &Path) -> Vec<Chapter> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::new(file);
+    let mut magic = [0u8; 8];
+    if reader.read_exact(&mut magic).is_err() {
+        return Vec::new();
+    }
+    let mut chapters = if &magic[..3] == b"ID3" {
+        read_id3_chapters(&mut reader).unwrap_or_default()
+    } else if &magic[4..8] == b"ftyp" {
+        read_mp4_chapters(&mut reader).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    chapters.sort_by_key(|c| c.start);
+    chapters
+}
+
+/// Parse the CHAP frames of an ID3v2.3/2.4 tag at the start of the file.
+/// When a top-level CTOC lists the chapters, only those are kept.
+fn read_id3_chapters<R: Read + Seek>(reader: &mut R) -> Option<Vec<Chapter>> {
+    reader.seek(SeekFrom::Start(0)).ok()?;
+    let mut header = [0u8; 10];
+    reader.read_exact(&mut header).ok()?;
+    let version = header[3];
+    if !(3..=4).contains(&version) {
+        return None;
+    }
+    let tag_size = syncsafe(&header[6..10]) as usize;
+    if tag_size > MAX_ID3_SIZE {
+        return None;
+    }
+    let mut tag = vec![0u8; tag_size];
+    reader.read_exact(&mut tag).ok()?;
+
+    let mut chapters: HashMap<String, Chapter> = HashMap::new();
+    let mut order: Option<Vec<String>> = None;
+    let mut pos = 0;
+    // Skip the extended header if present
+    if header[5] & 0x40 != 0 && tag.len() >= 4 {
+        pos = if version == 4 {
+            syncsafe(&tag[0..4]) as usize
+        } else {
+            u32::from_be_bytes(tag[0..4].try_into().ok()?) as usize + 4
+        };
+    }
+
+    for (id, body) in id3_frames(&tag[pos.min(tag.len())..], version) {
+        match id {
+            b"CHAP" => {
+                if let Some((element, chapter)) = parse_chap(body, version, chapters.len()) {
+                    chapters.insert(element, chapter);
+                }
+            }
+            b"CTOC" if order.is_none() => order = parse_ctoc(body),
+            _ => {}
+        }
+    }
+
+    Some(match order {
+        Some(ids) if ids.iter().all(|id| chapters.contains_key(id)) => {
+            ids.iter().filter_map(|id| chapters.remove(id)).collect()
+        }
+        _ => chapters.into_values().collect(),
+    })
+}
+
+/// Iterate the `(frame id, frame body)` pairs of an ID3v2 frame area.
+fn id3_frames(mut data: &[u8], version: u8) -> impl Iterator<Item = (&[u8; 4], &[u8])> {
+    std::iter::from_fn(move || {
+        if data.len() < 10 || data[0] == 0 {
+            return None;
+        }
+        let id: &[u8; 4] = data[0..4].try_into().ok()?;
+        let size = if version == 4 {
+            syncsafe(&data[4..8])
+        } else {
+            u32::from_be_bytes(data[4..8].try_into().ok()?)
+        } as usize;
+        let end = size.checked_add(10)?;
+        let body = data.get(10..end)?;
+        data = &data[end..];
+        Some((id, body))
+    })
+}
+
+/// Parse a CHAP frame body into its element id and chapter.
+fn parse_chap(body: &[u8], version: u8, n: usize) -> Option<(String, Chapter)> {
+    let nul = body.iter().position(|&b| b == 0)?;
+    let element = String::from_utf8_lossy(&body[..nul]).into_owned();
+    let times = body.get(nul + 1..nul + 17)?;
+    let start_ms = u32::from_be_bytes(times[0..4].try_into().ok()?);
+    // Embedded frames (usually TIT2) follow the four time/offset fields
+    let title = id3_frames(&body[nul + 17..], version)
+        .find(|(id, _)| *id == b"TIT2")
+        .and_then(|(_, text)| decode_id3_text(text))
+        .unwrap_or_else(|| format!("Chapter {}", n + 1));
+    Some((
+        element,
+        Chapter {
+            title,
+            start: Duration::from_millis(start_ms as u64),
+        },
+    ))
+}
+
+/// Child element ids of a top-level, ordered CTOC frame.
+fn parse_ctoc(body: &[u8]) -> Option<Vec<String>> {
+    let nul = body.iter().position(|&b| b == 0)?;
+    let flags = *body.get(nul + 1)?;
+    let count = *body.get(nul + 2)? as usize;
+    // Only the top-level table of contents defines the chapter order
+    if flags & 0x02 == 0 {
+        return None;
+    }
+    let mut rest = body.get(nul + 3..)?;
+    let mut ids = Vec::with_capacity(count);
+    for _ in 0..count {
+        let end = rest.iter().position(|&b| b == 0)?;
+        ids.push(String::from_utf8_lossy(&rest[..end]).into_owned());
+        rest = &rest[end + 1..];
+    }
+    Some(ids)
+}
+
+/// Decode an ID3v2 text frame (encoding byte followed by the text).
+fn decode_id3_text(body: &[u8]) -> Option<String> {
+    let (&encoding, text) = body.split_first()?;
+    let text = match encoding {
+        // UTF-16 with BOM, or UTF-16BE
+        1 | 2 => {
+            let (big_endian, text) = match text {
+                [0xFF, 0xFE, rest @ ..] => (false, rest),
+                [0xFE, 0xFF, rest @ ..] => (true, rest),
+                _ => (encoding == 2, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .take_while(|&u| u != 0)
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(text.split(|&b| b == 0).next()?).into_owned(),
+        // ISO-8859-1
+        _ => text
+            .iter()
+            .take_while(|&&b| b != 0)
+            .map(|&b| b as char)
+            .collect(),
+    };
+    Some(text.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// 28-bit "syncsafe" integer used by ID3v2 sizes.
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |acc, &b| (acc << 7) | (b & 0x7F) as u32)
+}
+
+/// Read the chapters of an MP4 file: the Nero list in `moov/udta/chpl`,
+/// or else the QuickTime chapter track.
+fn read_mp4_chapters<R: Read + Seek>(reader: &mut R) -> Option<Vec<Chapter>> {
+    let end = reader.seek(SeekFrom::End(0)).ok()?;
+    let moov = find_box(reader, 0, end, b"moov")?;
+    find_path(reader, moov, &[b"udta", b"chpl"])
+        .and_then(|chpl| read_payload(reader, chpl))
+        .and_then(|data| parse_chpl(&data))
+        .filter(|chapters| !chapters.is_empty())
+        .or_else(|| read_quicktime_chapters(reader, moov))
+}
+
+/// Parse the payload of a Nero `chpl` box.
+fn parse_chpl(data: &[u8]) -> Option<Vec<Chapter>> {
+    // Full box header, then 4 reserved bytes in version 1
+    let version = *data.first()?;
+    let mut pos = if version == 1 { 8 } else { 4 };
+    let count = *data.get(pos)? as usize;
+    pos += 1;
+    let mut chapters = Vec::with_capacity(count);
+    for n in 0..count {
+        // Start time in 100 ns units, then a length-prefixed UTF-8 title
+        let start = u64::from_be_bytes(data.get(pos..pos + 8)?.try_into().ok()?);
+        let len = *data.get(pos + 8)? as usize;
+        let title = String::from_utf8_lossy(data.get(pos + 9..pos + 9 + len)?).into_owned();
+        pos += 9 + len;
+        chapters.push(Chapter {
+            title: chapter_title(&title, n),
+            start: Duration::from_nanos(start.saturating_mul(100)),
+        });
+    }
+    Some(chapters)
+}
+
+/// Read QuickTime chapters: the samples of the text track that another
+/// track lists in its `tref/chap` box, timed by the text track's sample
+/// table.
+fn read_quicktime_chapters<R: Read + Seek>(
+    reader: &mut R,
+    moov: (u64, u64),
+) -> Option<Vec<Chapter>> {
+    // Track ids with the offsets of their boxes, and the chapter track ids
+    let mut tracks = Vec::new();
+    let mut chapter_ids = Vec::new();
+    let mut pos = moov.0;
+    while let Some(trak) = find_box(reader, pos, moov.1, b"trak") {
+        pos = trak.1;
+        let Some(tkhd) =
+            find_box(reader, trak.0, trak.1, b"tkhd").and_then(|tkhd| read_payload(reader, tkhd))
+        else {
+            continue;
+        };
+        let id_at = if tkhd.first() == Some(&1) { 20 } else { 12 };
+        if let Some(id) = be_u32(&tkhd, id_at) {
+            tracks.push((id, trak));
+        }
+        if let Some(chap) =
+            find_path(reader, trak, &[b"tref", b"chap"]).and_then(|chap| read_payload(reader, chap))
+        {
+            chapter_ids.extend((0..chap.len() / 4).filter_map(|i| be_u32(&chap, i * 4)));
+        }
+    }
+    let &(_, trak) = tracks.iter().find(|(id, _)| chapter_ids.contains(id))?;
+
+    let mdhd = find_path(reader, trak, &[b"mdia", b"mdhd"])?;
+    let mdhd = read_payload(reader, mdhd)?;
+    let timescale = be_u32(&mdhd, if mdhd.first() == Some(&1) { 20 } else { 12 })? as u64;
+    if timescale == 0 {
+        return None;
+    }
+    let stbl = find_path(reader, trak, &[b"mdia", b"minf", b"stbl"])?;
+    let table = |reader: &mut R, name: &[u8; 4]| {
+        find_box(reader, stbl.0, stbl.1, name).and_then(|b| read_payload(reader, b))
+    };
+    let starts = sample_starts(&table(reader, b"stts")?);
+    let sizes = sample_sizes(&table(reader, b"stsz")?)?;
+    let chunks = match table(reader, b"stco") {
+        Some(stco) => chunk_offsets(&stco, 4),
+        None => chunk_offsets(&table(reader, b"co64")?, 8),
+    };
+    let offsets = sample_offsets(&table(reader, b"stsc")?, &chunks, &sizes)?;
+
+    let mut chapters = Vec::new();
+    for (n, ((&start, &offset), &size)) in starts.iter().zip(&offsets).zip(&sizes).enumerate() {
+        let text = read_payload(reader, (offset, offset.checked_add(size.min(MAX_TITLE))?))
+            .map(|sample| decode_text_sample(&sample))
+            .unwrap_or_default();
+        chapters.push(Chapter {
+            title: chapter_title(&text, n),
+            start: Duration::from_millis(start.saturating_mul(1000) / timescale),
+        });
+    }
+    Some(chapters)
+}
+
+/// Start of each sample, in media time units, from an `stts` payload.
+fn sample_starts(stts: &[u8]) -> Vec<u64> {
+    let count = be_u32(stts, 4).unwrap_or(0) as usize;
+    let mut starts = Vec::new();
+    let mut time = 0u64;
+    for entry in 0..count {
+        let (Some(samples), Some(delta)) =
+            (be_u32(stts, 8 + entry * 8), be_u32(stts, 12 + entry * 8))
+        else {
+            break;
+        };
+        for _ in 0..samples {
+            if starts.len() >= MAX_CHAPTERS {
+                return starts;
+            }
+            starts.push(time);
+            time = time.saturating_add(delta as u64);
+        }
+    }
+    starts
+}
+
+/// Size of each sample from an `stsz` payload.
+fn sample_sizes(stsz: &[u8]) -> Option<Vec<u64>> {
+    let fixed = be_u32(stsz, 4)?;
+    let count = (be_u32(stsz, 8)? as usize).min(MAX_CHAPTERS);
+    Some(if fixed != 0 {
+        vec![fixed as u64; count]
+    } else {
+        (0..count)
+            .map_while(|i| be_u32(stsz, 12 + i * 4))
+            .map(u64::from)
+            .collect()
+    })
+}
+
+/// Chunk offsets from an `stco` (`width` 4) or `co64` (`width` 8) payload.
+fn chunk_offsets(data: &[u8], width: usize) -> Vec<u64> {
+    let count = be_u32(data, 4).unwrap_or(0) as usize;
+    (0..count.min(MAX_CHAPTERS))
+        .map_while(|i| {
+            let bytes = data.get(8 + i * width..8 + (i + 1) * width)?;
+            Some(bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+        })
+        .collect()
+}
+
+/// File offset of each sample, laying the samples out in chunks as the
+/// `stsc` payload describes.
+fn sample_offsets(stsc: &[u8], chunks: &[u64], sizes: &[u64]) -> Option<Vec<u64>> {
+    // (first chunk, samples per chunk), with chunks numbered from 1
+    let runs: Vec<(u32, u32)> = (0..be_u32(stsc, 4)? as usize)
+        .map_while(|i| Some((be_u32(stsc, 8 + i * 12)?, be_u32(stsc, 12 + i * 12)?)))
+        .collect();
+    let mut offsets = Vec::with_capacity(sizes.len());
+    for (i, &chunk_offset) in chunks.iter().enumerate() {
+        let chunk = i as u32 + 1;
+        let per_chunk = runs
+            .iter()
+            .rev()
+            .find(|(first, _)| *first <= chunk)
+            .map_or(0, |(_, n)| *n);
+        let mut offset = chunk_offset;
+        for _ in 0..per_chunk {
+            let Some(&size) = sizes.get(offsets.len()) else {
+                return Some(offsets);
+            };
+            offsets.push(offset);
+            offset = offset.checked_add(size)?;
+        }
+    }
+    Some(offsets)
+}
+
+/// Title of a QuickTime text sample: a 16-bit length, then UTF-8 text or
+/// UTF-16 text with a byte order mark.
+fn decode_text_sample(sample: &[u8]) -> String {
+    let len = sample
+        .get(0..2)
+        .map_or(0, |b| u16::from_be_bytes([b[0], b[1]]) as usize);
+    let text = sample.get(2..).unwrap_or_default();
+    let text = &text[..len.min(text.len())];
+    match text {
+        [0xFE, 0xFF, rest @ ..] | [0xFF, 0xFE, rest @ ..] => {
+            let big_endian = text[0] == 0xFE;
+            let units: Vec<u16> = rest
+                .chunks_exact(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).into_owned(),
+    }
+}
+
+/// `text` trimmed, or a numbered placeholder for chapter `n` when empty.
+fn chapter_title(text: &str, n: usize) -> String {
+    Some(text.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| format!("Chapter {}", n + 1))
+}
+
+/// Big-endian `u32` at `pos` of `data`.
+fn be_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(pos..pos.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Payload of the box at `range`, unless it is implausibly large.
+fn read_payload<R: Read + Seek>(reader: &mut R, (start, end): (u64, u64)) -> Option<Vec<u8>> {
+    let len = end.checked_sub(start)?;
+    if len > MAX_BOX_SIZE {
+        return None;
+    }
+    reader.seek(SeekFrom::Start(start)).ok()?;
+    let mut data = vec![0u8; len as usize];
+    reader.read_exact(&mut data).ok()?;
+    Some(data)
+}
+
+/// Follow a path of nested boxes below the payload at `range`.
+fn find_path<R: Read + Seek>(
+    reader: &mut R,
+    range: (u64, u64),
+    path: &[&[u8; 4]],
+) -> Option<(u64, u64)> {
+    path.iter().try_fold(range, |(start, end), name| {
+        find_box(reader, start, end, name)
+    })
+}
+
+/// Locate the child box `name` between `start` and `end`, returning the
+/// offsets of its payload.
+fn find_box<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    end: u64,
+    name: &[u8; 4],
+) -> Option<(u64, u64)> {
+    let mut pos = start;
+    while pos.checked_add(8)? <= end {
+        reader.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header).ok()?;
+        let mut size = u32::from_be_bytes(header[0..4].try_into().ok()?) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            reader.read_exact(&mut large).ok()?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - pos;
+        }
+        if size < header_len {
+            return None;
+        }
+        let box_end = pos.checked_add(size)?;
+        if &header[4..8] == name {
+            return Some((pos + header_len, box_end.min(end)));
+        }
+        pos = box_end;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// An MP4 box with `payload`.
+    fn mp4_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(name);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    /// A full box payload (version 0, no flags) followed by big-endian words.
+    fn words(values: &[u32]) -> Vec<u8> {
+        let mut data = vec![0; 4];
+        for v in values {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        data
+    }
+
+    fn tkhd(id: u32) -> Vec<u8> {
+        mp4_box(b"tkhd", &words(&[0, 0, id, 0, 0]))
+    }
+
+    #[test]
+    fn quicktime_chapter_track() {
+        let ftyp = mp4_box(b"ftyp", b"M4B \0\0\0\0");
+        let samples = [&b"\0\x05Intro"[..], &b"\0\x06Part 2"[..]];
+        let mdat = mp4_box(b"mdat", &samples.concat());
+        let first_sample = (ftyp.len() + 8) as u32;
+
+        let audio = mp4_box(
+            b"trak",
+            &[
+                tkhd(1),
+                mp4_box(b"tref", &mp4_box(b"chap", &2u32.to_be_bytes())),
+            ]
+            .concat(),
+        );
+        let stbl = [
+            mp4_box(b"stts", &words(&[1, 2, 60_000])),
+            mp4_box(b"stsz", &words(&[0, 2, 7, 8])),
+            mp4_box(b"stsc", &words(&[1, 1, 2, 1])),
+            mp4_box(b"stco", &words(&[1, first_sample])),
+        ]
+        .concat();
+        let mdia = [
+            mp4_box(b"mdhd", &words(&[0, 0, 1000, 120_000])),
+            mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+        ]
+        .concat();
+        let text = mp4_box(b"trak", &[tkhd(2), mp4_box(b"mdia", &mdia)].concat());
+        let moov = mp4_box(b"moov", &[audio, text].concat());
+
+        let file = [ftyp, mdat, moov].concat();
+        let chapters = read_mp4_chapters(&mut Cursor::new(file)).unwrap();
+        assert_eq!(
+            chapters,
+            [
+                Chapter {
+                    title: "Intro".into(),
+                    start: Duration::ZERO
+                },
+                Chapter {
+                    title: "Part 2".into(),
+                    start: Duration::from_secs(60)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nero_chapters() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        chpl.extend_from_slice(&0u64.to_be_bytes());
+        chpl.extend_from_slice(b"\x03One");
+        chpl.extend_from_slice(&300_000_000u64.to_be_bytes());
+        chpl.push(0);
+        let chapters = parse_chpl(&chpl).unwrap();
+        assert_eq!(chapters[0].title, "One");
+        assert_eq!(chapters[1].title, "Chapter 2");
+        assert_eq!(chapters[1].start, Duration::from_secs(30));
+
+        // Truncated lists are rejected rather than read past their end
+        assert_eq!(parse_chpl(&chpl[..chpl.len() - 1]), None);
+        assert_eq!(parse_chpl(&[]), None);
+    }
+
+    #[test]
+    fn malformed_boxes() {
+        // A 64-bit size that would overflow the offset
+        let mut huge = 1u32.to_be_bytes().to_vec();
+        huge.extend_from_slice(b"free");
+        huge.extend_from_slice(&u64::MAX.to_be_bytes());
+        huge.extend(mp4_box(b"moov", &[]));
+        let end = huge.len() as u64;
+        assert_eq!(find_box(&mut Cursor::new(huge), 0, end, b"moov"), None);
+
+        // A size smaller than the header
+        let tiny = [0, 0, 0, 4, b'm', b'o', b'o', b'v'];
+        assert_eq!(find_box(&mut Cursor::new(tiny), 0, 8, b"moov"), None);
+
+        // Tables claiming more entries than they hold
+        assert_eq!(sample_starts(&words(&[u32::MAX, 1])), Vec::<u64>::new());
+        assert_eq!(sample_sizes(&words(&[0, u32::MAX, 5])), Some(vec![5]));
+        assert_eq!(
+            sample_offsets(&words(&[1, 1, u32::MAX, 1]), &[10], &[5, 5]),
+            Some(vec![10, 15])
+        );
+        assert_eq!(
+            sample_offsets(&words(&[1, 1, 2, 1]), &[u64::MAX], &[5, 5]),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_id3() {
+        // Tag size beyond the cap
+        let mut tag = b"ID3\x04\x00\x00\x7f\x7f\x7f\x7f".to_vec();
+        tag.extend_from_slice(&[0; 16]);
+        assert_eq!(read_id3_chapters(&mut Cursor::new(tag)), None);
+
+        // A frame whose size runs past the tag is dropped
+        let frames = [&b"CHAP\x7f\x7f\x7f\x7f\0\0"[..], &[0; 10]].concat();
+        assert_eq!(id3_frames(&frames, 4).count(), 0);
+
+        // CHAP frames too short for their times
+        assert_eq!(parse_chap(b"ch1\0\0\0", 4, 0), None);
+    }
+}
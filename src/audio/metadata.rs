@@ -1,12 +1,16 @@
 // src/audio/metadata.rs
 //! Track metadata extraction using Lofty.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use lofty::config::ParseOptions;
 use lofty::file::{AudioFile, TaggedFileExt};
 use lofty::probe::Probe;
-use lofty::tag::ItemKey;
+use lofty::tag::{Accessor, ItemKey};
+
+use super::chapters::{load_chapters, Chapter};
+use crate::playlist::cue::{cue_from_tags, sibling_cue, CueSheet};
 
 /// One metadata entry: raw tag key & value.
 pub type TagEntry = (String, String);
@@ -17,6 +21,8 @@ pub type TagEntry = (String, String);
 pub struct TrackMetadata {
     /// All tag-frame key/value pairs from the primary tag.
     pub tags: Vec<TagEntry>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
     /// Audio properties (bitrate, sample rate, channels, etc.)
     pub properties: Vec<(String, String)>,
     /// Total track length in seconds.
@@ -25,6 +31,57 @@ pub struct TrackMetadata {
     pub lyrics: Option<String>,
     /// Raw image bytes (PNG/JPEG) for artwork, if available.
     pub artwork: Option<Vec<u8>>,
+    /// CUE sheet tracks inside this file (embedded or from a `.cue` next to it).
+    pub cue: Option<CueSheet>,
+    /// Chapter markers (audiobooks, podcasts), ordered by start time.
+    pub chapters: Vec<Chapter>,
+}
+
+/// Typed tag values used for library indexing, browsing, and sorting.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub genre: Option<String>,
+    pub composer: Option<String>,
+    pub year: Option<u32>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    /// Total track length in seconds.
+    pub duration_secs: u64,
+    /// Audio bitrate in kbps, if known.
+    pub bitrate: Option<u32>,
+}
+
+/// Read the typed tags and audio properties of `path`, skipping cover art.
+/// This is safe to call from a background thread.
+pub fn load_tags(path: &Path) -> Result<TrackTags> {
+    let tagged_file = Probe::open(path)?
+        .options(ParseOptions::new().read_cover_art(false))
+        .read()?;
+
+    let props = tagged_file.properties();
+    let mut tags = TrackTags {
+        duration_secs: props.duration().as_secs(),
+        bitrate: props.audio_bitrate(),
+        ..Default::default()
+    };
+
+    if let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) {
+        tags.title = tag.title().map(|s| s.into_owned());
+        tags.artist = tag.artist().map(|s| s.into_owned());
+        tags.album = tag.album().map(|s| s.into_owned());
+        tags.genre = tag.genre().map(|s| s.into_owned());
+        tags.album_artist = tag.get_string(&ItemKey::AlbumArtist).map(str::to_owned);
+        tags.composer = tag.get_string(&ItemKey::Composer).map(str::to_owned);
+        tags.year = tag.year();
+        tags.track_number = tag.track();
+        tags.disc_number = tag.disk();
+    }
+
+    Ok(tags)
 }
 
 /// Load metadata for a file path without touching player state.
@@ -46,6 +103,10 @@ pub fn load_metadata(path: PathBuf) -> Result<TrackMetadata> {
         .primary_tag()
         .and_then(|tag| tag.pictures().first().map(|pic| pic.data().to_vec()));
 
+    let tag = tagged_file.primary_tag().or_else(|| tagged_file.first_tag());
+    let title = tag.and_then(|t| t.title()).map(|s| s.into_owned());
+    let artist = tag.and_then(|t| t.artist()).map(|s| s.into_owned());
+
     // Collect all other tag key/value pairs
     let mut tags = Vec::new();
     if let Some(tag) = tagged_file.primary_tag() {
@@ -68,11 +129,18 @@ pub fn load_metadata(path: PathBuf) -> Result<TrackMetadata> {
     }
     let duration_secs = props.duration().as_secs();
 
+    let cue = cue_from_tags(tagged_file.tags(), &path).or_else(|| sibling_cue(&path));
+    let chapters = load_chapters(&path);
+
     Ok(TrackMetadata {
         tags,
+        title,
+        artist,
         properties,
         duration_secs,
         lyrics,
         artwork,
+        cue,
+        chapters,
     })
 }
@@ -1,12 +1,14 @@
 // src/audio/mod.rs
 //! Audio module - handles all audio playback, metadata, and visualization.
 
+pub mod chapters;
 pub mod metadata;
 pub mod player;
 pub mod sample_capture;
 pub mod visualizer;
 
 // Re-export commonly used types
-pub use metadata::TrackMetadata;
+pub use chapters::Chapter;
+pub use metadata::{TrackMetadata, TrackTags};
 pub use player::MusicPlayer;
 pub use visualizer::Visualizer;
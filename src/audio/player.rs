@@ -3,18 +3,22 @@
 
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{self, Sender};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
 use anyhow::Result;
 use ringbuf::{traits::*, HeapRb};
-use rodio::{Decoder, OutputStream, Sink, Source};
+use rodio::{
+    cpal::traits::HostTrait, DeviceTrait, Decoder, OutputStream, OutputStreamHandle, Sink, Source,
+};
 
 use super::metadata::{load_metadata, TrackMetadata};
 use super::sample_capture::SampleCapture;
+use crate::config::Config;
 
 /// Commands sent to the audio playback thread.
 enum PlayerCommand {
@@ -22,6 +26,8 @@ enum PlayerCommand {
     Pause,
     Resume,
     Stop,
+    Seek(Duration),
+    SetVolume(f32),
 }
 
 /// Simple player that can `play()`, `pause()`, `resume()`, or `stop()` a file,
@@ -32,6 +38,14 @@ pub struct MusicPlayer {
     /// Local flags mirrored from the audio thread for quick UI access
     is_playing_flag: Arc<AtomicBool>,
     is_paused_flag: Arc<AtomicBool>,
+    /// Set by the audio thread when a track plays to its end
+    finished_flag: Arc<AtomicBool>,
+    /// Set by the audio thread when a track can't be opened or decoded
+    error: Arc<Mutex<Option<String>>>,
+    /// Playback position of the current track in milliseconds
+    position_ms: Arc<AtomicU64>,
+    /// Output volume (1.0 is the file's own level)
+    volume: f32,
     /// Most-recent metadata (if any).
     pub metadata: Option<TrackMetadata>,
     /// Shared circular buffer containing recent audio samples for visualization
@@ -39,13 +53,18 @@ pub struct MusicPlayer {
 }
 
 impl MusicPlayer {
-    /// Create an idle player.
-    pub fn new() -> Self {
+    /// Create an idle player at the configured volume.
+    pub fn new(config: &Config) -> Self {
+        let initial_volume = config.audio.volume as f32 / 100.0;
+        let device = config.audio.device.clone();
         // Channel to send commands to audio thread
         let (tx, rx) = mpsc::channel::<PlayerCommand>();
 
         let is_playing_flag = Arc::new(AtomicBool::new(false));
         let is_paused_flag = Arc::new(AtomicBool::new(false));
+        let finished_flag = Arc::new(AtomicBool::new(false));
+        let error = Arc::new(Mutex::new(None));
+        let position_ms = Arc::new(AtomicU64::new(0));
 
         // Create a larger circular buffer for audio samples (16384 samples ~= 372ms at 44.1kHz)
         let sample_buffer = Arc::new(Mutex::new(HeapRb::<f32>::new(16384)));
@@ -53,17 +72,22 @@ impl MusicPlayer {
         // Clone flags for audio thread
         let ap = is_playing_flag.clone();
         let az = is_paused_flag.clone();
+        let af = finished_flag.clone();
+        let aerr = error.clone();
+        let apos = position_ms.clone();
         let sample_buf_clone = sample_buffer.clone();
 
         // Spawn audio thread which owns the OutputStream and handles play/pause/stop
         thread::spawn(move || {
             // Try to create the output stream once
-            let stream_res = OutputStream::try_default();
-            if stream_res.is_err() {
-                // If we can't create audio output, just drain commands until the
+            let stream_res = open_output(device.as_deref());
+            if stream_res.is_none() {
+                // If we can't create audio output, refuse to play until the
                 // sender is dropped, then return.
-                while rx.recv().is_ok() {
-                    // ignore commands
+                while let Ok(cmd) = rx.recv() {
+                    if let PlayerCommand::Play(_) = cmd {
+                        report(&aerr, "no audio output".to_string());
+                    }
                 }
                 return;
             }
@@ -71,14 +95,36 @@ impl MusicPlayer {
             let (stream, handle) = stream_res.unwrap();
             // Current sink (if any)
             let mut sink: Option<Sink> = None;
+            // Volume applied to every new sink
+            let mut volume = initial_volume;
 
-            while let Ok(cmd) = rx.recv() {
+            loop {
+                // Wake up periodically to notice when the current track runs out
+                let cmd = rx.recv_timeout(Duration::from_millis(100));
+                if let Some(s) = &sink {
+                    apos.store(s.get_pos().as_millis() as u64, Ordering::SeqCst);
+                }
+                let cmd = match cmd {
+                    Ok(cmd) => cmd,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if sink.as_ref().is_some_and(|s| s.empty()) {
+                            sink = None;
+                            ap.store(false, Ordering::SeqCst);
+                            az.store(false, Ordering::SeqCst);
+                            af.store(true, Ordering::SeqCst);
+                        }
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
                 match cmd {
                     PlayerCommand::Play(path) => {
                         // Stop previous sink
                         if let Some(s) = sink.take() {
                             s.stop();
                         }
+                        af.store(false, Ordering::SeqCst);
+                        apos.store(0, Ordering::SeqCst);
 
                         // Clear the sample buffer when starting a new track
                         if let Ok(mut buf) = sample_buf_clone.lock() {
@@ -86,20 +132,24 @@ impl MusicPlayer {
                         }
 
                         // Try to create a new sink and queue the file
-                        if let Ok(new_sink) = Sink::try_new(&handle) {
-                            if let Ok(file) = File::open(&path) {
-                                if let Ok(source) = Decoder::new(BufReader::new(file)) {
-                                    // Convert to f32 and wrap with sample capture
-                                    let converted = source.convert_samples::<f32>();
-                                    let capturing =
-                                        SampleCapture::new(converted, sample_buf_clone.clone());
-
-                                    new_sink.append(capturing);
-                                    new_sink.play();
-                                    ap.store(true, Ordering::SeqCst);
-                                    az.store(false, Ordering::SeqCst);
-                                    sink = Some(new_sink);
-                                }
+                        match open_sink(&handle, &path) {
+                            Ok((new_sink, source)) => {
+                                // Convert to f32 and wrap with sample capture
+                                let converted = source.convert_samples::<f32>();
+                                let capturing =
+                                    SampleCapture::new(converted, sample_buf_clone.clone());
+
+                                new_sink.append(capturing);
+                                new_sink.set_volume(volume);
+                                new_sink.play();
+                                ap.store(true, Ordering::SeqCst);
+                                az.store(false, Ordering::SeqCst);
+                                sink = Some(new_sink);
+                            }
+                            Err(e) => {
+                                ap.store(false, Ordering::SeqCst);
+                                az.store(false, Ordering::SeqCst);
+                                report(&aerr, e);
                             }
                         }
                     }
@@ -121,6 +171,21 @@ impl MusicPlayer {
                         }
                         ap.store(false, Ordering::SeqCst);
                         az.store(false, Ordering::SeqCst);
+                        apos.store(0, Ordering::SeqCst);
+                    }
+                    PlayerCommand::Seek(pos) => {
+                        // Formats that can't seek just keep playing where they are
+                        if let Some(s) = &sink
+                            && s.try_seek(pos).is_ok()
+                        {
+                            apos.store(pos.as_millis() as u64, Ordering::SeqCst);
+                        }
+                    }
+                    PlayerCommand::SetVolume(level) => {
+                        volume = level;
+                        if let Some(s) = &sink {
+                            s.set_volume(level);
+                        }
                     }
                 }
             }
@@ -136,17 +201,21 @@ impl MusicPlayer {
             cmd_tx: tx,
             is_playing_flag,
             is_paused_flag,
+            finished_flag,
+            error,
+            position_ms,
+            volume: initial_volume,
             metadata: None,
             sample_buffer,
         }
     }
 
-    /// Stop any existing playback, load metadata, and start playing `path`.
-    pub fn play(&mut self, path: &PathBuf) -> Result<()> {
+    /// Stop any existing playback and start playing `path`. Problems
+    /// opening or decoding it show up later through `take_error`.
+    pub fn play(&mut self, path: &Path) {
         // Send Play command to audio thread and return immediately.
-        let p = path.clone();
+        let p = path.to_path_buf();
         self.cmd_tx.send(PlayerCommand::Play(p)).ok();
-        Ok(())
     }
 
     /// Load metadata for `path` without touching player state. This is safe to call
@@ -170,13 +239,82 @@ impl MusicPlayer {
         let _ = self.cmd_tx.send(PlayerCommand::Stop);
     }
 
+    /// Jump to `position` within the current track.
+    pub fn seek(&mut self, position: Duration) {
+        let _ = self.cmd_tx.send(PlayerCommand::Seek(position));
+    }
+
+    /// Set the output volume, clamped to 0.0..=1.0.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+        let _ = self.cmd_tx.send(PlayerCommand::SetVolume(self.volume));
+    }
+
+    /// Current output volume.
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// Playback position within the current track.
+    pub fn position(&self) -> Duration {
+        Duration::from_millis(self.position_ms.load(Ordering::SeqCst))
+    }
+
     /// Returns true if there's an active sink (i.e. playing or paused).
     pub fn is_playing(&self) -> bool {
         self.is_playing_flag.load(Ordering::SeqCst)
     }
 
+    /// Returns true once after the current track played to its end.
+    pub fn take_finished(&self) -> bool {
+        self.finished_flag.swap(false, Ordering::SeqCst)
+    }
+
+    /// Why the last track couldn't be played, once.
+    pub fn take_error(&self) -> Option<String> {
+        self.error.lock().ok()?.take()
+    }
+
     /// Returns true if playback is currently paused.
     pub fn is_paused(&self) -> bool {
         self.is_paused_flag.load(Ordering::SeqCst)
     }
 }
+
+/// Names of the audio output devices of the default host.
+pub fn output_device_names() -> Vec<String> {
+    rodio::cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// A new sink and the decoded file at `path`, or why it can't be played.
+fn open_sink(
+    handle: &OutputStreamHandle,
+    path: &Path,
+) -> Result<(Sink, Decoder<BufReader<File>>), String> {
+    let sink = Sink::try_new(handle).map_err(|e| e.to_string())?;
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let source = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+    Ok((sink, source))
+}
+
+/// Hand a playback error to the UI thread.
+fn report(error: &Mutex<Option<String>>, message: String) {
+    if let Ok(mut slot) = error.lock() {
+        *slot = Some(message);
+    }
+}
+
+/// Open the output device called `name`, or the default one.
+fn open_output(name: Option<&str>) -> Option<(OutputStream, OutputStreamHandle)> {
+    let Some(name) = name else {
+        return OutputStream::try_default().ok();
+    };
+    let device = rodio::cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|d| d.name().is_ok_and(|n| n == name))?;
+    OutputStream::try_from_device(&device).ok()
+}
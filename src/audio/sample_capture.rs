@@ -61,4 +61,8 @@ where
     fn total_duration(&self) -> Option<std::time::Duration> {
         self.source.total_duration()
     }
+
+    fn try_seek(&mut self, pos: std::time::Duration) -> Result<(), rodio::source::SeekError> {
+        self.source.try_seek(pos)
+    }
 }
@@ -9,6 +9,8 @@ use std::sync::{Arc, Mutex};
 use ratatui::{layout::Rect, Frame};
 use ringbuf::HeapRb;
 
+use crate::config::Config;
+use crate::ui::theme::Theme;
 use fft::FftProcessor;
 use renderer::SpectrumRenderer;
 
@@ -31,17 +33,18 @@ pub struct Visualizer {
 }
 
 impl Visualizer {
-    /// Create a new visualizer with specified number of frequency bands.
-    pub fn new() -> Self {
-        let num_bands = 64; // Display 64 frequency bands
+    /// Create a visualizer with the configured bands and smoothing.
+    pub fn new(config: &Config) -> Self {
+        let settings = &config.visualizer;
+        let num_bands = settings.bands;
         Self {
             fft_processor: FftProcessor::new(num_bands),
-            renderer: SpectrumRenderer::new(),
+            renderer: SpectrumRenderer::new(settings),
             num_bands,
             smoothed_magnitudes: vec![0.0; num_bands],
-            smoothing_factor: 0.70, // Balanced smoothing
+            smoothing_factor: settings.smoothing,
             peak_holds: vec![0.0; num_bands],
-            peak_decay: 0.87, // Balanced decay
+            peak_decay: settings.peak_decay,
         }
     }
 
@@ -92,14 +95,21 @@ impl Visualizer {
     }
 
     /// Render the frequency spectrum as mirrored bars (CAVA-style).
-    pub fn render(&self, f: &mut Frame<'_>, area: Rect) {
+    pub fn render(&self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
         self.renderer
-            .render(f, area, &self.smoothed_magnitudes, self.num_bands);
+            .render(f, area, &self.smoothed_magnitudes, self.num_bands, theme);
+    }
+
+    /// Render the spectrum as a single row of bars, without a border.
+    pub fn render_line(&self, f: &mut Frame<'_>, area: Rect, theme: &Theme) {
+        let bands = self.num_bands.min(self.smoothed_magnitudes.len());
+        self.renderer
+            .render_line(f, area, &self.smoothed_magnitudes[..bands], theme);
     }
 }
 
 impl Default for Visualizer {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
@@ -1,12 +1,10 @@
 // src/audio/visualizer/renderer.rs
 //! Spectrum bar rendering for the visualizer.
 
-use ratatui::{
-    layout::Rect,
-    style::{Color, Style},
-    widgets::{Block, Borders, Paragraph},
-    Frame,
-};
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+use crate::config::VisualizerConfig;
+use crate::ui::theme::Theme;
 
 /// Renderer for spectrum visualization bars.
 pub struct SpectrumRenderer {
@@ -19,11 +17,11 @@ pub struct SpectrumRenderer {
 }
 
 impl SpectrumRenderer {
-    /// Create a new spectrum renderer with default settings.
-    pub fn new() -> Self {
+    /// Create a spectrum renderer with the configured bar sizes.
+    pub fn new(config: &VisualizerConfig) -> Self {
         Self {
-            bar_width: 2,
-            bar_gap: 1,
+            bar_width: config.bar_width,
+            bar_gap: config.bar_gap,
             chars: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█', '█', '█'],
         }
     }
@@ -35,20 +33,37 @@ impl SpectrumRenderer {
         area: Rect,
         magnitudes: &[f32],
         num_bands: usize,
+        theme: &Theme,
     ) {
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title("4: Spectrum Visualizer (FFT)");
+        let block = theme.block("4: Spectrum Visualizer (FFT)");
 
         let inner = block.inner(area);
 
         // Render custom mirrored visualization
-        self.render_mirrored(f, inner, magnitudes, num_bands);
+        self.render_mirrored(f, inner, magnitudes, num_bands, theme);
 
         // Render the border
         f.render_widget(block, area);
     }
 
+    /// Render one row of bars, low bands on the left, spreading the bands
+    /// over the width; the levels are drawn with eighth blocks, silence
+    /// with the lowest one like the full view.
+    pub fn render_line(&self, f: &mut Frame<'_>, area: Rect, magnitudes: &[f32], theme: &Theme) {
+        const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let width = area.width as usize;
+        if width == 0 || magnitudes.is_empty() {
+            return;
+        }
+        let line: String = (0..width)
+            .map(|x| {
+                let magnitude = magnitudes[x * magnitudes.len() / width].clamp(0.0, 1.0);
+                LEVELS[(magnitude * 7.0).round() as usize]
+            })
+            .collect();
+        f.render_widget(Paragraph::new(line).style(theme.styles.spectrum), area);
+    }
+
     /// Render mirrored bars like CAVA (symmetric around center).
     fn render_mirrored(
         &self,
@@ -56,6 +71,7 @@ impl SpectrumRenderer {
         area: Rect,
         magnitudes: &[f32],
         num_bands: usize,
+        theme: &Theme,
     ) {
         if area.height < 2 || area.width < 2 {
             return;
@@ -141,7 +157,7 @@ impl SpectrumRenderer {
         }
 
         // Render the entire visualization as a single widget to ensure proper clearing
-        let paragraph = Paragraph::new(full_content).style(Style::default().fg(Color::White));
+        let paragraph = Paragraph::new(full_content).style(theme.styles.spectrum);
         f.render_widget(paragraph, area);
     }
 
@@ -191,6 +207,6 @@ impl SpectrumRenderer {
 
 impl Default for SpectrumRenderer {
     fn default() -> Self {
-        Self::new()
+        Self::new(&VisualizerConfig::default())
     }
 }
@@ -0,0 +1,240 @@
+// src/cli.rs
+//! Command-line arguments, checked before the terminal enters raw mode.
+
+use std::{
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+
+use crate::{
+    app::queue::RepeatMode,
+    audio::player::output_device_names,
+    config::{self, Config},
+    fs::{detection::detect_file_type, for_each_audio_file, FileCategory},
+    playlist::{load_playlist, PlaylistFormat},
+};
+
+/// A terminal music player.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Audio files, directories or playlists to play right away
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+    /// Directory to browse at startup
+    #[arg(short, long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+    /// Play in random order
+    #[arg(short, long)]
+    pub shuffle: bool,
+    /// Repeat mode
+    #[arg(short, long, value_enum)]
+    pub repeat: Option<RepeatMode>,
+    /// Config file to use instead of the default one
+    #[arg(short, long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Audio output device (see --list-outputs)
+    #[arg(short, long, value_name = "NAME")]
+    pub output: Option<String>,
+    /// List the audio output devices and exit
+    #[arg(long)]
+    pub list_outputs: bool,
+    /// Initial volume in percent
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub volume: Option<u8>,
+}
+
+/// What the command line asks the app to do once it is up.
+#[derive(Debug, Default, Clone)]
+pub struct Startup {
+    /// Directory to browse instead of the configured or saved one
+    pub dir: Option<PathBuf>,
+    /// Tracks to queue and play
+    pub tracks: Vec<PathBuf>,
+    /// Turn shuffle on
+    pub shuffle: bool,
+    /// Repeat mode overriding the saved one
+    pub repeat: Option<RepeatMode>,
+    /// Volume in percent overriding the saved one
+    pub volume: Option<u8>,
+}
+
+impl Cli {
+    /// Load the config and check every argument, applying the overrides.
+    pub fn resolve(self) -> Result<(Config, Startup)> {
+        let mut config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => config::load()?,
+        };
+        if let Some(volume) = self.volume {
+            config.audio.volume = volume;
+        }
+        if self.output.is_some() {
+            config.audio.device = self.output;
+        }
+        if let Some(name) = &config.audio.device {
+            let names = output_device_names();
+            if !names.contains(name) {
+                bail!(
+                    "unknown output device \"{}\" (available: {})",
+                    name,
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
+                    }
+                );
+            }
+        }
+
+        if let Some(dir) = &self.dir
+            && !dir.is_dir()
+        {
+            bail!("not a directory: {}", dir.display());
+        }
+        let mut tracks = Vec::new();
+        for path in &self.paths {
+            tracks.extend(tracks_of(path)?);
+        }
+        if !self.paths.is_empty() && tracks.is_empty() {
+            bail!("no audio files found to play");
+        }
+
+        Ok((
+            config,
+            Startup {
+                dir: self.dir,
+                tracks,
+                shuffle: self.shuffle,
+                repeat: self.repeat,
+                volume: self.volume,
+            },
+        ))
+    }
+}
+
+/// Tracks named by a path: an audio file, the audio files below a
+/// directory (in path order) or the entries of a playlist.
+pub fn tracks_of(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.exists() {
+        bail!("no such file or directory: {}", path.display());
+    }
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("can't open {}", path.display()))?;
+    if path.is_dir() {
+        let mut tracks = Vec::new();
+        let _ = for_each_audio_file(&path, |track| {
+            tracks.push(track);
+            ControlFlow::Continue(())
+        });
+        tracks.sort();
+        return Ok(tracks);
+    }
+    if PlaylistFormat::from_path(&path).is_some() {
+        let entries = load_playlist(&path)
+            .with_context(|| format!("can't read playlist {}", path.display()))?;
+        let total = entries.len();
+        let tracks: Vec<_> = entries
+            .into_iter()
+            .filter(|e| !e.missing)
+            .map(|e| e.path)
+            .collect();
+        if total > 0 && tracks.is_empty() {
+            bail!("no playable tracks in playlist {}", path.display());
+        }
+        return Ok(tracks);
+    }
+    let kind = detect_file_type(&path).with_context(|| format!("can't read {}", path.display()))?;
+    if kind.category != FileCategory::Audio {
+        bail!("not an audio file or playlist: {}", path.display());
+    }
+    Ok(vec![path])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// A fresh scratch directory for one test.
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("chirpy-cli-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_paths_and_non_audio_files_are_rejected() {
+        let dir = scratch("reject");
+        assert!(tracks_of(&dir.join("nope.mp3")).is_err());
+        let text = dir.join("notes.txt");
+        fs::write(&text, "hello").unwrap();
+        let err = tracks_of(&text).unwrap_err().to_string();
+        assert!(err.starts_with("not an audio file"), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn directories_yield_their_audio_files_in_order() {
+        let dir = scratch("dir");
+        assert!(tracks_of(&dir).unwrap().is_empty());
+        fs::write(dir.join("b.mp3"), "").unwrap();
+        fs::write(dir.join("a.flac"), "").unwrap();
+        fs::write(dir.join("cover.txt"), "").unwrap();
+        let names: Vec<_> = tracks_of(&dir)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["a.flac", "b.mp3"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_playlist_entries_are_left_out() {
+        let dir = scratch("playlist");
+        fs::write(dir.join("here.mp3"), "").unwrap();
+        let list = dir.join("list.m3u");
+        fs::write(&list, "gone.mp3\nhere.mp3\n").unwrap();
+        let tracks = tracks_of(&list).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert!(tracks[0].ends_with("here.mp3"));
+
+        fs::write(&list, "gone.mp3\n").unwrap();
+        let err = tracks_of(&list).unwrap_err().to_string();
+        assert!(err.starts_with("no playable tracks"), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolve_checks_the_startup_directory() {
+        let dir = scratch("resolve");
+        let config = dir.join("config.toml");
+        fs::write(&config, "").unwrap();
+        let cli = |args: &[&str]| {
+            let mut argv = vec!["chirpy", "--config", config.to_str().unwrap()];
+            argv.extend(args);
+            Cli::parse_from(argv)
+        };
+
+        let missing = dir.join("nowhere");
+        let err = cli(&["--dir", missing.to_str().unwrap()])
+            .resolve()
+            .unwrap_err();
+        assert!(err.to_string().starts_with("not a directory"), "{err}");
+
+        let (_, startup) = cli(&["--dir", dir.to_str().unwrap()]).resolve().unwrap();
+        assert_eq!(startup.dir.as_deref(), Some(dir.as_path()));
+        assert!(startup.tracks.is_empty());
+
+        let err = cli(&[dir.to_str().unwrap()]).resolve().unwrap_err();
+        assert_eq!(err.to_string(), "no audio files found to play");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,579 @@
 // src/config/mod.rs
-//! Configuration module for user settings, themes, and keybindings.
+//! Configuration module - user settings loaded from
+//! `$XDG_CONFIG_HOME/chirpy/config.toml`.
 //!
-//! This module is a placeholder for future configuration support.
+//! A documented default file is written on first run. Every key is optional;
+//! out-of-range values are rejected with the line they appear on.
 
-// TODO: Add configuration structs for:
-// - User preferences (default directory, etc.)
-// - Theme settings (colors, symbols)
-// - Keybinding customization
-// - Visualizer settings (smoothing, bands, etc.)
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+
+use crate::ui::{
+    icons::IconSet,
+    keymap::Keymap,
+    layout::{
+        default_layout, sized_layout, LayoutNode, SectionVisibility, DEFAULT_VISUALIZER_HEIGHT,
+        DEFAULT_WIDTHS,
+    },
+    theme::{bundled_names, BorderKind, Palette, StyleSpec, Styles},
+};
+
+/// The documented default configuration file.
+pub const DEFAULT_CONFIG: &str = include_str!("default.toml");
+
+/// Copies one setting from a parsed config into the running one.
+type CopySetting = fn(&mut Config, Config);
+
+/// Settings `:set` can change while chirpy runs.
+const SETTINGS: &[(&str, CopySetting)] = &[
+    ("general.resume_min_minutes", |c, n| {
+        c.general.resume_min_minutes = n.general.resume_min_minutes
+    }),
+    ("audio.volume_step", |c, n| c.audio.volume_step = n.audio.volume_step),
+    ("visualizer.bands", |c, n| c.visualizer.bands = n.visualizer.bands),
+    ("visualizer.smoothing", |c, n| c.visualizer.smoothing = n.visualizer.smoothing),
+    ("visualizer.peak_decay", |c, n| c.visualizer.peak_decay = n.visualizer.peak_decay),
+    ("visualizer.bar_width", |c, n| c.visualizer.bar_width = n.visualizer.bar_width),
+    ("visualizer.bar_gap", |c, n| c.visualizer.bar_gap = n.visualizer.bar_gap),
+    ("layout.show_files", |c, n| c.layout.show_files = n.layout.show_files),
+    ("layout.show_player", |c, n| c.layout.show_player = n.layout.show_player),
+    ("layout.show_artwork", |c, n| c.layout.show_artwork = n.layout.show_artwork),
+    ("layout.show_visualizer", |c, n| c.layout.show_visualizer = n.layout.show_visualizer),
+    ("layout.stack_below", |c, n| c.layout.stack_below = n.layout.stack_below),
+    ("layout.mini_below", |c, n| c.layout.mini_below = n.layout.mini_below),
+    ("layout.root", |c, n| c.layout.root = n.layout.root),
+    ("theme.name", |c, n| c.theme.name = n.theme.name),
+    ("theme.border", |c, n| c.theme.border = n.theme.border),
+    ("theme.icons", |c, n| c.theme.icons = n.theme.icons),
+    ("theme.highlight_symbol", |c, n| c.theme.highlight_symbol = n.theme.highlight_symbol),
+];
+
+/// All user settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub general: GeneralConfig,
+    pub library: LibraryConfig,
+    pub audio: AudioConfig,
+    pub visualizer: VisualizerConfig,
+    pub layout: LayoutConfig,
+    pub theme: ThemeConfig,
+    pub keys: Keymap,
+}
+
+/// Startup and session behaviour.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneralConfig {
+    /// Directory to open at startup, overriding the session's
+    #[serde(deserialize_with = "path")]
+    pub start_dir: Option<PathBuf>,
+    /// Reopen the last directory, queue, modes and panels
+    pub restore_session: bool,
+    /// Reopen the last track, paused at its position
+    pub restore_playback: bool,
+    /// Shortest track length (minutes) whose position is remembered
+    pub resume_min_minutes: u64,
+    /// Capture mouse clicks and scrolling
+    pub mouse: bool,
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Self {
+            start_dir: None,
+            restore_session: true,
+            restore_playback: true,
+            resume_min_minutes: 20,
+            mouse: true,
+        }
+    }
+}
+
+/// Library scanning.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LibraryConfig {
+    /// Music roots; empty means the XDG music directory
+    #[serde(deserialize_with = "paths")]
+    pub roots: Vec<PathBuf>,
+}
+
+/// Playback settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioConfig {
+    /// Volume without a saved session, in percent
+    #[serde(deserialize_with = "percent")]
+    pub volume: u8,
+    /// Volume change of one key press, in percent
+    #[serde(deserialize_with = "percent")]
+    pub volume_step: u8,
+    /// Output device name; the system default when unset
+    pub device: Option<String>,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            volume_step: 5,
+            device: None,
+        }
+    }
+}
+
+/// Spectrum visualizer settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VisualizerConfig {
+    /// Number of frequency bands
+    #[serde(deserialize_with = "bands")]
+    pub bands: usize,
+    /// Smoothing factor of bar movement
+    #[serde(deserialize_with = "factor")]
+    pub smoothing: f32,
+    /// Decay factor of peak holds
+    #[serde(deserialize_with = "factor")]
+    pub peak_decay: f32,
+    /// Bar width in cells
+    #[serde(deserialize_with = "bar_size")]
+    pub bar_width: usize,
+    /// Gap between bars in cells
+    pub bar_gap: usize,
+}
+
+impl Default for VisualizerConfig {
+    fn default() -> Self {
+        Self {
+            bands: 64,
+            smoothing: 0.70,
+            peak_decay: 0.87,
+            bar_width: 2,
+            bar_gap: 1,
+        }
+    }
+}
+
+/// Panel layout settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    pub show_files: bool,
+    pub show_player: bool,
+    pub show_artwork: bool,
+    pub show_visualizer: bool,
+    /// Terminal width (columns) below which the panels are stacked
+    pub stack_below: u16,
+    /// Terminal height (rows) below which the mini-player is shown
+    pub mini_below: u16,
+    /// Arrangement and sizes of the panels
+    #[serde(deserialize_with = "layout_root")]
+    pub root: LayoutNode,
+    /// Sizes from before `root`, still read from older configs. They
+    /// resize the default arrangement unless `root` replaces it.
+    #[serde(deserialize_with = "weight")]
+    pub files_width: Option<u16>,
+    #[serde(deserialize_with = "weight")]
+    pub player_width: Option<u16>,
+    #[serde(deserialize_with = "weight")]
+    pub artwork_width: Option<u16>,
+    #[serde(deserialize_with = "visualizer_height")]
+    pub visualizer_height: Option<u16>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            show_files: true,
+            show_player: true,
+            show_artwork: true,
+            show_visualizer: true,
+            stack_below: 60,
+            mini_below: 12,
+            root: default_layout(),
+            files_width: None,
+            player_width: None,
+            artwork_width: None,
+            visualizer_height: None,
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Panels shown at startup.
+    pub fn visibility(&self) -> SectionVisibility {
+        SectionVisibility {
+            files: self.show_files,
+            player: self.show_player,
+            artwork: self.show_artwork,
+            visualizer: self.show_visualizer,
+        }
+    }
+
+    /// Turn the old size settings into the layout tree, if they are set
+    /// and the tree is the default one.
+    fn apply_old_sizes(&mut self) {
+        let widths = [self.files_width, self.player_width, self.artwork_width];
+        if (widths.iter().all(Option::is_none) && self.visualizer_height.is_none())
+            || self.root != default_layout()
+        {
+            return;
+        }
+        let widths = [0, 1, 2].map(|i| widths[i].unwrap_or(DEFAULT_WIDTHS[i]));
+        let height = self.visualizer_height.unwrap_or(DEFAULT_VISUALIZER_HEIGHT);
+        self.root = sized_layout(widths, height);
+    }
+}
+
+/// Look of the UI. The bundled themes are written in this form too.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// Bundled theme to start from
+    #[serde(deserialize_with = "theme_name")]
+    pub name: String,
+    /// Panel border type
+    pub border: Option<BorderKind>,
+    /// Nerd Font or ASCII glyphs
+    pub icons: Option<IconSet>,
+    /// Prefix of the highlighted list row
+    pub highlight_symbol: Option<String>,
+    /// Palette colors replaced
+    #[serde(deserialize_with = "palette")]
+    pub palette: BTreeMap<String, Color>,
+    /// Widget styles replaced
+    #[serde(deserialize_with = "styles")]
+    pub styles: BTreeMap<String, StyleSpec>,
+    /// Old spelling of `palette.progress`, still read from older configs
+    #[serde(deserialize_with = "legacy_color")]
+    pub progress: Option<Color>,
+    /// Old spelling of `palette.spectrum`, still read from older configs
+    #[serde(deserialize_with = "legacy_color")]
+    pub spectrum: Option<Color>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            border: None,
+            icons: None,
+            highlight_symbol: None,
+            palette: BTreeMap::new(),
+            styles: BTreeMap::new(),
+            progress: None,
+            spectrum: None,
+        }
+    }
+}
+
+impl Config {
+    /// Parse configuration text. Errors name the offending line.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut config: Config = toml::from_str(text)?;
+        config.layout.apply_old_sizes();
+        Ok(config)
+    }
+
+    /// Change the setting `key` (e.g. `visualizer.bands`) to `value`, which
+    /// is checked like in the config file. Strings may be left unquoted.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let Some((_, copy)) = SETTINGS.iter().find(|(k, _)| *k == key) else {
+            bail!("unknown setting \"{}\"", key);
+        };
+        let (section, name) = key.split_once('.').unwrap_or_default();
+        let value = if toml::from_str::<toml::Table>(&format!("v = {}", value)).is_ok() {
+            value.to_string()
+        } else {
+            toml::Value::String(value.to_string()).to_string()
+        };
+        let parsed: Config = toml::from_str(&format!("[{}]\n{} = {}", section, name, value))
+            .map_err(|e| anyhow!("{}: {}", key, e.message().trim()))?;
+        copy(self, parsed);
+        Ok(())
+    }
+
+    /// Read the configuration at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("can't read config file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    /// Read the configuration at `path`, first writing the documented
+    /// default there if the file doesn't exist yet.
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            // A read-only config directory shouldn't keep chirpy from starting
+            let written = path
+                .parent()
+                .is_none_or(|dir| fs::create_dir_all(dir).is_ok())
+                && fs::write(path, DEFAULT_CONFIG).is_ok();
+            if !written {
+                return Ok(Self::default());
+            }
+        }
+        Self::load(path)
+    }
+}
+
+/// Default location of the config file (`$XDG_CONFIG_HOME/chirpy/config.toml`).
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("chirpy").join("config.toml"))
+}
+
+/// Load the config from its default location, or use the defaults when
+/// there is no config directory.
+pub fn load() -> Result<Config> {
+    match default_config_path() {
+        Some(path) => Config::load_or_create(&path),
+        None => Ok(Config::default()),
+    }
+}
+
+/// Names of the settings `Config::set` accepts.
+pub fn setting_names() -> impl Iterator<Item = &'static str> {
+    SETTINGS.iter().map(|(key, _)| *key)
+}
+
+/// Expand a leading `~` to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Deserialize a number and check it against `range`.
+fn in_range<'de, D, T>(d: D, range: std::ops::RangeInclusive<T>, what: &str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + PartialOrd + Display,
+{
+    let value = T::deserialize(d)?;
+    if range.contains(&value) {
+        Ok(value)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "{} must be between {} and {}, got {}",
+            what,
+            range.start(),
+            range.end(),
+            value
+        )))
+    }
+}
+
+fn percent<'de, D: Deserializer<'de>>(d: D) -> Result<u8, D::Error> {
+    in_range(d, 0..=100, "a percentage")
+}
+
+fn bands<'de, D: Deserializer<'de>>(d: D) -> Result<usize, D::Error> {
+    in_range(d, 8..=256, "the number of bands")
+}
+
+fn factor<'de, D: Deserializer<'de>>(d: D) -> Result<f32, D::Error> {
+    in_range(d, 0.0..=0.99, "the factor")
+}
+
+fn bar_size<'de, D: Deserializer<'de>>(d: D) -> Result<usize, D::Error> {
+    in_range(d, 1..=8, "the bar width")
+}
+
+fn weight<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u16>, D::Error> {
+    in_range(d, 1..=100, "a column width").map(Some)
+}
+
+fn visualizer_height<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u16>, D::Error> {
+    in_range(d, 5..=80, "the visualizer height").map(Some)
+}
+
+fn layout_root<'de, D: Deserializer<'de>>(d: D) -> Result<LayoutNode, D::Error> {
+    let root = LayoutNode::deserialize(d)?;
+    let panes = root.panes();
+    for (i, pane) in panes.iter().enumerate() {
+        if panes[..i].contains(pane) {
+            return Err(serde::de::Error::custom(format!(
+                "the {} pane appears more than once",
+                pane
+            )));
+        }
+    }
+    Ok(root)
+}
+
+fn path<'de, D: Deserializer<'de>>(d: D) -> Result<Option<PathBuf>, D::Error> {
+    Ok(Some(expand_home(&String::deserialize(d)?)))
+}
+
+fn paths<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<PathBuf>, D::Error> {
+    Ok(Vec::<String>::deserialize(d)?
+        .iter()
+        .map(|p| expand_home(p))
+        .collect())
+}
+
+fn theme_name<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let name = String::deserialize(d)?;
+    let names = bundled_names();
+    if names.contains(&name.as_str()) {
+        Ok(name)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "unknown theme \"{}\", expected one of: {}",
+            name,
+            names.join(", ")
+        )))
+    }
+}
+
+fn palette<'de, D: Deserializer<'de>>(d: D) -> Result<BTreeMap<String, Color>, D::Error> {
+    let mut palette = BTreeMap::new();
+    for (name, value) in BTreeMap::<String, String>::deserialize(d)? {
+        if !Palette::NAMES.contains(&name.as_str()) {
+            return Err(serde::de::Error::custom(format!(
+                "unknown palette color \"{}\", expected one of: {}",
+                name,
+                Palette::NAMES.join(", ")
+            )));
+        }
+        palette.insert(name, parse_color(&value)?);
+    }
+    Ok(palette)
+}
+
+fn legacy_color<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Color>, D::Error> {
+    parse_color(&String::deserialize(d)?).map(Some)
+}
+
+fn parse_color<E: serde::de::Error>(value: &str) -> Result<Color, E> {
+    Color::from_str(value).map_err(|_| {
+        E::custom(format!(
+            "unknown color \"{}\" (use a name like \"cyan\", \"#rrggbb\" or 0-255)",
+            value
+        ))
+    })
+}
+
+fn styles<'de, D: Deserializer<'de>>(d: D) -> Result<BTreeMap<String, StyleSpec>, D::Error> {
+    let styles = BTreeMap::<String, StyleSpec>::deserialize(d)?;
+    if let Some(name) = styles.keys().find(|n| !Styles::NAMES.contains(&n.as_str())) {
+        return Err(serde::de::Error::custom(format!(
+            "unknown style \"{}\", expected one of: {}",
+            name,
+            Styles::NAMES.join(", ")
+        )));
+    }
+    Ok(styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{layout::Pane, theme::Theme};
+
+    /// Error message of parsing `text`.
+    fn error(text: &str) -> String {
+        format!("{:#}", Config::parse(text).unwrap_err())
+    }
+
+    #[test]
+    fn default_file_parses() {
+        Config::parse(DEFAULT_CONFIG).unwrap();
+        Config::parse("").unwrap();
+    }
+
+    #[test]
+    fn out_of_range_values_name_the_range_and_line() {
+        let message = error("[audio]\nvolume = 101\n");
+        assert!(
+            message.contains("a percentage must be between 0 and 100, got 101"),
+            "{}",
+            message
+        );
+        assert!(message.contains("line 2"), "{}", message);
+
+        assert!(error("[visualizer]\nbands = 7").contains("between 8 and 256"));
+        assert!(error("[visualizer]\nbands = 257").contains("between 8 and 256"));
+        assert!(error("[visualizer]\nsmoothing = 1.0").contains("between 0 and 0.99"));
+        assert!(error("[visualizer]\nbar_width = 0").contains("between 1 and 8"));
+    }
+
+    #[test]
+    fn bounds_are_inclusive() {
+        let config = Config::parse(
+            "[audio]\nvolume = 0\nvolume_step = 100\n[visualizer]\nbands = 256\nsmoothing = 0.99",
+        )
+        .unwrap();
+        assert_eq!(config.audio.volume, 0);
+        assert_eq!(config.visualizer.bands, 256);
+    }
+
+    #[test]
+    fn malformed_values() {
+        // Negative and oversized numbers, wrong types and unknown keys
+        assert!(Config::parse("[audio]\nvolume = -1").is_err());
+        assert!(Config::parse("[audio]\nvolume = 99999999999999999999").is_err());
+        assert!(Config::parse("[audio]\nvolume = \"loud\"").is_err());
+        assert!(error("[audio]\nvolumes = 5").contains("unknown field"));
+        assert!(error("[theme]\nname = \"nope\"").contains("unknown theme"));
+        assert!(error("[theme.palette]\naccent = \"not a color\"").contains("unknown color"));
+        assert!(error("[theme]\nprogress = \"not a color\"").contains("unknown color"));
+    }
+
+    #[test]
+    fn old_theme_colors_are_read() {
+        let palette = |text| Theme::from_config(&Config::parse(text).unwrap().theme).palette;
+        let old = palette("[theme]\nprogress = \"green\"\nspectrum = \"#102030\"");
+        assert_eq!(old.progress, Color::Green);
+        assert_eq!(old.spectrum, Color::Rgb(0x10, 0x20, 0x30));
+
+        // They replace the bundled theme's colors, but not the config's palette
+        let bundled = palette("[theme]\nname = \"gruvbox\"\nprogress = \"green\"");
+        assert_eq!(bundled.progress, Color::Green);
+        let both = palette("[theme]\nprogress = \"green\"\n[theme.palette]\nprogress = \"red\"");
+        assert_eq!(both.progress, Color::Red);
+    }
+
+    #[test]
+    fn old_layout_sizes_are_read() {
+        let config = Config::parse("[layout]\nfiles_width = 1\nplayer_width = 1").unwrap();
+        assert_eq!(config.layout.root, sized_layout([1, 1, 28], 20));
+        let config = Config::parse("[layout]\nvisualizer_height = 40").unwrap();
+        assert_eq!(config.layout.root, sized_layout(DEFAULT_WIDTHS, 40));
+        assert!(error("[layout]\nvisualizer_height = 90").contains("between 5 and 80"));
+
+        // A layout tree wins over them
+        let config =
+            Config::parse("[layout]\nfiles_width = 50\n[layout.root]\npane = \"player\"").unwrap();
+        assert_eq!(config.layout.root.panes(), [Pane::Player]);
+    }
+
+    #[test]
+    fn set_checks_ranges() {
+        let mut config = Config::default();
+        config.set("visualizer.bands", "96").unwrap();
+        assert_eq!(config.visualizer.bands, 96);
+
+        let message = config
+            .set("visualizer.bands", "1000")
+            .unwrap_err()
+            .to_string();
+        assert!(message.starts_with("visualizer.bands: "), "{}", message);
+        assert_eq!(config.visualizer.bands, 96);
+        assert!(config.set("audio.volume_step", "x").is_err());
+        assert!(config.set("audio.nope", "1").is_err());
+    }
+}
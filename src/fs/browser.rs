@@ -2,11 +2,15 @@
 //! Directory browsing and file listing functionality.
 
 use std::{
-    fs,
-    path::{Component, Path, PathBuf},
+    fs, io,
+    path::{Component, Path},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use super::detection::{detect_file_type, FileCategory};
+use super::detection::{detect_file_type, guess_category, FileCategory};
+use super::sort::{sort_entries, SortSpec};
+use crate::audio::{metadata::load_tags, TrackTags};
+use crate::library::{LibraryIndex, ResumePoint};
 
 /// Returns the last `n` components of `path` joined by `/`.
 /// If the path has fewer than `n` components, returns the full path.
@@ -38,37 +42,185 @@ pub fn tail_path(path: &Path, n: usize) -> String {
     }
 }
 
-/// Load **only** directories and audio files from `dir`.
-/// Returns a Vec of (name, is_dir, category, mime).
-pub fn load_entries(dir: &PathBuf) -> Vec<(String, bool, FileCategory, String)> {
-    let mut list = fs::read_dir(dir)
-        .unwrap() // you might replace this with `?` and a Result in real code
+/// One entry of a directory listing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirEntry {
+    /// File name
+    pub name: String,
+    /// True for directories (including symlinks to directories)
+    pub is_dir: bool,
+    /// Detected category (placeholder `Binary` for readable directories)
+    pub category: FileCategory,
+    /// Detected MIME type (empty for directories)
+    pub mime: String,
+    /// File size in bytes (files only)
+    pub size: Option<u64>,
+    /// Last modification time
+    pub modified: Option<SystemTime>,
+    /// True if the entry itself is a symbolic link
+    pub is_symlink: bool,
+    /// True for symbolic links whose target doesn't exist
+    pub is_broken_link: bool,
+    /// Cached tags and audio properties (audio files, once refined)
+    pub tags: Option<TrackTags>,
+    /// Remembered playback position (long audio files, once refined)
+    pub resume: Option<ResumePoint>,
+}
+
+impl DirEntry {
+    /// True for playable audio files.
+    pub fn is_audio(&self) -> bool {
+        !self.is_dir && self.category == FileCategory::Audio
+    }
+
+    /// True for playlist files.
+    pub fn is_playlist(&self) -> bool {
+        !self.is_dir && self.category == FileCategory::Playlist
+    }
+
+    /// Track length in seconds, if known.
+    pub fn duration_secs(&self) -> Option<u64> {
+        self.tags.as_ref().map(|t| t.duration_secs)
+    }
+
+    /// Audio bitrate in kbps, if known.
+    pub fn bitrate(&self) -> Option<u32> {
+        self.tags.as_ref().and_then(|t| t.bitrate)
+    }
+}
+
+/// Which entries a listing includes beyond directories and audio files.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListOptions {
+    /// Include dot-files and dot-directories
+    pub show_hidden: bool,
+    /// Include files of every type, not just audio
+    pub show_all: bool,
+}
+
+/// Load **only** directories, audio files and playlists from `dir`.
+/// Returns the entries, or the error that kept `dir` from being read.
+/// Children that can't be inspected are listed with
+/// `FileCategory::Inaccessible` instead of being dropped.
+///
+/// This sniffs every file and can be slow; the browser uses `DirLoad` instead.
+pub fn load_entries(dir: &Path) -> io::Result<Vec<DirEntry>> {
+    let mut list = fs::read_dir(dir)?
         .filter_map(Result::ok)
-        .filter_map(|e| {
-            let path = e.path();
-            let name = e.file_name().to_string_lossy().into_owned();
-
-            // Skip hidden files and folders (those starting with a dot)
-            if name.starts_with('.') {
-                return None;
-            }
-
-            if path.is_dir() {
-                // Always include directories so we can navigate into them
-                Some((name, true, FileCategory::Binary, String::new()))
-            } else {
-                // Only include if it's an audio file
-                match detect_file_type(&path) {
-                    Ok(ft) if ft.category == FileCategory::Audio => {
-                        Some((name, false, ft.category, ft.mime))
-                    }
-                    _ => None, // skip non-audio files
-                }
-            }
-        })
+        .filter_map(|e| quick_entry(&e, ListOptions::default()))
+        .map(|entry| refine_entry(dir, entry))
+        .filter(|entry| is_listed(entry, ListOptions::default()))
         .collect::<Vec<_>>();
 
-    // Sort alphabetically
-    list.sort_by_key(|(n, _, _, _)| n.to_lowercase());
-    list
+    sort_entries(&mut list, SortSpec::default());
+    Ok(list)
+}
+
+/// Cheap first look at a directory entry using only its name and file
+/// metadata. Hidden entries are skipped unless `options` asks for them;
+/// files get an extension-based category.
+pub fn quick_entry(e: &fs::DirEntry, options: ListOptions) -> Option<DirEntry> {
+    let path = e.path();
+    let name = e.file_name().to_string_lossy().into_owned();
+
+    // Skip hidden files and folders (those starting with a dot)
+    if !options.show_hidden && name.starts_with('.') {
+        return None;
+    }
+
+    let is_symlink = e.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+    // Follows symlinks, so a link to a directory browses like one
+    let meta = fs::metadata(&path).ok();
+    let is_dir = meta.as_ref().is_some_and(|m| m.is_dir());
+
+    let (category, mime) = if is_dir {
+        (FileCategory::Binary, String::new())
+    } else {
+        let mime = mime_guess::from_path(&path)
+            .first_or_octet_stream()
+            .to_string();
+        (guess_category(&path), mime)
+    };
+
+    Some(DirEntry {
+        name,
+        is_dir,
+        category,
+        mime,
+        size: meta.as_ref().filter(|m| m.is_file()).map(|m| m.len()),
+        modified: meta.as_ref().and_then(|m| m.modified().ok()),
+        is_symlink,
+        is_broken_link: is_symlink && meta.is_none(),
+        tags: None,
+        resume: None,
+    })
+}
+
+/// Accurate category and MIME type of an entry of `dir`, sniffing file contents.
+/// Files that can't be read keep their extension-based guess.
+pub fn refine_entry(dir: &Path, mut entry: DirEntry) -> DirEntry {
+    let path = dir.join(&entry.name);
+    if entry.is_dir {
+        entry.category = if fs::read_dir(&path).is_ok() {
+            FileCategory::Binary
+        } else {
+            FileCategory::Inaccessible
+        };
+        return entry;
+    }
+    match detect_file_type(&path) {
+        Ok(ft) => {
+            entry.category = ft.category;
+            entry.mime = ft.mime;
+        }
+        // Unreadable files are kept if they look like audio by name
+        Err(_) if entry.category == FileCategory::Audio => {
+            entry.category = FileCategory::Inaccessible;
+        }
+        Err(_) => {}
+    }
+    entry
+}
+
+/// Fill in tags and audio properties for an audio entry of `dir`, reusing the
+/// library index when it has an up-to-date record of the file, along with
+/// its remembered playback position.
+pub fn load_entry_tags(dir: &Path, entry: &mut DirEntry, index: Option<&LibraryIndex>) {
+    if !entry.is_audio() {
+        return;
+    }
+    let path = dir.join(&entry.name);
+    let mtime = entry
+        .modified
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    entry.resume = index.and_then(|index| index.resume_point(&path).ok().flatten());
+    let cached = index
+        .and_then(|index| index.get(&path).ok().flatten())
+        .filter(|t| Some(t.mtime) == mtime && Some(t.size) == entry.size);
+    entry.tags = match cached {
+        Some(track) => Some(track.tags),
+        None => load_tags(&path).ok(),
+    };
+}
+
+/// True if an entry belongs in the browser: directories, audio, playlists
+/// and anything we couldn't inspect, or everything with `show_all`.
+pub fn is_listed(entry: &DirEntry, options: ListOptions) -> bool {
+    options.show_all
+        || entry.is_dir
+        || matches!(
+            entry.category,
+            FileCategory::Audio | FileCategory::Playlist | FileCategory::Inaccessible
+        )
+}
+
+/// Short, user-facing description of an I/O error (e.g. "Permission denied").
+pub fn describe_io_error(err: &io::Error) -> String {
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => "Permission denied".to_string(),
+        io::ErrorKind::NotFound => "Directory not found".to_string(),
+        io::ErrorKind::NotADirectory => "Not a directory".to_string(),
+        _ => err.to_string(),
+    }
 }
@@ -7,6 +7,8 @@ use anyhow::Result;
 use infer::{Infer, MatcherType};
 use mime_guess::MimeGuess;
 
+use crate::playlist::PlaylistFormat;
+
 /// High-level file categories.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum FileCategory {
@@ -15,6 +17,10 @@ pub enum FileCategory {
     Video,
     Document,
     Binary,
+    /// M3U, PLS or XSPF playlist
+    Playlist,
+    /// Exists but can't be read (e.g. permission denied)
+    Inaccessible,
 }
 
 impl fmt::Display for FileCategory {
@@ -25,6 +31,8 @@ impl fmt::Display for FileCategory {
             FileCategory::Video => "Video",
             FileCategory::Document => "Document",
             FileCategory::Binary => "Binary",
+            FileCategory::Playlist => "Playlist",
+            FileCategory::Inaccessible => "Inaccessible",
         };
         write!(f, "{}", s)
     }
@@ -39,6 +47,12 @@ pub struct FileType {
 
 /// Detect MIME type & category for a given file path.
 pub fn detect_file_type(path: &Path) -> Result<FileType> {
+    // Playlists are text files recognised by extension alone
+    if PlaylistFormat::from_path(path).is_some() {
+        let mime = MimeGuess::from_path(path).first_or_octet_stream().to_string();
+        return Ok(FileType { mime, category: FileCategory::Playlist });
+    }
+
     // 1. Try magic-number sniffing
     if let Some(kind) = Infer::new().get_from_path(path)? {
         let mime = kind.mime_type().to_string();
@@ -58,14 +72,27 @@ pub fn detect_file_type(path: &Path) -> Result<FileType> {
         .to_string();
 
     // 3. Map top-level type to category
-    let category = match mime.split('/').next().unwrap_or("application") {
+    let category = category_for_mime(&mime);
+
+    Ok(FileType { mime, category })
+}
+
+/// Cheap extension-only guess of a file's category; never touches the file.
+pub fn guess_category(path: &Path) -> FileCategory {
+    if PlaylistFormat::from_path(path).is_some() {
+        return FileCategory::Playlist;
+    }
+    category_for_mime(MimeGuess::from_path(path).first_or_octet_stream().essence_str())
+}
+
+/// Map the top-level MIME type to a category.
+fn category_for_mime(mime: &str) -> FileCategory {
+    match mime.split('/').next().unwrap_or("application") {
         "image" => FileCategory::Image,
         "audio" => FileCategory::Audio,
         "video" => FileCategory::Video,
         "text" => FileCategory::Document,
         "application" => FileCategory::Document,
         _ => FileCategory::Binary,
-    };
-
-    Ok(FileType { mime, category })
+    }
 }
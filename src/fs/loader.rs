@@ -0,0 +1,124 @@
+// src/fs/loader.rs
+//! Background, streaming directory loading.
+//!
+//! Names are listed first using cheap extension checks, then each entry is
+//! refined by sniffing its contents and reading audio tags (from the library
+//! index when it is up to date). Dropping a `DirLoad` cancels the work.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::browser::{is_listed, load_entry_tags, quick_entry, refine_entry, DirEntry, ListOptions};
+use crate::library::LibraryIndex;
+
+/// Longest time refined entries are held back before being sent.
+const REFINE_FLUSH: Duration = Duration::from_millis(50);
+
+/// Progress of a directory load.
+#[derive(Debug)]
+pub enum LoadEvent {
+    /// Initial listing based on names only.
+    Listed(Vec<DirEntry>),
+    /// Refined entries, by name, with whether each should be shown.
+    Refined(Vec<(DirEntry, bool)>),
+    /// Every entry has been refined.
+    Done,
+    /// The directory couldn't be read.
+    Failed(io::Error),
+}
+
+/// A directory load running on a background thread.
+pub struct DirLoad {
+    /// Directory being loaded
+    pub dir: PathBuf,
+    rx: Receiver<LoadEvent>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl DirLoad {
+    /// Start loading `dir` with the entries selected by `options`, taking
+    /// cached tags from the index at `index_path`.
+    pub fn spawn(dir: PathBuf, options: ListOptions, index_path: Option<PathBuf>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        let worker_dir = dir.clone();
+        thread::spawn(move || {
+            let index = index_path.and_then(|p| LibraryIndex::open(&p).ok());
+            load(&worker_dir, options, index.as_ref(), &tx, &flag)
+        });
+        Self { dir, rx, cancel }
+    }
+
+    /// Next pending event, if any. Returns `None` when nothing is ready.
+    pub fn try_next(&self) -> Option<LoadEvent> {
+        match self.rx.try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) => None,
+            // The worker only hangs up after `Done`/`Failed` or on cancellation
+            Err(TryRecvError::Disconnected) => Some(LoadEvent::Done),
+        }
+    }
+}
+
+impl Drop for DirLoad {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Worker body: list, then refine.
+fn load(
+    dir: &Path,
+    options: ListOptions,
+    index: Option<&LibraryIndex>,
+    tx: &Sender<LoadEvent>,
+    cancel: &AtomicBool,
+) {
+    let read = match fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) => {
+            let _ = tx.send(LoadEvent::Failed(e));
+            return;
+        }
+    };
+
+    let all: Vec<DirEntry> = read
+        .filter_map(Result::ok)
+        .filter_map(|e| quick_entry(&e, options))
+        .collect();
+    let listed = all.iter().filter(|e| is_listed(e, options)).cloned().collect();
+    if tx.send(LoadEvent::Listed(listed)).is_err() {
+        return;
+    }
+
+    let mut batch = Vec::new();
+    let mut last_flush = Instant::now();
+    for entry in all {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut refined = refine_entry(dir, entry);
+        load_entry_tags(dir, &mut refined, index);
+        let shown = is_listed(&refined, options);
+        batch.push((refined, shown));
+
+        if last_flush.elapsed() >= REFINE_FLUSH {
+            if tx.send(LoadEvent::Refined(std::mem::take(&mut batch))).is_err() {
+                return;
+            }
+            last_flush = Instant::now();
+        }
+    }
+    let _ = tx.send(LoadEvent::Refined(batch));
+    let _ = tx.send(LoadEvent::Done);
+}
@@ -3,7 +3,14 @@
 
 pub mod browser;
 pub mod detection;
+pub mod loader;
+pub mod sort;
+pub mod walk;
+pub mod watcher;
 
 // Re-export commonly used types
-pub use browser::{load_entries, tail_path};
+pub use browser::{describe_io_error, load_entries, tail_path, DirEntry, ListOptions};
+pub use loader::{DirLoad, LoadEvent};
+pub use sort::{sort_entries, SortKey, SortSpec};
 pub use detection::FileCategory;
+pub use walk::{for_each_audio_file, walk_audio_files};
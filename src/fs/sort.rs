@@ -0,0 +1,182 @@
+// src/fs/sort.rs
+//! Sort orders for directory listings.
+
+use std::{cmp::Ordering, fmt};
+
+use super::browser::DirEntry;
+
+/// Property entries are ordered by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Natural name order ("2 - x" before "10 - x")
+    #[default]
+    Name,
+    /// Modification time
+    Modified,
+    /// File size
+    Size,
+    /// Track number from tags
+    TrackNumber,
+    /// Track length
+    Duration,
+}
+
+impl SortKey {
+    /// The key after this one, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::Modified,
+            SortKey::Modified => SortKey::Size,
+            SortKey::Size => SortKey::TrackNumber,
+            SortKey::TrackNumber => SortKey::Duration,
+            SortKey::Duration => SortKey::Name,
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortKey::Name => "name",
+            SortKey::Modified => "modified",
+            SortKey::Size => "size",
+            SortKey::TrackNumber => "track",
+            SortKey::Duration => "duration",
+        })
+    }
+}
+
+/// A complete sort order for one directory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SortSpec {
+    /// Primary sort key
+    pub key: SortKey,
+    /// Reverse the order of `key`
+    pub descending: bool,
+    /// List directories before files instead of interleaving them
+    pub dirs_first: bool,
+}
+
+impl fmt::Display for SortSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.key, if self.descending { "↓" } else { "↑" })
+    }
+}
+
+/// Sort `list` by `spec`. Entries missing the key's value (e.g. untagged files
+/// when sorting by track number) go last in either direction; ties fall back
+/// to natural name order.
+pub fn sort_entries(list: &mut [DirEntry], spec: SortSpec) {
+    list.sort_by(|a, b| {
+        if spec.dirs_first && a.is_dir != b.is_dir {
+            return b.is_dir.cmp(&a.is_dir);
+        }
+        let by_key = match spec.key {
+            SortKey::Name => Some(natural_cmp(&a.name, &b.name)),
+            SortKey::Modified => cmp_present(a.modified, b.modified),
+            SortKey::Size => cmp_present(a.size, b.size),
+            SortKey::TrackNumber => cmp_present(
+                a.tags.as_ref().and_then(|t| t.track_number),
+                b.tags.as_ref().and_then(|t| t.track_number),
+            ),
+            SortKey::Duration => cmp_present(a.duration_secs(), b.duration_secs()),
+        };
+        match by_key {
+            Some(ord) if spec.descending => ord.reverse(),
+            Some(ord) => ord,
+            None => lacks_key(a, spec.key).cmp(&lacks_key(b, spec.key)),
+        }
+        .then_with(|| natural_cmp(&a.name, &b.name))
+    });
+}
+
+/// Compare two optional values, or `None` if either is missing.
+fn cmp_present<T: Ord>(a: Option<T>, b: Option<T>) -> Option<Ordering> {
+    Some(a?.cmp(&b?))
+}
+
+/// True if `entry` has no value for `key`.
+fn lacks_key(entry: &DirEntry, key: SortKey) -> bool {
+    match key {
+        SortKey::Name => false,
+        SortKey::Modified => entry.modified.is_none(),
+        SortKey::Size => entry.size.is_none(),
+        SortKey::TrackNumber => entry.tags.as_ref().and_then(|t| t.track_number).is_none(),
+        SortKey::Duration => entry.duration_secs().is_none(),
+    }
+}
+
+/// Case-insensitive comparison treating runs of digits as numbers. Names
+/// that only differ in case or leading zeros fall back to byte order, so
+/// distinct names never compare equal.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    loose_cmp(a, b).then_with(|| a.cmp(b))
+}
+
+/// [`natural_cmp`] without the final tie-break.
+fn loose_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let na = take_number(&mut a);
+                let nb = take_number(&mut b);
+                // Longer digit runs (without leading zeros) are larger numbers
+                let ord = na.len().cmp(&nb.len()).then_with(|| na.cmp(&nb));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ord = x.to_lowercase().cmp(y.to_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Consume a run of digits, returning it without leading zeros.
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        if !(digits.is_empty() && c == '0') {
+            digits.push(c);
+        }
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(natural_cmp("2 - x", "10 - x"), Ordering::Less);
+        assert_eq!(natural_cmp("track 9", "Track 10"), Ordering::Less);
+        let mut names = vec!["10 - x", "2 - x", "1 - x"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["1 - x", "2 - x", "10 - x"]);
+    }
+
+    #[test]
+    fn distinct_names_never_tie() {
+        assert_ne!(natural_cmp("a01", "a1"), Ordering::Equal);
+        assert_ne!(natural_cmp("Abc", "abc"), Ordering::Equal);
+        assert_eq!(natural_cmp("a01", "a1"), natural_cmp("a1", "a01").reverse());
+        assert_eq!(natural_cmp("abc", "abc"), Ordering::Equal);
+    }
+
+    #[test]
+    fn directories_are_interleaved_by_default() {
+        assert!(!SortSpec::default().dirs_first);
+    }
+}
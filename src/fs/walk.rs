@@ -0,0 +1,56 @@
+// src/fs/walk.rs
+//! Recursive discovery of audio files below a root directory.
+
+use std::{
+    fs, io,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use super::detection::{guess_category, FileCategory};
+
+/// Call `visit` for every audio file (by extension) below `root`, skipping
+/// hidden entries and not following directory symlinks. Stops early when
+/// `visit` returns `ControlFlow::Break`.
+pub fn for_each_audio_file(
+    root: &Path,
+    visit: impl FnMut(PathBuf) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    walk_audio_files(root, visit, |_, _| {})
+}
+
+/// Like [`for_each_audio_file`], but report every directory (including
+/// `root`) that couldn't be listed to `unreadable` instead of skipping it
+/// silently.
+pub fn walk_audio_files(
+    root: &Path,
+    mut visit: impl FnMut(PathBuf) -> ControlFlow<()>,
+    mut unreadable: impl FnMut(&Path, io::Error),
+) -> ControlFlow<()> {
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let read = match fs::read_dir(&dir) {
+            Ok(read) => read,
+            Err(e) => {
+                unreadable(&dir, e);
+                continue;
+            }
+        };
+        for entry in read.filter_map(Result::ok) {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if path.is_file() && guess_category(&path) == FileCategory::Audio {
+                visit(path)?;
+            }
+        }
+    }
+    ControlFlow::Continue(())
+}
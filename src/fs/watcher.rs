@@ -0,0 +1,124 @@
+// src/fs/watcher.rs
+//! Debounced filesystem notifications for the browsed directory and library roots.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Quiet period after the last event before changes are reported.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Changes reported by `DirWatcher::poll` once events have settled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WatchChanges {
+    /// The watched directory's listing changed (or the directory itself went away)
+    pub dir_changed: bool,
+    /// Something changed below one of the library roots
+    pub library_changed: bool,
+}
+
+impl WatchChanges {
+    /// True if anything changed.
+    pub fn any(&self) -> bool {
+        self.dir_changed || self.library_changed
+    }
+}
+
+/// Watches the current directory (non-recursively) and the library roots (recursively).
+pub struct DirWatcher {
+    watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<Event>>,
+    /// Directory currently watched for listing changes
+    dir: Option<PathBuf>,
+    /// Whether `dir` has a watch of its own, rather than being covered by a root
+    owns_dir_watch: bool,
+    /// Library roots watched recursively
+    roots: Vec<PathBuf>,
+    /// Changes accumulated since the last report
+    pending: WatchChanges,
+    /// Time of the most recent relevant event
+    last_event: Option<Instant>,
+}
+
+impl DirWatcher {
+    /// Create a watcher over the given library roots.
+    pub fn new(roots: &[PathBuf]) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        })?;
+        let roots: Vec<PathBuf> = roots
+            .iter()
+            .filter(|root| watcher.watch(root, RecursiveMode::Recursive).is_ok())
+            .cloned()
+            .collect();
+        Ok(Self {
+            watcher,
+            rx,
+            dir: None,
+            owns_dir_watch: false,
+            roots,
+            pending: WatchChanges::default(),
+            last_event: None,
+        })
+    }
+
+    /// Switch the watched directory to `dir`. Directories inside a library
+    /// root are already covered by the root's recursive watch and get no
+    /// watch of their own: with inotify both would share one watch
+    /// descriptor, so replacing or removing it would drop the root's watch.
+    pub fn watch_dir(&mut self, dir: &Path) {
+        if self.dir.as_deref() == Some(dir) {
+            return;
+        }
+        if let Some(old) = self.dir.take()
+            && std::mem::take(&mut self.owns_dir_watch)
+        {
+            let _ = self.watcher.unwatch(&old);
+        }
+        if self.roots.iter().any(|root| dir.starts_with(root)) {
+            self.dir = Some(dir.to_path_buf());
+        } else if self.watcher.watch(dir, RecursiveMode::NonRecursive).is_ok() {
+            self.dir = Some(dir.to_path_buf());
+            self.owns_dir_watch = true;
+        }
+    }
+
+    /// Drain pending notifications. Returns the accumulated changes once no
+    /// new event has arrived for the debounce period.
+    pub fn poll(&mut self) -> WatchChanges {
+        while let Ok(res) = self.rx.try_recv() {
+            let Ok(event) = res else {
+                continue;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                if let Some(dir) = &self.dir
+                    && (path.parent() == Some(dir.as_path()) || path == dir)
+                {
+                    self.pending.dir_changed = true;
+                    self.last_event = Some(Instant::now());
+                }
+                if self.roots.iter().any(|root| path.starts_with(root)) {
+                    self.pending.library_changed = true;
+                    self.last_event = Some(Instant::now());
+                }
+            }
+        }
+
+        match self.last_event {
+            Some(t) if t.elapsed() >= DEBOUNCE => {
+                self.last_event = None;
+                std::mem::take(&mut self.pending)
+            }
+            _ => WatchChanges::default(),
+        }
+    }
+}
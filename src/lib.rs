@@ -5,6 +5,10 @@
 
 pub mod app;
 pub mod audio;
+pub mod cli;
 pub mod config;
 pub mod fs;
+pub mod library;
+pub mod playlist;
+pub mod search;
 pub mod ui;
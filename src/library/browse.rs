@@ -0,0 +1,110 @@
+// src/library/browse.rs
+//! Tag-based browse hierarchies over the library index.
+
+use std::fmt;
+
+/// Browser modes: the raw directory tree or one of the tag hierarchies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrowseMode {
+    #[default]
+    Files,
+    Artists,
+    AlbumArtists,
+    Genres,
+    Years,
+    Composers,
+}
+
+impl BrowseMode {
+    /// All modes in cycling order.
+    pub const ALL: [BrowseMode; 6] = [
+        BrowseMode::Files,
+        BrowseMode::Artists,
+        BrowseMode::AlbumArtists,
+        BrowseMode::Genres,
+        BrowseMode::Years,
+        BrowseMode::Composers,
+    ];
+
+    /// The mode after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Grouping levels walked before reaching the track list.
+    pub fn levels(self) -> &'static [Field] {
+        match self {
+            BrowseMode::Files => &[],
+            BrowseMode::Artists => &[Field::Artist, Field::Album],
+            BrowseMode::AlbumArtists => &[Field::AlbumArtist, Field::Album],
+            BrowseMode::Genres => &[Field::Genre, Field::Album],
+            BrowseMode::Years => &[Field::Decade, Field::Year, Field::Album],
+            BrowseMode::Composers => &[Field::Composer, Field::Album],
+        }
+    }
+}
+
+impl fmt::Display for BrowseMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            BrowseMode::Files => "Files",
+            BrowseMode::Artists => "Artists",
+            BrowseMode::AlbumArtists => "Album Artists",
+            BrowseMode::Genres => "Genres",
+            BrowseMode::Years => "Years",
+            BrowseMode::Composers => "Composers",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A tag field that tracks can be grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Artist,
+    AlbumArtist,
+    Album,
+    Genre,
+    Year,
+    Decade,
+    Composer,
+}
+
+impl Field {
+    /// SQL expression yielding the field as text (NULL when the tag is missing).
+    pub(crate) fn sql_expr(self) -> &'static str {
+        match self {
+            Field::Artist => "artist",
+            Field::AlbumArtist => "COALESCE(album_artist, artist)",
+            Field::Album => "album",
+            Field::Genre => "genre",
+            Field::Year => "CAST(year AS TEXT)",
+            Field::Decade => "CAST((year / 10) * 10 AS TEXT)",
+            Field::Composer => "composer",
+        }
+    }
+
+    /// Label shown for tracks missing this tag.
+    pub fn unknown_label(self) -> &'static str {
+        match self {
+            Field::Artist | Field::AlbumArtist => "Unknown Artist",
+            Field::Album => "Unknown Album",
+            Field::Genre => "Unknown Genre",
+            Field::Year | Field::Decade => "Unknown Year",
+            Field::Composer => "Unknown Composer",
+        }
+    }
+
+    /// Format a group value for display.
+    pub fn display(self, value: Option<&str>) -> String {
+        match (self, value) {
+            (_, None) => self.unknown_label().to_string(),
+            (Field::Decade, Some(v)) => format!("{}s", v),
+            (_, Some(v)) => v.to_string(),
+        }
+    }
+}
+
+/// Conjunction of field constraints; `None` matches tracks missing the tag.
+pub type BrowseFilter = Vec<(Field, Option<String>)>;
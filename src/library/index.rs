@@ -0,0 +1,360 @@
+// src/library/index.rs
+//! SQLite-backed storage for the library index.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::browse::{BrowseFilter, Field};
+use super::resume::ResumePoint;
+use super::track::Track;
+use crate::audio::TrackTags;
+
+/// Columns selected by every track query, in `row_to_track` order.
+const TRACK_COLUMNS: &str = "path, mtime, size, title, artist, album, album_artist, genre, \
+     composer, year, track_number, disc_number, duration_secs, bitrate";
+
+/// Persistent index of tracks and their tags.
+pub struct LibraryIndex {
+    conn: Connection,
+}
+
+impl LibraryIndex {
+    /// Open (or create) the index database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        // Path prefix queries rely on LIKE being case sensitive
+        conn.pragma_update(None, "case_sensitive_like", true)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tracks (
+                path          TEXT PRIMARY KEY,
+                mtime         INTEGER NOT NULL,
+                size          INTEGER NOT NULL,
+                title         TEXT,
+                artist        TEXT,
+                album         TEXT,
+                album_artist  TEXT,
+                genre         TEXT,
+                composer      TEXT,
+                year          INTEGER,
+                track_number  INTEGER,
+                disc_number   INTEGER,
+                duration_secs INTEGER NOT NULL DEFAULT 0,
+                bitrate       INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS tracks_artist ON tracks(artist);
+            CREATE INDEX IF NOT EXISTS tracks_album ON tracks(album);
+            CREATE TABLE IF NOT EXISTS resume_points (
+                path          TEXT PRIMARY KEY,
+                position_ms   INTEGER NOT NULL,
+                duration_secs INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Number of indexed tracks.
+    pub fn track_count(&self) -> Result<usize> {
+        let n: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM tracks", [], |r| r.get(0))?;
+        Ok(n as usize)
+    }
+
+    /// Look up a single track by path.
+    pub fn get(&self, path: &Path) -> Result<Option<Track>> {
+        let sql = format!("SELECT {TRACK_COLUMNS} FROM tracks WHERE path = ?1");
+        Ok(self
+            .conn
+            .query_row(&sql, params![path_key(path)], row_to_track)
+            .optional()?)
+    }
+
+    /// All indexed tracks, ordered by path.
+    pub fn all_tracks(&self) -> Result<Vec<Track>> {
+        self.query_tracks(
+            &format!("SELECT {TRACK_COLUMNS} FROM tracks ORDER BY path"),
+            [],
+        )
+    }
+
+    /// All indexed tracks located below `dir`, ordered by path.
+    pub fn tracks_under(&self, dir: &Path) -> Result<Vec<Track>> {
+        let sql = format!(
+            "SELECT {TRACK_COLUMNS} FROM tracks WHERE path LIKE ?1 ESCAPE '\\' ORDER BY path"
+        );
+        self.query_tracks(&sql, params![prefix_pattern(dir)])
+    }
+
+    /// Distinct values of `field` among tracks matching `filter`.
+    /// `None` stands for tracks without that tag and sorts last.
+    pub fn group_values(&self, field: Field, filter: &BrowseFilter) -> Result<Vec<Option<String>>> {
+        let (where_sql, args) = filter_clause(filter);
+        let expr = field.sql_expr();
+        // Albums follow release order; everything else is alphabetical
+        let order = if field == Field::Album {
+            format!("MIN(year), {expr} COLLATE NOCASE")
+        } else {
+            format!("{expr} COLLATE NOCASE")
+        };
+        let sql = format!(
+            "SELECT {expr} AS v FROM tracks {where_sql} GROUP BY v \
+             ORDER BY v IS NULL, {order}"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(args), |r| r.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Tracks matching `filter`, in album order (album, disc, track number, path).
+    pub fn tracks_matching(&self, filter: &BrowseFilter) -> Result<Vec<Track>> {
+        let (where_sql, args) = filter_clause(filter);
+        let sql = format!(
+            "SELECT {TRACK_COLUMNS} FROM tracks {where_sql} \
+             ORDER BY album COLLATE NOCASE, COALESCE(disc_number, 0), \
+             COALESCE(track_number, 0), path"
+        );
+        self.query_tracks(&sql, rusqlite::params_from_iter(args))
+    }
+
+    /// Map of path -> (mtime, size) for every track below `root`, used to
+    /// decide which files need re-reading during an incremental scan.
+    pub fn fingerprints_under(&self, root: &Path) -> Result<HashMap<PathBuf, (i64, u64)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, mtime, size FROM tracks WHERE path LIKE ?1 ESCAPE '\\'")?;
+        let rows = stmt.query_map(params![prefix_pattern(root)], |r| {
+            Ok((
+                PathBuf::from(r.get::<_, String>(0)?),
+                (r.get::<_, i64>(1)?, r.get::<_, i64>(2)? as u64),
+            ))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Insert or replace a batch of tracks in one transaction.
+    pub fn upsert_tracks(&mut self, tracks: &[Track]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO tracks (path, mtime, size, title, artist, album, \
+                 album_artist, genre, composer, year, track_number, disc_number, \
+                 duration_secs, bitrate) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            )?;
+            for t in tracks {
+                stmt.execute(params![
+                    path_key(&t.path),
+                    t.mtime,
+                    t.size as i64,
+                    t.tags.title,
+                    t.tags.artist,
+                    t.tags.album,
+                    t.tags.album_artist,
+                    t.tags.genre,
+                    t.tags.composer,
+                    t.tags.year,
+                    t.tags.track_number,
+                    t.tags.disc_number,
+                    t.tags.duration_secs as i64,
+                    t.tags.bitrate,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Remove a batch of tracks by path in one transaction.
+    pub fn remove_tracks(&mut self, paths: &[PathBuf]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("DELETE FROM tracks WHERE path = ?1")?;
+            for p in paths {
+                stmt.execute(params![path_key(p)])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Remembered playback position of `path`, if any.
+    pub fn resume_point(&self, path: &Path) -> Result<Option<ResumePoint>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT position_ms, duration_secs FROM resume_points WHERE path = ?1",
+                params![path_key(path)],
+                |r| {
+                    Ok(ResumePoint {
+                        position: Duration::from_millis(r.get::<_, i64>(0)? as u64),
+                        duration_secs: r.get::<_, i64>(1)? as u64,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Remember `point` for `path`, or forget the position of `path` with `None`.
+    pub fn set_resume_point(&self, path: &Path, point: Option<ResumePoint>) -> Result<()> {
+        match point {
+            Some(point) => self.conn.execute(
+                "INSERT OR REPLACE INTO resume_points (path, position_ms, duration_secs) \
+                 VALUES (?1, ?2, ?3)",
+                params![
+                    path_key(path),
+                    point.position.as_millis() as i64,
+                    point.duration_secs as i64
+                ],
+            )?,
+            None => self.conn.execute(
+                "DELETE FROM resume_points WHERE path = ?1",
+                params![path_key(path)],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// Run a track query and collect the rows.
+    fn query_tracks<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<Track>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, row_to_track)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// Convert a row selected with `TRACK_COLUMNS` into a `Track`.
+fn row_to_track(r: &Row<'_>) -> rusqlite::Result<Track> {
+    Ok(Track {
+        path: PathBuf::from(r.get::<_, String>(0)?),
+        mtime: r.get(1)?,
+        size: r.get::<_, i64>(2)? as u64,
+        tags: TrackTags {
+            title: r.get(3)?,
+            artist: r.get(4)?,
+            album: r.get(5)?,
+            album_artist: r.get(6)?,
+            genre: r.get(7)?,
+            composer: r.get(8)?,
+            year: r.get(9)?,
+            track_number: r.get(10)?,
+            disc_number: r.get(11)?,
+            duration_secs: r.get::<_, i64>(12)? as u64,
+            bitrate: r.get(13)?,
+        },
+    })
+}
+
+/// Build a `WHERE` clause and its arguments from a browse filter.
+fn filter_clause(filter: &BrowseFilter) -> (String, Vec<String>) {
+    if filter.is_empty() {
+        return (String::new(), Vec::new());
+    }
+    let mut conds = Vec::new();
+    let mut args = Vec::new();
+    for (field, value) in filter {
+        match value {
+            Some(v) => {
+                args.push(v.clone());
+                conds.push(format!("{} = ?{}", field.sql_expr(), args.len()));
+            }
+            None => conds.push(format!("{} IS NULL", field.sql_expr())),
+        }
+    }
+    (format!("WHERE {}", conds.join(" AND ")), args)
+}
+
+/// Text key used to store a path.
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// `LIKE` pattern matching every path below `dir`.
+fn prefix_pattern(dir: &Path) -> String {
+    let mut prefix = path_key(dir);
+    if !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+    let escaped = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("{escaped}%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            mtime: 0,
+            size: 0,
+            tags: TrackTags::default(),
+        }
+    }
+
+    fn paths(tracks: Vec<Track>) -> Vec<PathBuf> {
+        tracks.into_iter().map(|t| t.path).collect()
+    }
+
+    #[test]
+    fn prefix_pattern_escapes_like_wildcards() {
+        assert_eq!(prefix_pattern(Path::new("/music")), "/music/%");
+        assert_eq!(prefix_pattern(Path::new("/music/")), "/music/%");
+        assert_eq!(
+            prefix_pattern(Path::new("/a_b/100%/c\\d")),
+            "/a\\_b/100\\%/c\\\\d/%"
+        );
+    }
+
+    #[test]
+    fn wildcards_in_directory_names_match_literally() {
+        let dir = std::env::temp_dir().join(format!("chirpy-index-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut index = LibraryIndex::open(&dir.join("library.db")).unwrap();
+        index
+            .upsert_tracks(&[
+                track("/m/a_b/1.mp3"),
+                track("/m/axb/2.mp3"),
+                track("/m/100%/3.mp3"),
+                track("/m/100 more/4.mp3"),
+                track("/m/c\\d/5.mp3"),
+                track("/m/c\\\\d/6.mp3"),
+                track("/m/A_b/7.mp3"),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            paths(index.tracks_under(Path::new("/m/a_b")).unwrap()),
+            [PathBuf::from("/m/a_b/1.mp3")]
+        );
+        assert_eq!(
+            paths(index.tracks_under(Path::new("/m/100%")).unwrap()),
+            [PathBuf::from("/m/100%/3.mp3")]
+        );
+        assert_eq!(
+            paths(index.tracks_under(Path::new("/m/c\\d")).unwrap()),
+            [PathBuf::from("/m/c\\d/5.mp3")]
+        );
+        assert_eq!(
+            index.fingerprints_under(Path::new("/m/a_b")).unwrap().len(),
+            1
+        );
+        assert_eq!(index.fingerprints_under(Path::new("/m")).unwrap().len(), 7);
+        drop(index);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+// src/library/mod.rs
+//! Library module - persistent index of the tracks found under the music roots.
+//!
+//! Roots are scanned in the background into an SQLite database so the UI can
+//! query tracks and their tags without touching the filesystem.
+
+pub mod browse;
+pub mod index;
+pub mod resume;
+pub mod scanner;
+pub mod track;
+
+use std::path::PathBuf;
+
+// Re-export commonly used types
+pub use browse::{BrowseFilter, BrowseMode, Field};
+pub use index::LibraryIndex;
+pub use resume::ResumePoint;
+pub use scanner::{spawn_scan, ScanEvent};
+pub use track::Track;
+
+/// Default location of the library database (`$XDG_DATA_HOME/chirpy/library.db`).
+pub fn default_index_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("chirpy").join("library.db"))
+}
+
+/// Default music roots: the user's XDG music directory, if it exists.
+pub fn default_roots() -> Vec<PathBuf> {
+    dirs::audio_dir().filter(|d| d.is_dir()).into_iter().collect()
+}
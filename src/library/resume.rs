@@ -0,0 +1,34 @@
+// src/library/resume.rs
+//! Remembered playback positions of long tracks (audiobooks, DJ sets).
+
+use std::time::Duration;
+
+/// Positions this close to either end of a track count as "not started"
+/// or "finished" and are forgotten instead.
+const MARGIN: Duration = Duration::from_secs(15);
+
+/// Where playback of a track stopped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResumePoint {
+    /// Playback position
+    pub position: Duration,
+    /// Track length in seconds when the position was saved
+    pub duration_secs: u64,
+}
+
+impl ResumePoint {
+    /// Resume point for `position` in a track of `duration_secs`, or `None`
+    /// when the position is too close to the start or the end to keep.
+    pub fn new(position: Duration, duration_secs: u64) -> Option<Self> {
+        let end = Duration::from_secs(duration_secs);
+        (position >= MARGIN && position + MARGIN < end).then_some(Self {
+            position,
+            duration_secs,
+        })
+    }
+
+    /// Share of the track already played, between 0 and 1.
+    pub fn fraction(&self) -> f64 {
+        (self.position.as_secs_f64() / self.duration_secs.max(1) as f64).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,215 @@
+// src/library/scanner.rs
+//! Background, incremental scanning of music roots into the index.
+
+use std::{
+    collections::HashSet,
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::UNIX_EPOCH,
+};
+
+use anyhow::Result;
+
+use super::{index::LibraryIndex, track::Track};
+use crate::audio::metadata::load_tags;
+use crate::fs::walk_audio_files;
+
+/// Number of changed tracks written to the index per transaction.
+const BATCH_SIZE: usize = 200;
+
+/// Progress reports sent from the scanner thread to the UI.
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    /// Periodic progress: audio files seen so far and how many were (re)indexed.
+    Progress { scanned: usize, updated: usize },
+    /// Scan completed.
+    Finished {
+        scanned: usize,
+        updated: usize,
+        removed: usize,
+    },
+    /// Scan aborted with an error.
+    Failed(String),
+}
+
+/// Scan `roots` into the index at `db_path` on a background thread.
+/// Only files whose mtime or size changed since the last scan are re-read.
+pub fn spawn_scan(db_path: PathBuf, roots: Vec<PathBuf>) -> Receiver<ScanEvent> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        if let Err(e) = scan(&db_path, &roots, &tx) {
+            let _ = tx.send(ScanEvent::Failed(e.to_string()));
+        }
+    });
+    rx
+}
+
+/// Scan every root, then report totals.
+fn scan(db_path: &Path, roots: &[PathBuf], tx: &Sender<ScanEvent>) -> Result<()> {
+    // The scanner owns its own connection; SQLite connections aren't shared across threads.
+    let mut index = LibraryIndex::open(db_path)?;
+    let mut stats = ScanStats::default();
+
+    for root in roots {
+        scan_root(&mut index, root, tx, &mut stats)?;
+    }
+
+    let _ = tx.send(ScanEvent::Finished {
+        scanned: stats.scanned,
+        updated: stats.updated,
+        removed: stats.removed,
+    });
+    Ok(())
+}
+
+/// Running totals for one scan.
+#[derive(Default)]
+struct ScanStats {
+    scanned: usize,
+    updated: usize,
+    removed: usize,
+}
+
+/// Incrementally index a single root. Tracks are only dropped from the
+/// index when their directory could be listed, so an unmounted or
+/// unreadable root keeps its tracks (and their resume points).
+fn scan_root(
+    index: &mut LibraryIndex,
+    root: &Path,
+    tx: &Sender<ScanEvent>,
+    stats: &mut ScanStats,
+) -> Result<()> {
+    if !root.is_dir() {
+        return Ok(());
+    }
+    let known = index.fingerprints_under(root)?;
+    let mut seen = HashSet::new();
+    let mut batch = Vec::new();
+
+    let mut unreadable = Vec::new();
+    let mut result = Ok(());
+    let _ = walk_audio_files(
+        root,
+        |path| {
+            let Ok(meta) = fs::metadata(&path) else {
+                // A failed stat isn't proof the file is gone; keep its entry
+                seen.insert(path);
+                return ControlFlow::Continue(());
+            };
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let size = meta.len();
+            stats.scanned += 1;
+
+            if known.get(&path) != Some(&(mtime, size)) {
+                // Unreadable tags still get indexed so the file shows up by name
+                let tags = load_tags(&path).unwrap_or_default();
+                batch.push(Track {
+                    path: path.clone(),
+                    mtime,
+                    size,
+                    tags,
+                });
+                if batch.len() >= BATCH_SIZE {
+                    stats.updated += batch.len();
+                    if let Err(e) = index.upsert_tracks(&batch) {
+                        result = Err(e);
+                        return ControlFlow::Break(());
+                    }
+                    batch.clear();
+                    let _ = tx.send(ScanEvent::Progress {
+                        scanned: stats.scanned,
+                        updated: stats.updated,
+                    });
+                }
+            }
+            seen.insert(path);
+            ControlFlow::Continue(())
+        },
+        |dir, _| unreadable.push(dir.to_path_buf()),
+    );
+    result?;
+
+    stats.updated += batch.len();
+    index.upsert_tracks(&batch)?;
+
+    // Drop tracks that disappeared from this root
+    let missing: Vec<PathBuf> = known
+        .into_keys()
+        .filter(|p| !seen.contains(p) && !unreadable.iter().any(|dir| p.starts_with(dir)))
+        .collect();
+    stats.removed += missing.len();
+    index.remove_tracks(&missing)?;
+
+    let _ = tx.send(ScanEvent::Progress {
+        scanned: stats.scanned,
+        updated: stats.updated,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a full scan of `root` into the index at `db` and return the totals.
+    fn scan_totals(db: &Path, root: &Path) -> (usize, usize, usize) {
+        let (tx, rx) = mpsc::channel();
+        scan(db, &[root.to_path_buf()], &tx).unwrap();
+        drop(tx);
+        rx.iter()
+            .find_map(|event| match event {
+                ScanEvent::Finished {
+                    scanned,
+                    updated,
+                    removed,
+                } => Some((scanned, updated, removed)),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn rescans_only_reindex_changed_files() {
+        let dir = std::env::temp_dir().join(format!("chirpy-scan-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let root = dir.join("music");
+        fs::create_dir_all(&root).unwrap();
+        let db = dir.join("library.db");
+        fs::write(root.join("a.mp3"), "a").unwrap();
+        fs::write(root.join("b.mp3"), "b").unwrap();
+
+        assert_eq!(scan_totals(&db, &root), (2, 2, 0));
+        assert_eq!(scan_totals(&db, &root), (2, 0, 0));
+
+        // Same mtime second, different size
+        fs::write(root.join("a.mp3"), "longer").unwrap();
+        assert_eq!(scan_totals(&db, &root), (2, 1, 0));
+        assert_eq!(
+            LibraryIndex::open(&db)
+                .unwrap()
+                .get(&root.join("a.mp3"))
+                .unwrap()
+                .unwrap()
+                .size,
+            6
+        );
+
+        fs::remove_file(root.join("b.mp3")).unwrap();
+        assert_eq!(scan_totals(&db, &root), (1, 0, 1));
+        assert_eq!(LibraryIndex::open(&db).unwrap().track_count().unwrap(), 1);
+
+        // An unmounted root keeps its tracks
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(scan_totals(&db, &root), (0, 0, 0));
+        assert_eq!(LibraryIndex::open(&db).unwrap().track_count().unwrap(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
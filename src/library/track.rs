@@ -0,0 +1,31 @@
+// src/library/track.rs
+//! Indexed track record.
+
+use std::path::PathBuf;
+
+use crate::audio::TrackTags;
+
+/// One track stored in the library index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    /// Absolute path of the audio file
+    pub path: PathBuf,
+    /// Modification time (seconds since the Unix epoch) when last indexed
+    pub mtime: i64,
+    /// File size in bytes when last indexed
+    pub size: u64,
+    /// Typed tag values
+    pub tags: TrackTags,
+}
+
+impl Track {
+    /// Title to display: the tag title, falling back to the file name.
+    pub fn display_title(&self) -> String {
+        self.tags.title.clone().unwrap_or_else(|| {
+            self.path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    }
+}
@@ -1,12 +1,19 @@
 //src/main.rs
 //! Chirpy - A terminal-based music player.
 
-mod app;
-mod audio;
-mod config;
-mod fs;
-mod ui;
+use clap::Parser;
+
+use chirpy::{audio::player::output_device_names, cli::Cli};
 
 fn main() -> anyhow::Result<()> {
-    ui::run()
+    let cli = Cli::parse();
+    if cli.list_outputs {
+        for name in output_device_names() {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+    // Check arguments and settings before the terminal enters raw mode, so errors stay readable
+    let (config, startup) = cli.resolve()?;
+    chirpy::ui::run(config, startup)
 }
@@ -0,0 +1,353 @@
+// src/playlist/cue.rs
+//! CUE sheets: virtual tracks inside single-file album rips.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Result;
+use lofty::{
+    config::ParseOptions,
+    file::TaggedFileExt,
+    probe::Probe,
+    tag::{ItemKey, Tag},
+};
+
+use super::{decode_text, PlaylistEntry};
+
+/// Parsed CUE sheet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CueSheet {
+    /// Album title
+    pub title: Option<String>,
+    /// Album performer
+    pub performer: Option<String>,
+    /// Tracks in sheet order
+    pub tracks: Vec<CueTrack>,
+}
+
+/// One track of a CUE sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueTrack {
+    /// Track number from the sheet
+    pub number: u32,
+    /// Track title
+    pub title: Option<String>,
+    /// Track performer, falling back to the album performer
+    pub performer: Option<String>,
+    /// Audio file containing the track
+    pub file: PathBuf,
+    /// Offset of the track (`INDEX 01`) within `file`
+    pub start: Duration,
+}
+
+impl CueTrack {
+    /// `Performer - Title`, or whichever of them is known.
+    pub fn display_title(&self) -> String {
+        match (&self.performer, &self.title) {
+            (Some(performer), Some(title)) => format!("{} - {}", performer, title),
+            (None, Some(title)) => title.clone(),
+            _ => format!("Track {:02}", self.number),
+        }
+    }
+}
+
+impl CueSheet {
+    /// Only the tracks stored in `file`, matched by file name (ignoring
+    /// case) since sheets often name the file differently than it is
+    /// stored, and pointed at `file` itself.
+    pub fn for_file(&self, file: &Path) -> CueSheet {
+        let name = lowercase_name(file);
+        CueSheet {
+            title: self.title.clone(),
+            performer: self.performer.clone(),
+            tracks: self
+                .tracks
+                .iter()
+                .filter(|t| name.is_some() && lowercase_name(&t.file) == name)
+                .map(|t| CueTrack {
+                    file: file.to_path_buf(),
+                    ..t.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// Index of the track playing at `position`. Assumes a single-file sheet.
+    pub fn track_at(&self, position: Duration) -> Option<usize> {
+        self.tracks.iter().rposition(|t| t.start <= position)
+    }
+
+    /// Start of the track after `index` in the same file, if any.
+    pub fn end_of(&self, index: usize) -> Option<Duration> {
+        let track = self.tracks.get(index)?;
+        self.tracks
+            .get(index + 1)
+            .filter(|next| next.file == track.file)
+            .map(|next| next.start)
+    }
+
+    /// Tracks as playlist entries with start offsets and lengths.
+    pub fn entries(&self) -> Vec<PlaylistEntry> {
+        (0..self.tracks.len())
+            .map(|i| {
+                let track = &self.tracks[i];
+                PlaylistEntry {
+                    path: track.file.clone(),
+                    title: Some(track.display_title()),
+                    duration_secs: self
+                        .end_of(i)
+                        .map(|end| end.saturating_sub(track.start).as_secs()),
+                    start: Some(track.start),
+                    missing: false,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Read the CUE sheet at `path`, resolving `FILE` entries relative to it.
+pub fn load_cue(path: &Path) -> Result<CueSheet> {
+    let bytes = fs::read(path)?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    Ok(parse(&decode_text(&bytes), base))
+}
+
+/// CUE sheet embedded in the tags of `audio` (e.g. a FLAC `CUESHEET` comment).
+/// Reads the whole tag, so call it off the UI thread.
+pub fn embedded_cue(audio: &Path) -> Option<CueSheet> {
+    let tagged = Probe::open(audio)
+        .ok()?
+        .options(ParseOptions::new().read_cover_art(false))
+        .read()
+        .ok()?;
+    cue_from_tags(tagged.tags(), audio)
+}
+
+/// CUE sheet stored in one of `tags` of `audio`. Its tracks are pointed at
+/// `audio` itself, whatever the sheet's `FILE` says.
+pub fn cue_from_tags(tags: &[Tag], audio: &Path) -> Option<CueSheet> {
+    let text = tags.iter().find_map(|tag| {
+        tag.items().find_map(|item| match item.key() {
+            ItemKey::Unknown(key) if key.eq_ignore_ascii_case("cuesheet") => {
+                item.value().text().map(str::to_owned)
+            }
+            _ => None,
+        })
+    })?;
+    let mut sheet = parse(&text, audio.parent().unwrap_or(Path::new(".")));
+    for track in &mut sheet.tracks {
+        track.file = audio.to_path_buf();
+    }
+    Some(sheet).filter(|s| !s.tracks.is_empty())
+}
+
+/// A `.cue` file next to `audio` that references it, reduced to the tracks
+/// inside `audio`.
+pub fn sibling_cue(audio: &Path) -> Option<CueSheet> {
+    let dir = audio.parent()?;
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cue"))
+        })
+        .filter_map(|p| load_cue(&p).ok())
+        .map(|sheet| sheet.for_file(audio))
+        .find(|sheet| !sheet.tracks.is_empty())
+}
+
+/// Parse CUE sheet text. Tracks without an `INDEX 01` (or `INDEX 00`) are
+/// dropped, and the tracks of each file are put in the order of their
+/// starts, which garbled sheets don't always list them in.
+pub fn parse(text: &str, base: &Path) -> CueSheet {
+    let mut sheet = CueSheet::default();
+    let mut file: Option<PathBuf> = None;
+    // Track being read, and whether it has its INDEX 01 yet
+    let mut current: Option<(CueTrack, bool)> = None;
+    let mut tracks = Vec::new();
+
+    for line in text.lines() {
+        let words = split_words(line);
+        let Some(command) = words.first() else {
+            continue;
+        };
+        let arg = |i: usize| words.get(i).cloned();
+        match command.to_ascii_uppercase().as_str() {
+            "FILE" => file = arg(1).map(|name| base.join(name.replace('\\', "/"))),
+            "TRACK" => {
+                tracks.extend(current.take().map(|(t, _)| t));
+                let is_audio = arg(2).is_none_or(|kind| kind.eq_ignore_ascii_case("AUDIO"));
+                if let (Some(file), true) = (&file, is_audio) {
+                    current = Some((
+                        CueTrack {
+                            number: arg(1).and_then(|n| n.parse().ok()).unwrap_or(0),
+                            title: None,
+                            performer: None,
+                            file: file.clone(),
+                            start: Duration::MAX,
+                        },
+                        false,
+                    ));
+                }
+            }
+            "TITLE" => match &mut current {
+                Some((track, _)) => track.title = arg(1),
+                None => sheet.title = arg(1),
+            },
+            "PERFORMER" => match &mut current {
+                Some((track, _)) => track.performer = arg(1),
+                None => sheet.performer = arg(1),
+            },
+            "INDEX" => {
+                if let Some((track, has_start)) = &mut current
+                    && let Some(start) = arg(2).as_deref().and_then(parse_msf)
+                {
+                    match arg(1).as_deref() {
+                        Some("01") => {
+                            track.start = start;
+                            *has_start = true;
+                        }
+                        // Pregap start, used only when INDEX 01 is missing
+                        Some("00") if !*has_start => track.start = start,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    tracks.extend(current.map(|(t, _)| t));
+
+    for track in &mut tracks {
+        if track.performer.is_none() {
+            track.performer = sheet.performer.clone();
+        }
+    }
+    tracks.retain(|t| t.start != Duration::MAX);
+    // Files stay in sheet order; the sort is stable for equal starts
+    let mut files: Vec<PathBuf> = Vec::new();
+    for track in &tracks {
+        if !files.contains(&track.file) {
+            files.push(track.file.clone());
+        }
+    }
+    tracks.sort_by_key(|t| (files.iter().position(|f| *f == t.file), t.start));
+    sheet.tracks = tracks;
+    sheet
+}
+
+/// File name of `path` in lowercase.
+fn lowercase_name(path: &Path) -> Option<String> {
+    path.file_name().map(|n| n.to_string_lossy().to_lowercase())
+}
+
+/// `mm:ss:ff` (75 frames per second) as a duration. Seconds must be below
+/// 60 and frames below 75.
+fn parse_msf(text: &str) -> Option<Duration> {
+    let mut parts = text.split(':').map(|p| p.parse::<u64>().ok());
+    let (m, s, f) = (parts.next()??, parts.next()??, parts.next()??);
+    if s >= 60 || f >= 75 {
+        return None;
+    }
+    let millis = m.checked_mul(60)?.checked_add(s)?.checked_mul(1000)?;
+    Some(Duration::from_millis(millis.checked_add(f * 1000 / 75)?))
+}
+
+/// Split a CUE line into words, keeping `"quoted strings"` together.
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            words.push(chars.by_ref().take_while(|&c| c != '"').collect());
+        } else {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                word.push(c);
+            }
+            words.push(word);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_lines_are_ignored() {
+        let text = "REM junk\n\
+                    PERFORMER \"Band\n\
+                    TRACK 01 AUDIO\n\
+                    FILE \"Album.flac\" WAVE\n\
+                    TRACK xx AUDIO\n\
+                    INDEX 01 00:0a:00\n\
+                    INDEX 00 00:01:00\n\
+                    TRACK 02 AUDIO\n\
+                    INDEX 01\n\
+                    INDEX 01 03:00:00:00\n\
+                    TRACK 03 MODE1/2352\n\
+                    INDEX 01 05:00:00\n";
+        let sheet = parse(text, Path::new("/music"));
+        // Track 01 precedes any FILE, track 02 has no valid index and
+        // track 03 is a data track; track xx falls back to its pregap
+        assert_eq!(sheet.tracks.len(), 2);
+        assert_eq!(sheet.tracks[0].number, 0);
+        assert_eq!(sheet.tracks[0].start, Duration::from_secs(1));
+        assert_eq!(sheet.tracks[0].performer.as_deref(), Some("Band"));
+        assert_eq!(sheet.tracks[1].start, Duration::from_secs(180));
+    }
+
+    #[test]
+    fn msf_needs_three_numbers() {
+        assert_eq!(parse_msf("01:02:74"), Some(Duration::from_millis(62_986)));
+        assert_eq!(parse_msf("01:02"), None);
+        assert_eq!(parse_msf("a:b:c"), None);
+        assert_eq!(parse_msf(""), None);
+    }
+
+    #[test]
+    fn msf_rejects_out_of_range_and_overflowing_values() {
+        assert_eq!(parse_msf("01:60:00"), None);
+        assert_eq!(parse_msf("01:02:75"), None);
+        assert_eq!(parse_msf("999999999999999999:00:00"), None);
+        assert_eq!(parse_msf(&format!("{}:00:00", u64::MAX)), None);
+    }
+
+    #[test]
+    fn reversed_indexes_are_sorted() {
+        let text = "FILE \"a.flac\" WAVE\n\
+                    TRACK 01 AUDIO\nINDEX 01 05:00:00\n\
+                    TRACK 02 AUDIO\nINDEX 01 02:00:00\n\
+                    TRACK 03 AUDIO\nINDEX 01 00:00:00\n\
+                    FILE \"b.flac\" WAVE\n\
+                    TRACK 04 AUDIO\nINDEX 01 00:00:00\n";
+        let sheet = parse(text, Path::new("/music"));
+        let numbers: Vec<u32> = sheet.tracks.iter().map(|t| t.number).collect();
+        assert_eq!(numbers, [3, 2, 1, 4]);
+        let lengths: Vec<Option<u64>> = sheet.entries().iter().map(|e| e.duration_secs).collect();
+        assert_eq!(lengths, [Some(120), Some(180), None, None]);
+        let album = sheet.for_file(Path::new("/music/a.flac"));
+        assert_eq!(album.track_at(Duration::from_secs(150)), Some(1));
+    }
+
+    #[test]
+    fn for_file_matches_the_name_ignoring_case() {
+        let text = "FILE \"sub\\ALBUM.FLAC\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\n\
+                    FILE \"other.flac\" WAVE\nTRACK 02 AUDIO\nINDEX 01 00:00:00\n";
+        let sheet = parse(text, Path::new("/music"));
+        let audio = Path::new("/music/album.flac");
+        let tracks = sheet.for_file(audio).tracks;
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].file, audio);
+    }
+}
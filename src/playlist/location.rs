@@ -0,0 +1,93 @@
+// src/playlist/location.rs
+//! Conversion between playlist locations and local file paths.
+
+use std::path::{Path, PathBuf};
+
+/// Resolve a playlist location to a local path. Handles absolute and relative
+/// paths (relative to `base`), Windows-style separators and `file://` URLs.
+/// Returns `None` for remote URLs.
+pub fn resolve(location: &str, base: &Path) -> Option<PathBuf> {
+    let location = location.trim();
+    if location.is_empty() {
+        return None;
+    }
+    if let Some(rest) = location.strip_prefix("file://") {
+        // `file:///abs` or `file://localhost/abs`
+        let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+        return Some(PathBuf::from(percent_decode(rest)));
+    }
+    if is_url(location) {
+        return None;
+    }
+    let path = PathBuf::from(location.replace('\\', "/"));
+    Some(if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    })
+}
+
+/// Resolve a URI reference (an XSPF location) to a local path. Like
+/// [`resolve`], but relative references are percent-decoded too.
+pub fn resolve_uri(location: &str, base: &Path) -> Option<PathBuf> {
+    let location = location.trim();
+    if location.starts_with("file://") || is_url(location) {
+        return resolve(location, base);
+    }
+    resolve(&percent_decode(location), base)
+}
+
+/// Location of `path` for a playlist stored in `base`: relative when it lies
+/// below `base`, absolute otherwise.
+pub fn relative_to(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// `file://` URL for an absolute path, percent-encoding unsafe bytes.
+pub fn file_url(path: &Path) -> String {
+    let mut url = String::from("file://");
+    for &b in path.to_string_lossy().as_bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            url.push(b as char);
+        } else {
+            url.push_str(&format!("%{:02X}", b));
+        }
+    }
+    url
+}
+
+/// True for `scheme://...` locations.
+fn is_url(location: &str) -> bool {
+    location.split_once("://").is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    })
+}
+
+/// Decode `%XX` escapes; malformed escapes are kept verbatim.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
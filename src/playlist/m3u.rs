@@ -0,0 +1,109 @@
+// src/playlist/m3u.rs
+//! Extended M3U (`#EXTM3U` / `#EXTINF`) playlists.
+
+use std::path::Path;
+
+use super::{location, PlaylistEntry};
+
+/// Parse M3U text. `#EXTINF` lines give the title and duration of the next entry.
+pub fn parse(text: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut info: Option<(Option<u64>, Option<String>)> = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("#EXTINF:") {
+            info = Some(parse_extinf(rest));
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else if let Some(path) = location::resolve(line, base) {
+            let (duration_secs, title) = info.take().unwrap_or_default();
+            entries.push(PlaylistEntry {
+                path,
+                title,
+                duration_secs,
+                start: None,
+                missing: false,
+            });
+        } else {
+            // Remote stream: drop its #EXTINF along with it
+            info = None;
+        }
+    }
+    entries
+}
+
+/// Split `<seconds>[ attributes],<title>`; -1 means unknown length.
+/// Commas inside quoted attribute values (`tvg-name="a, b"`) don't end the head.
+fn parse_extinf(rest: &str) -> (Option<u64>, Option<String>) {
+    let mut quoted = false;
+    let comma = rest.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ',' if !quoted => Some(i),
+        _ => None,
+    });
+    let (head, title) = match comma {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, ""),
+    };
+    let secs = head
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|s| *s >= 0.0)
+        .map(|s| s.round() as u64);
+    let title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+    (secs, title)
+}
+
+/// Render entries as UTF-8 extended M3U.
+pub fn write(entries: &[PlaylistEntry], base: &Path) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for entry in entries {
+        let secs = entry.duration_secs.map(|s| s as i64).unwrap_or(-1);
+        out.push_str(&format!("#EXTINF:{},{}\n", secs, entry.display_title()));
+        out.push_str(&location::relative_to(&entry.path, base));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+
+    #[test]
+    fn extinf_title_after_quoted_comma() {
+        let (secs, title) =
+            parse_extinf(r#"-1 tvg-name="Smith, John" group-title="A,B",Song, Part 2"#);
+        assert_eq!(secs, None);
+        assert_eq!(title.as_deref(), Some("Song, Part 2"));
+    }
+
+    #[test]
+    fn extinf_without_comma_or_length() {
+        assert_eq!(parse_extinf("123"), (Some(123), None));
+        assert_eq!(parse_extinf(""), (None, None));
+        assert_eq!(parse_extinf("abc,Title"), (None, Some("Title".to_string())));
+        // An unterminated quote swallows the rest of the line
+        assert_eq!(parse_extinf(r#"5 a="x,y"#), (Some(5), None));
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let text = "#EXTM3U\n#EXTINF:oops\n\n  a.mp3  \n#EXTINF:10,Remote\nhttp://x/y.mp3\nb.mp3\n#EXTINF:7,Dangling";
+        let entries = parse(text, Path::new("/music"));
+        let paths: Vec<&PathBuf> = entries.iter().map(|e| &e.path).collect();
+        assert_eq!(
+            paths,
+            [Path::new("/music/a.mp3"), Path::new("/music/b.mp3")]
+        );
+        assert_eq!(entries[0].title, None);
+        // The remote stream's #EXTINF doesn't carry over to the next file
+        assert_eq!(entries[1].title, None);
+        assert_eq!(entries[1].duration_secs, None);
+    }
+}
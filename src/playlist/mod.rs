@@ -0,0 +1,134 @@
+// src/playlist/mod.rs
+//! Playlist module - reading and writing M3U/M3U8, PLS and XSPF playlists,
+//! and reading CUE sheets.
+//!
+//! Entries are resolved to local file paths on load; remote URLs are skipped.
+
+pub mod cue;
+pub mod location;
+pub mod m3u;
+pub mod pls;
+pub mod xspf;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+
+/// Supported playlist file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    /// Extended M3U (`.m3u8` is always UTF-8, `.m3u` may be Latin-1)
+    M3u,
+    /// INI-style `.pls`
+    Pls,
+    /// XML Shareable Playlist Format
+    Xspf,
+    /// CUE sheet (read-only)
+    Cue,
+}
+
+impl PlaylistFormat {
+    /// Format implied by the extension of `path`, if it is a playlist.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "m3u" | "m3u8" => Some(PlaylistFormat::M3u),
+            "pls" => Some(PlaylistFormat::Pls),
+            "xspf" => Some(PlaylistFormat::Xspf),
+            "cue" => Some(PlaylistFormat::Cue),
+            _ => None,
+        }
+    }
+}
+
+/// One track of a playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistEntry {
+    /// Resolved path of the audio file
+    pub path: PathBuf,
+    /// Title given by the playlist, if any
+    pub title: Option<String>,
+    /// Length given by the playlist, in seconds
+    pub duration_secs: Option<u64>,
+    /// Offset of the track within `path` (CUE sheet tracks)
+    pub start: Option<Duration>,
+    /// The file wasn't found when the playlist was loaded
+    pub missing: bool,
+}
+
+impl PlaylistEntry {
+    /// Entry for `path` with no playlist-provided details.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            title: None,
+            duration_secs: None,
+            start: None,
+            missing: false,
+        }
+    }
+
+    /// Title to display: the playlist title, falling back to the file name.
+    pub fn display_title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| {
+            self.path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Read the playlist at `path`, resolving entries relative to its directory.
+pub fn load_playlist(path: &Path) -> Result<Vec<PlaylistEntry>> {
+    let Some(format) = PlaylistFormat::from_path(path) else {
+        bail!("{} is not a playlist", path.display());
+    };
+    let bytes = fs::read(path)?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut entries = match format {
+        PlaylistFormat::M3u => m3u::parse(&decode_text(&bytes), base),
+        PlaylistFormat::Pls => pls::parse(&decode_text(&bytes), base),
+        PlaylistFormat::Xspf => xspf::parse(&String::from_utf8_lossy(&bytes), base)?,
+        PlaylistFormat::Cue => cue::parse(&decode_text(&bytes), base).entries(),
+    };
+    mark_missing(&mut entries);
+    Ok(entries)
+}
+
+/// Flag the entries whose file doesn't exist (anymore).
+pub fn mark_missing(entries: &mut [PlaylistEntry]) {
+    for entry in entries {
+        entry.missing = !entry.path.is_file();
+    }
+}
+
+/// Write `entries` to `path` in the format given by its extension.
+/// Paths below the playlist's directory are stored relative to it.
+pub fn save_playlist(path: &Path, entries: &[PlaylistEntry]) -> Result<()> {
+    let Some(format) = PlaylistFormat::from_path(path) else {
+        bail!("unknown playlist extension (use .m3u8, .pls or .xspf)");
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+    let text = match format {
+        PlaylistFormat::M3u => m3u::write(entries, base),
+        PlaylistFormat::Pls => pls::write(entries, base),
+        PlaylistFormat::Xspf => xspf::write(entries),
+        PlaylistFormat::Cue => bail!("CUE sheets can't be written (use .m3u8, .pls or .xspf)"),
+    };
+    fs::write(path, text)?;
+    Ok(())
+}
+
+/// Decode playlist text: UTF-8 (without BOM) when valid, Latin-1 otherwise.
+fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
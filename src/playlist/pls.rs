@@ -0,0 +1,62 @@
+// src/playlist/pls.rs
+//! INI-style PLS playlists (`FileN=`, `TitleN=`, `LengthN=`).
+
+use std::{collections::BTreeMap, path::Path};
+
+use super::{location, PlaylistEntry};
+
+/// Parse PLS text. Entries are ordered by their number, not by line order.
+pub fn parse(text: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut files: BTreeMap<u32, String> = BTreeMap::new();
+    let mut titles: BTreeMap<u32, String> = BTreeMap::new();
+    let mut lengths: BTreeMap<u32, i64> = BTreeMap::new();
+
+    for line in text.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+        let numbered = |prefix: &str| key.strip_prefix(prefix).and_then(|n| n.parse::<u32>().ok());
+        if let Some(n) = numbered("file") {
+            files.insert(n, value);
+        } else if let Some(n) = numbered("title") {
+            titles.insert(n, value);
+        } else if let Some(n) = numbered("length")
+            && let Ok(secs) = value.parse()
+        {
+            lengths.insert(n, secs);
+        }
+    }
+
+    files
+        .into_iter()
+        .filter_map(|(n, file)| {
+            Some(PlaylistEntry {
+                path: location::resolve(&file, base)?,
+                title: titles.remove(&n).filter(|t| !t.is_empty()),
+                duration_secs: lengths.get(&n).filter(|s| **s >= 0).map(|s| *s as u64),
+                start: None,
+                missing: false,
+            })
+        })
+        .collect()
+}
+
+/// Render entries as PLS version 2.
+pub fn write(entries: &[PlaylistEntry], base: &Path) -> String {
+    let mut out = String::from("[playlist]\n");
+    for (i, entry) in entries.iter().enumerate() {
+        let n = i + 1;
+        out.push_str(&format!(
+            "File{}={}\n",
+            n,
+            location::relative_to(&entry.path, base)
+        ));
+        out.push_str(&format!("Title{}={}\n", n, entry.display_title()));
+        let secs = entry.duration_secs.map(|s| s as i64).unwrap_or(-1);
+        out.push_str(&format!("Length{}={}\n", n, secs));
+    }
+    out.push_str(&format!("NumberOfEntries={}\nVersion=2\n", entries.len()));
+    out
+}
@@ -0,0 +1,155 @@
+// src/playlist/xspf.rs
+//! XML Shareable Playlist Format (XSPF) playlists.
+
+use std::path::Path;
+
+use anyhow::Result;
+use quick_xml::{
+    escape::{escape, resolve_predefined_entity},
+    events::Event,
+    Reader,
+};
+
+use super::{location, PlaylistEntry};
+
+/// Track fields collected while inside a `<track>` element.
+#[derive(Default)]
+struct TrackFields {
+    location: Option<String>,
+    title: Option<String>,
+    creator: Option<String>,
+    duration_ms: Option<u64>,
+}
+
+/// Parse XSPF text. Only the first `<location>` of each track is used.
+pub fn parse(text: &str, base: &Path) -> Result<Vec<PlaylistEntry>> {
+    let mut reader = Reader::from_str(text);
+    let mut entries = Vec::new();
+    let mut track: Option<TrackFields> = None;
+    // Name of the track child element being read, and its text so far
+    let mut field: Option<String> = None;
+    let mut value = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = e.local_name().as_ref().to_string();
+                if name == "track" {
+                    track = Some(TrackFields::default());
+                } else if track.is_some() {
+                    field = Some(name);
+                    value.clear();
+                }
+            }
+            Event::Text(t) if field.is_some() => value.push_str(&t.xml10_content()),
+            Event::CData(t) if field.is_some() => value.push_str(&t.xml10_content()),
+            Event::GeneralRef(r) if field.is_some() => {
+                if let Ok(Some(c)) = r.resolve_char_ref() {
+                    value.push(c);
+                } else if let Some(s) = resolve_predefined_entity(&r) {
+                    value.push_str(s);
+                }
+            }
+            Event::End(e) => {
+                if e.local_name().as_ref() == "track" {
+                    if let Some(fields) = track.take() {
+                        entries.extend(to_entry(fields, base));
+                    }
+                } else if let (Some(fields), Some(f)) = (track.as_mut(), field.take()) {
+                    let text = value.trim().to_string();
+                    match f.as_str() {
+                        "location" if fields.location.is_none() => fields.location = Some(text),
+                        "title" => fields.title = Some(text),
+                        "creator" => fields.creator = Some(text),
+                        "duration" => fields.duration_ms = text.parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Playlist entry for a parsed track, if its location is a local file.
+fn to_entry(fields: TrackFields, base: &Path) -> Option<PlaylistEntry> {
+    let path = location::resolve_uri(fields.location.as_deref()?, base)?;
+    let title = match (fields.creator, fields.title) {
+        (Some(creator), Some(title)) if !creator.is_empty() => {
+            Some(format!("{} - {}", creator, title))
+        }
+        (_, title) => title,
+    };
+    Some(PlaylistEntry {
+        path,
+        title: title.filter(|t| !t.is_empty()),
+        duration_secs: fields.duration_ms.map(|ms| ms / 1000),
+        start: None,
+        missing: false,
+    })
+}
+
+/// Render entries as XSPF with absolute `file://` locations.
+pub fn write(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <trackList>\n",
+    );
+    for entry in entries {
+        out.push_str("    <track>\n");
+        out.push_str(&format!(
+            "      <location>{}</location>\n",
+            escape(location::file_url(&entry.path))
+        ));
+        if let Some(title) = &entry.title {
+            out.push_str(&format!(
+                "      <title>{}</title>\n",
+                escape(title.as_str())
+            ));
+        }
+        if let Some(secs) = entry.duration_secs {
+            out.push_str(&format!("      <duration>{}</duration>\n", secs * 1000));
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn relative_locations_are_percent_decoded() {
+        let text = r#"<playlist><trackList>
+            <track><location>My%20Album/01%20Song.flac</location><title>Song</title></track>
+            <track><location>file:///abs/a%2Cb.mp3</location></track>
+        </trackList></playlist>"#;
+        let entries = parse(text, Path::new("/music")).unwrap();
+        assert_eq!(entries[0].path, Path::new("/music/My Album/01 Song.flac"));
+        assert_eq!(entries[1].path, Path::new("/abs/a,b.mp3"));
+    }
+
+    #[test]
+    fn tracks_without_usable_location_are_skipped() {
+        let text = r#"<playlist><trackList>
+            <track><title>No location</title></track>
+            <track><location>https://example.com/a.mp3</location></track>
+            <track><location>  </location></track>
+            <track><location>ok.mp3</location><duration>not a number</duration></track>
+        </trackList></playlist>"#;
+        let entries = parse(text, Path::new("/music")).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].duration_secs, None);
+    }
+
+    #[test]
+    fn broken_xml_is_an_error() {
+        assert!(parse("<playlist><trackList><track></playlist>", Path::new("/")).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+// src/search/finder.rs
+//! Candidate source for the global fuzzy finder.
+//!
+//! Candidates come from the library index first (if any), then from a
+//! recursive walk of the music roots, streamed in batches.
+
+use std::{
+    collections::HashSet,
+    ops::ControlFlow,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use crate::fs::for_each_audio_file;
+use crate::library::{LibraryIndex, Track};
+
+/// Number of walked files sent per batch.
+const BATCH_SIZE: usize = 256;
+
+/// One searchable track.
+#[derive(Debug, Clone)]
+pub struct FinderItem {
+    /// Path of the audio file
+    pub path: PathBuf,
+    /// Text shown and matched against: tags (if known) followed by the path
+    pub haystack: String,
+}
+
+impl FinderItem {
+    /// Item for a file without tag information.
+    pub fn from_path(path: PathBuf) -> Self {
+        let haystack = path.to_string_lossy().into_owned();
+        Self { path, haystack }
+    }
+
+    /// Item for an indexed track.
+    pub fn from_track(track: Track) -> Self {
+        let tags = [
+            Some(track.display_title()),
+            track.tags.artist,
+            track.tags.album,
+        ];
+        let mut haystack: Vec<String> = tags.into_iter().flatten().collect();
+        haystack.push(track.path.to_string_lossy().into_owned());
+        Self {
+            path: track.path,
+            haystack: haystack.join(" · "),
+        }
+    }
+}
+
+/// Stream finder candidates for `roots` on a background thread.
+/// The walk stops as soon as the receiver is dropped.
+pub fn spawn_candidates(
+    index_path: Option<PathBuf>,
+    roots: Vec<PathBuf>,
+) -> Receiver<Vec<FinderItem>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut seen = HashSet::new();
+
+        // Indexed tracks carry tags, so send them first
+        if let Some(tracks) = index_path
+            .and_then(|p| LibraryIndex::open(&p).ok())
+            .and_then(|index| index.all_tracks().ok())
+        {
+            let items: Vec<FinderItem> = tracks
+                .into_iter()
+                .inspect(|t| {
+                    seen.insert(t.path.clone());
+                })
+                .map(FinderItem::from_track)
+                .collect();
+            if tx.send(items).is_err() {
+                return;
+            }
+        }
+
+        // Then pick up anything not indexed (yet)
+        let mut batch = Vec::new();
+        for root in &roots {
+            let flow = for_each_audio_file(root, |path| {
+                if !seen.contains(&path) {
+                    batch.push(FinderItem::from_path(path));
+                    if batch.len() >= BATCH_SIZE && tx.send(std::mem::take(&mut batch)).is_err() {
+                        return ControlFlow::Break(());
+                    }
+                }
+                ControlFlow::Continue(())
+            });
+            if flow.is_break() {
+                return;
+            }
+        }
+        let _ = tx.send(batch);
+    });
+    rx
+}
@@ -0,0 +1,105 @@
+// src/search/fuzzy.rs
+//! Case-insensitive subsequence matching with fzf-like scoring.
+
+/// Score awarded for every matched character.
+const SCORE_MATCH: i64 = 16;
+/// Bonus when a match directly follows the previous one.
+const BONUS_CONSECUTIVE: i64 = 24;
+/// Bonus when a match starts a word (after a separator or at a lower->upper case change).
+const BONUS_WORD_START: i64 = 20;
+/// Penalty for each skipped character between two matches.
+const PENALTY_GAP: i64 = 2;
+
+/// Result of a successful fuzzy match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i64,
+    /// Char indices of `text` that matched the pattern, ascending
+    pub indices: Vec<usize>,
+}
+
+/// Match `pattern` against `text` as a case-insensitive subsequence.
+/// Whitespace in the pattern is ignored. Returns `None` if some pattern
+/// character can't be found in order. An empty pattern matches everything.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    // Find the shortest window ending at the earliest complete match:
+    // scan forward for the end, then backward for the tightest start.
+    let mut pi = 0;
+    let mut end = None;
+    for (i, c) in lower.iter().enumerate() {
+        if *c == pattern[pi] {
+            pi += 1;
+            if pi == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+    let mut start = end;
+    let mut pi = pattern.len();
+    for i in (0..=end).rev() {
+        if lower[i] == pattern[pi - 1] {
+            pi -= 1;
+            if pi == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    // Greedy forward pass inside the window, scoring as we go
+    let mut indices = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut pi = 0;
+    let mut last: Option<usize> = None;
+    for (i, c) in lower.iter().enumerate().take(end + 1).skip(start) {
+        if pi < pattern.len() && *c == pattern[pi] {
+            score += SCORE_MATCH;
+            if is_word_start(&chars, i) {
+                score += BONUS_WORD_START;
+            }
+            match last {
+                Some(l) if l + 1 == i => score += BONUS_CONSECUTIVE,
+                Some(l) => score -= PENALTY_GAP * (i - l - 1) as i64,
+                None => {}
+            }
+            indices.push(i);
+            last = Some(i);
+            pi += 1;
+        }
+    }
+    // Prefer matches near the start of the text
+    score -= start.min(20) as i64;
+
+    Some(FuzzyMatch { score, indices })
+}
+
+/// True if the char at `i` begins a word.
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    if i == 0 {
+        return true;
+    }
+    let prev = chars[i - 1];
+    let cur = chars[i];
+    !prev.is_alphanumeric() || (prev.is_lowercase() && cur.is_uppercase())
+}
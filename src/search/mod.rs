@@ -0,0 +1,9 @@
+// src/search/mod.rs
+//! Search module - fuzzy matching used by list filtering and the global finder.
+
+pub mod finder;
+pub mod fuzzy;
+
+// Re-export commonly used types
+pub use finder::{spawn_candidates, FinderItem};
+pub use fuzzy::{fuzzy_match, FuzzyMatch};
@@ -1,19 +1,139 @@
 // src/ui/icons.rs
-//! Icon mappings for file types in the browser.
+//! Icon sets: Nerd Font glyphs, with an ASCII fallback for terminals
+//! without a patched font.
+
+use serde::Deserialize;
 
 use crate::fs::FileCategory;
 
-/// Get the appropriate icon for a file or directory entry.
-pub fn icon_for_entry(is_dir: bool, category: &FileCategory) -> &'static str {
-    if is_dir {
-        "\u{f07b}" // folder icon
-    } else {
-        match category {
-            FileCategory::Audio => "\u{f1c7}",
-            FileCategory::Image => "\u{f1c5}",
-            FileCategory::Video => "\u{f1c8}",
-            FileCategory::Document => "\u{f15c}",
-            FileCategory::Binary => "\u{f1c6}",
+/// Which glyphs the UI draws with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    /// Nerd Font file icons and Unicode symbols
+    #[default]
+    Nerd,
+    /// Plain ASCII only
+    Ascii,
+}
+
+impl IconSet {
+    /// Glyphs of this set.
+    pub fn icons(self) -> &'static Icons {
+        match self {
+            IconSet::Nerd => &NERD,
+            IconSet::Ascii => &ASCII,
+        }
+    }
+}
+
+/// Every glyph the UI draws.
+#[derive(Debug)]
+pub struct Icons {
+    pub folder: &'static str,
+    pub locked: &'static str,
+    pub audio: &'static str,
+    pub image: &'static str,
+    pub video: &'static str,
+    pub document: &'static str,
+    pub binary: &'static str,
+    pub playlist: &'static str,
+    pub warning: &'static str,
+    pub link: &'static str,
+    pub broken_link: &'static str,
+    pub loading: &'static str,
+    /// Marks the chapter playing in the chapter list
+    pub current: &'static str,
+    /// Player buttons
+    pub previous: &'static str,
+    pub stop: &'static str,
+    pub play: &'static str,
+    pub pause: &'static str,
+    pub next: &'static str,
+    pub repeat: &'static str,
+    pub shuffle: &'static str,
+    /// Text cursor of prompts
+    pub cursor: &'static str,
+    /// Chapter marks above and below the progress gauge
+    pub tick_top: &'static str,
+    pub tick_bottom: &'static str,
+    /// Resume markers for under 3/8, under 5/8 and the rest of a track played
+    pub resume: [&'static str; 3],
+}
+
+/// Nerd Font glyphs.
+pub const NERD: Icons = Icons {
+    folder: "\u{f07b}",
+    locked: "\u{f023}",
+    audio: "\u{f1c7}",
+    image: "\u{f1c5}",
+    video: "\u{f1c8}",
+    document: "\u{f15c}",
+    binary: "\u{f1c6}",
+    playlist: "\u{f0cb}",
+    warning: "\u{f071}",
+    link: "\u{f0c1}",
+    broken_link: "\u{f127}",
+    loading: "\u{f110}",
+    current: "\u{f04b}",
+    previous: "⏮",
+    stop: "⏹",
+    play: "⏵",
+    pause: "⏸",
+    next: "⏭",
+    repeat: "⟳",
+    shuffle: "⤮",
+    cursor: "▏",
+    tick_top: "╷",
+    tick_bottom: "╵",
+    resume: ["◔", "◑", "◕"],
+};
+
+/// ASCII stand-ins.
+pub const ASCII: Icons = Icons {
+    folder: "/",
+    locked: "!",
+    audio: "~",
+    image: "#",
+    video: "%",
+    document: "=",
+    binary: "*",
+    playlist: "+",
+    warning: "!",
+    link: "->",
+    broken_link: "-x",
+    loading: "*",
+    current: ">",
+    previous: "|<",
+    stop: "[]",
+    play: ">",
+    pause: "||",
+    next: ">|",
+    repeat: "rep",
+    shuffle: "shuf",
+    cursor: "_",
+    tick_top: "|",
+    tick_bottom: "|",
+    resume: ["@", "@", "@"],
+};
+
+impl Icons {
+    /// Icon for a file or directory entry.
+    pub fn for_entry(&self, is_dir: bool, category: &FileCategory) -> &'static str {
+        if is_dir && *category == FileCategory::Inaccessible {
+            self.locked
+        } else if is_dir {
+            self.folder
+        } else {
+            match category {
+                FileCategory::Audio => self.audio,
+                FileCategory::Image => self.image,
+                FileCategory::Video => self.video,
+                FileCategory::Document => self.document,
+                FileCategory::Binary => self.binary,
+                FileCategory::Playlist => self.playlist,
+                FileCategory::Inaccessible => self.locked,
+            }
         }
     }
 }
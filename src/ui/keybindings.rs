@@ -1,66 +1,491 @@
 // src/ui/keybindings.rs
-//! Keyboard input handling and key mappings.
-
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-
-/// Map digit/shifted-digit keys to section number (1..4).
-pub fn map_key_to_digit(k: &KeyEvent) -> Option<usize> {
-    if let KeyCode::Char(c) = k.code {
-        match c {
-            '1' | '!' => Some(1),
-            '2' | '@' => Some(2),
-            '3' | '#' => Some(3),
-            '4' | '$' => Some(4),
-            _ => None,
-        }
-    } else {
-        None
-    }
-}
-
-/// Check if the key event is a shifted symbol (!, @, #, $).
-pub fn is_shifted_symbol(key: &KeyEvent) -> bool {
-    matches!(
-        key.code,
-        KeyCode::Char('!') | KeyCode::Char('@') | KeyCode::Char('#') | KeyCode::Char('$')
-    )
-}
+//! Actions that keys can be bound to, and the built-in key presets.
+//!
+//! Keys in the preset tables use the config file syntax (see `keymap`).
 
 /// Navigation actions derived from key events.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum NavigationAction {
     Up,
     Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
     Enter,
+    Open,
     Back,
+    Enqueue,
+    SaveQueue,
+    ShowQueue,
+    RemoveFromQueue,
+    MoveUpInQueue,
+    MoveDownInQueue,
+    ClearQueue,
+    CycleBrowseMode,
+    Search,
+    Find,
+    CommandLine,
+    Confirm,
+    Cancel,
+    DeleteChar,
+    CycleColumns,
+    ToggleColumn(usize),
+    ToggleHidden,
+    ToggleAllFiles,
+    PreviewImage,
+    CycleSort,
+    ReverseSort,
+    ToggleDirsFirst,
     TogglePause,
     Stop,
     NextTrack,
     PreviousTrack,
+    NextChapter,
+    PreviousChapter,
+    ChapterList,
+    VolumeUp,
+    VolumeDown,
+    CycleRepeat,
+    ToggleShuffle,
+    CycleTheme,
+    Help,
     Quit,
+    RescanLibrary,
     ToggleSection(usize),
+    GrowWidth,
+    ShrinkWidth,
+    GrowHeight,
+    ShrinkHeight,
+    ResetLayout,
+    FocusNext,
+    FocusPrevious,
+    FocusPane(usize),
+    ToggleMiniPlayer,
     None,
 }
 
-/// Convert a key event to a navigation action.
-pub fn key_to_action(key: &KeyEvent) -> NavigationAction {
-    // Check for section toggle first
-    if let Some(d) = map_key_to_digit(key) {
-        if key.modifiers.contains(KeyModifiers::SHIFT) || is_shifted_symbol(key) {
-            return NavigationAction::ToggleSection(d);
+use NavigationAction as A;
+
+/// Config names of all actions. `none` unbinds a key.
+pub const ACTION_NAMES: &[(&str, NavigationAction)] = &[
+    ("up", A::Up),
+    ("down", A::Down),
+    ("page_up", A::PageUp),
+    ("page_down", A::PageDown),
+    ("top", A::Top),
+    ("bottom", A::Bottom),
+    ("enter", A::Enter),
+    ("open", A::Open),
+    ("back", A::Back),
+    ("enqueue", A::Enqueue),
+    ("save_queue", A::SaveQueue),
+    ("show_queue", A::ShowQueue),
+    ("remove_from_queue", A::RemoveFromQueue),
+    ("move_up_in_queue", A::MoveUpInQueue),
+    ("move_down_in_queue", A::MoveDownInQueue),
+    ("clear_queue", A::ClearQueue),
+    ("cycle_browse_mode", A::CycleBrowseMode),
+    ("search", A::Search),
+    ("find", A::Find),
+    ("command_line", A::CommandLine),
+    ("confirm", A::Confirm),
+    ("cancel", A::Cancel),
+    ("delete_char", A::DeleteChar),
+    ("cycle_columns", A::CycleColumns),
+    ("toggle_duration_column", A::ToggleColumn(1)),
+    ("toggle_size_column", A::ToggleColumn(2)),
+    ("toggle_modified_column", A::ToggleColumn(3)),
+    ("toggle_bitrate_column", A::ToggleColumn(4)),
+    ("toggle_hidden", A::ToggleHidden),
+    ("toggle_all_files", A::ToggleAllFiles),
+    ("preview_image", A::PreviewImage),
+    ("cycle_sort", A::CycleSort),
+    ("reverse_sort", A::ReverseSort),
+    ("toggle_dirs_first", A::ToggleDirsFirst),
+    ("toggle_pause", A::TogglePause),
+    ("stop", A::Stop),
+    ("next_track", A::NextTrack),
+    ("previous_track", A::PreviousTrack),
+    ("next_chapter", A::NextChapter),
+    ("previous_chapter", A::PreviousChapter),
+    ("chapter_list", A::ChapterList),
+    ("volume_up", A::VolumeUp),
+    ("volume_down", A::VolumeDown),
+    ("cycle_repeat", A::CycleRepeat),
+    ("toggle_shuffle", A::ToggleShuffle),
+    ("cycle_theme", A::CycleTheme),
+    ("help", A::Help),
+    ("quit", A::Quit),
+    ("rescan_library", A::RescanLibrary),
+    ("toggle_files", A::ToggleSection(1)),
+    ("toggle_player", A::ToggleSection(2)),
+    ("toggle_artwork", A::ToggleSection(3)),
+    ("toggle_visualizer", A::ToggleSection(4)),
+    ("grow_width", A::GrowWidth),
+    ("shrink_width", A::ShrinkWidth),
+    ("grow_height", A::GrowHeight),
+    ("shrink_height", A::ShrinkHeight),
+    ("reset_layout", A::ResetLayout),
+    ("focus_next", A::FocusNext),
+    ("focus_previous", A::FocusPrevious),
+    ("focus_files", A::FocusPane(1)),
+    ("focus_player", A::FocusPane(2)),
+    ("focus_artwork", A::FocusPane(3)),
+    ("focus_visualizer", A::FocusPane(4)),
+    ("toggle_mini_player", A::ToggleMiniPlayer),
+    ("none", A::None),
+];
+
+impl NavigationAction {
+    /// Action with the config name `name`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        ACTION_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, action)| *action)
+    }
+
+    /// Config name of the action.
+    pub fn name(self) -> &'static str {
+        ACTION_NAMES
+            .iter()
+            .find(|(_, action)| *action == self)
+            .map_or("none", |(n, _)| n)
+    }
+
+    /// What the action does, for the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            A::Up => "Move up",
+            A::Down => "Move down",
+            A::PageUp => "Move up one page",
+            A::PageDown => "Move down one page",
+            A::Top => "Go to the first (or counted) row",
+            A::Bottom => "Go to the last (or counted) row",
+            A::Enter => "Open or play the selection",
+            A::Open => "Open the selection",
+            A::Back => "Go to the parent",
+            A::Enqueue => "Add the selection to the queue",
+            A::SaveQueue => "Save the queue as a playlist",
+            A::ShowQueue => "Show or hide the queue",
+            A::RemoveFromQueue => "Remove the track from the queue",
+            A::MoveUpInQueue => "Move the track up",
+            A::MoveDownInQueue => "Move the track down",
+            A::ClearQueue => "Clear the queue",
+            A::CycleBrowseMode => "Browse files, artists, albums or genres",
+            A::Search => "Search the list",
+            A::Find => "Find a track anywhere",
+            A::CommandLine => "Open the command line",
+            A::Confirm => "Keep the search",
+            A::Cancel => "Clear the search",
+            A::DeleteChar => "Delete a character",
+            A::CycleColumns => "Change the detail columns",
+            A::ToggleColumn(1) => "Show or hide the duration column",
+            A::ToggleColumn(2) => "Show or hide the size column",
+            A::ToggleColumn(3) => "Show or hide the modified column",
+            A::ToggleColumn(_) => "Show or hide the bitrate column",
+            A::ToggleHidden => "Show or hide hidden files",
+            A::ToggleAllFiles => "Show all files or only playable ones",
+            A::PreviewImage => "Preview the selected image",
+            A::CycleSort => "Change the sort order",
+            A::ReverseSort => "Reverse the sort order",
+            A::ToggleDirsFirst => "List directories first or mixed",
+            A::TogglePause => "Play or pause",
+            A::Stop => "Stop",
+            A::NextTrack => "Next track",
+            A::PreviousTrack => "Previous track",
+            A::NextChapter => "Next chapter",
+            A::PreviousChapter => "Previous chapter",
+            A::ChapterList => "List the chapters",
+            A::VolumeUp => "Raise the volume",
+            A::VolumeDown => "Lower the volume",
+            A::CycleRepeat => "Change the repeat mode",
+            A::ToggleShuffle => "Turn shuffle on or off",
+            A::CycleTheme => "Switch to the next theme",
+            A::Help => "Show this help",
+            A::Quit => "Quit",
+            A::RescanLibrary => "Rescan the library",
+            A::ToggleSection(1) => "Show or hide the file list",
+            A::ToggleSection(2) => "Show or hide the player",
+            A::ToggleSection(3) => "Show or hide the artwork",
+            A::ToggleSection(_) => "Show or hide the visualizer",
+            A::GrowWidth => "Widen the focused panel",
+            A::ShrinkWidth => "Narrow the focused panel",
+            A::GrowHeight => "Make the focused panel taller",
+            A::ShrinkHeight => "Make the focused panel shorter",
+            A::ResetLayout => "Undo panel resizing",
+            A::FocusNext => "Focus the next panel",
+            A::FocusPrevious => "Focus the previous panel",
+            A::FocusPane(1) => "Focus the file list",
+            A::FocusPane(2) => "Focus the player",
+            A::FocusPane(3) => "Focus the artwork",
+            A::FocusPane(_) => "Focus the visualizer",
+            A::ToggleMiniPlayer => "Switch between the panels and the mini-player",
+            A::None => "Nothing",
         }
     }
 
-    match key.code {
-        KeyCode::Down => NavigationAction::Down,
-        KeyCode::Up => NavigationAction::Up,
-        KeyCode::Enter | KeyCode::Right => NavigationAction::Enter,
-        KeyCode::Left => NavigationAction::Back,
-        KeyCode::Char(' ') => NavigationAction::TogglePause,
-        KeyCode::Char('s') => NavigationAction::Stop,
-        KeyCode::Char('n') | KeyCode::Char('>') => NavigationAction::NextTrack,
-        KeyCode::Char('p') | KeyCode::Char('<') => NavigationAction::PreviousTrack,
-        KeyCode::Char('q') => NavigationAction::Quit,
-        _ => NavigationAction::None,
+    /// True for actions a count prefix applies to: it repeats them (`5j`)
+    /// or, for `top`/`bottom`, picks the row (`5G`). Other actions ignore it.
+    pub fn takes_count(self) -> bool {
+        matches!(
+            self,
+            A::Up
+                | A::Down
+                | A::PageUp
+                | A::PageDown
+                | A::Top
+                | A::Bottom
+                | A::Open
+                | A::Back
+                | A::FocusNext
+                | A::FocusPrevious
+                | A::NextTrack
+                | A::PreviousTrack
+                | A::NextChapter
+                | A::PreviousChapter
+                | A::VolumeUp
+                | A::VolumeDown
+                | A::MoveUpInQueue
+                | A::MoveDownInQueue
+                | A::RemoveFromQueue
+                | A::GrowWidth
+                | A::ShrinkWidth
+                | A::GrowHeight
+                | A::ShrinkHeight
+        )
     }
 }
+
+/// Browser bindings of the default preset.
+pub const DEFAULT_BROWSER: &[(&str, NavigationAction)] = &[
+    ("down", A::Down),
+    ("up", A::Up),
+    ("pagedown", A::PageDown),
+    ("pageup", A::PageUp),
+    ("home", A::Top),
+    ("end", A::Bottom),
+    ("gg", A::Top),
+    ("G", A::Bottom),
+    ("enter", A::Enter),
+    ("right", A::Open),
+    ("left", A::Back),
+    ("space", A::TogglePause),
+    ("s", A::Stop),
+    ("a", A::Enqueue),
+    ("W", A::SaveQueue),
+    ("Q", A::ShowQueue),
+    ("m", A::CycleBrowseMode),
+    ("/", A::Search),
+    ("f", A::Find),
+    (":", A::CommandLine),
+    ("c", A::CycleColumns),
+    (".", A::ToggleHidden),
+    ("*", A::ToggleAllFiles),
+    ("v", A::PreviewImage),
+    ("o", A::CycleSort),
+    ("O", A::ReverseSort),
+    ("d", A::ToggleDirsFirst),
+    ("n", A::NextTrack),
+    (">", A::NextTrack),
+    ("p", A::PreviousTrack),
+    ("<", A::PreviousTrack),
+    ("]", A::NextChapter),
+    ("[", A::PreviousChapter),
+    ("C", A::ChapterList),
+    ("+", A::VolumeUp),
+    ("=", A::VolumeUp),
+    ("-", A::VolumeDown),
+    ("r", A::CycleRepeat),
+    ("z", A::ToggleShuffle),
+    ("T", A::CycleTheme),
+    ("?", A::Help),
+    ("q", A::Quit),
+    ("R", A::RescanLibrary),
+    // Shift+digit arrives as the symbol or, with keyboard enhancements, as
+    // the digit with SHIFT held
+    ("!", A::ToggleSection(1)),
+    ("@", A::ToggleSection(2)),
+    ("#", A::ToggleSection(3)),
+    ("$", A::ToggleSection(4)),
+    ("shift-1", A::ToggleSection(1)),
+    ("shift-2", A::ToggleSection(2)),
+    ("shift-3", A::ToggleSection(3)),
+    ("shift-4", A::ToggleSection(4)),
+    ("ctrl-right", A::GrowWidth),
+    ("ctrl-left", A::ShrinkWidth),
+    ("ctrl-up", A::GrowHeight),
+    ("ctrl-down", A::ShrinkHeight),
+    ("ctrl-r", A::ResetLayout),
+    ("tab", A::FocusNext),
+    ("shift-tab", A::FocusPrevious),
+    ("alt-1", A::FocusPane(1)),
+    ("alt-2", A::FocusPane(2)),
+    ("alt-3", A::FocusPane(3)),
+    ("alt-4", A::FocusPane(4)),
+    ("M", A::ToggleMiniPlayer),
+];
+
+/// Queue view bindings of the default preset; browser bindings apply too.
+pub const DEFAULT_QUEUE: &[(&str, NavigationAction)] = &[
+    ("x", A::RemoveFromQueue),
+    ("delete", A::RemoveFromQueue),
+    ("K", A::MoveUpInQueue),
+    ("J", A::MoveDownInQueue),
+    ("X", A::ClearQueue),
+];
+
+/// Search prompt bindings of the default preset. Unbound characters are typed.
+pub const DEFAULT_SEARCH: &[(&str, NavigationAction)] = &[
+    ("enter", A::Confirm),
+    ("esc", A::Cancel),
+    ("backspace", A::DeleteChar),
+    ("down", A::Down),
+    ("up", A::Up),
+];
+
+/// Chapter list bindings of the default preset.
+pub const DEFAULT_CHAPTERS: &[(&str, NavigationAction)] = &[
+    ("down", A::Down),
+    ("up", A::Up),
+    ("j", A::Down),
+    ("k", A::Up),
+    ("home", A::Top),
+    ("end", A::Bottom),
+    ("enter", A::Enter),
+    ("esc", A::Cancel),
+    ("q", A::Cancel),
+    ("C", A::Cancel),
+];
+
+/// Bindings answering the resume offer, over the browser ones.
+pub const DEFAULT_RESUME: &[(&str, NavigationAction)] = &[
+    ("y", A::Confirm),
+    ("n", A::Cancel),
+    ("esc", A::Cancel),
+];
+
+/// Browser bindings the vim preset adds to the default ones.
+pub const VIM_BROWSER: &[(&str, NavigationAction)] = &[
+    ("j", A::Down),
+    ("k", A::Up),
+    ("h", A::Back),
+    ("l", A::Open),
+    ("ctrl-d", A::PageDown),
+    ("ctrl-u", A::PageUp),
+    ("ctrl-f", A::PageDown),
+    ("ctrl-b", A::PageUp),
+];
+
+/// Search prompt bindings the vim preset adds to the default ones.
+pub const VIM_SEARCH: &[(&str, NavigationAction)] = &[
+    ("ctrl-n", A::Down),
+    ("ctrl-p", A::Up),
+    ("ctrl-h", A::DeleteChar),
+];
+
+/// What a key does in a prompt or overlay whose keys are fixed rather than
+/// taken from the keymap. Each prompt's handler and the help overlay both
+/// read the prompt's table below, so the two can't disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptAction {
+    Next,
+    Previous,
+    Confirm,
+    Enqueue,
+    Reveal,
+    Complete,
+    CompleteBack,
+    Filter,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+    DeleteChar,
+    Cancel,
+    Close,
+}
+
+/// Fixed keys of one prompt: the keys, their action and its description.
+pub type PromptKeys = &'static [(&'static [&'static str], PromptAction, &'static str)];
+
+use PromptAction as P;
+
+/// Keys of a confirmed (no longer edited) search.
+pub const CONFIRMED_SEARCH_KEYS: PromptKeys = &[
+    (&["n"], P::Next, "Select the next match"),
+    (&["N"], P::Previous, "Select the previous match"),
+    (&["esc"], P::Cancel, "Clear the search"),
+];
+
+/// Keys of the finder popup; other characters are typed into the query.
+pub const FINDER_KEYS: PromptKeys = &[
+    (&["down", "ctrl-n"], P::Next, "Select the next result"),
+    (&["up", "ctrl-p"], P::Previous, "Select the previous result"),
+    (&["enter"], P::Confirm, "Play the selected track"),
+    (&["tab"], P::Enqueue, "Add the selected track to the queue"),
+    (
+        &["ctrl-o"],
+        P::Reveal,
+        "Show the selected track in the browser",
+    ),
+    (&["backspace"], P::DeleteChar, "Delete the last character"),
+    (&["esc"], P::Cancel, "Close the finder"),
+];
+
+/// Keys of the command line; other characters are typed into it.
+pub const COMMAND_LINE_KEYS: PromptKeys = &[
+    (&["enter"], P::Confirm, "Run the command"),
+    (&["tab"], P::Complete, "Complete the word"),
+    (&["backtab"], P::CompleteBack, "Cycle the completions back"),
+    (&["up"], P::Previous, "Recall an earlier command"),
+    (&["down"], P::Next, "Recall a later command"),
+    (
+        &["backspace"],
+        P::DeleteChar,
+        "Delete the last character, closing when empty",
+    ),
+    (&["esc"], P::Cancel, "Close the command line"),
+];
+
+/// Keys of the "save queue as" prompt; other characters are typed into it.
+pub const SAVE_PROMPT_KEYS: PromptKeys = &[
+    (
+        &["enter"],
+        P::Confirm,
+        "Save the queue under the typed name",
+    ),
+    (&["backspace"], P::DeleteChar, "Delete the last character"),
+    (&["esc"], P::Cancel, "Cancel"),
+];
+
+/// Keys of the help overlay.
+pub const HELP_KEYS: PromptKeys = &[
+    (&["down", "j"], P::Next, "Scroll down a line"),
+    (&["up", "k"], P::Previous, "Scroll up a line"),
+    (
+        &["pagedown", "space", "ctrl-d", "ctrl-f"],
+        P::PageDown,
+        "Scroll down a page",
+    ),
+    (
+        &["pageup", "ctrl-u", "ctrl-b"],
+        P::PageUp,
+        "Scroll up a page",
+    ),
+    (&["home", "g"], P::Top, "Go to the top"),
+    (&["end", "G"], P::Bottom, "Go to the bottom"),
+    (&["/"], P::Filter, "Filter the keys"),
+    (&["esc"], P::Cancel, "Clear the filter, or close the help"),
+    (&["q", "?"], P::Close, "Close the help"),
+];
+
+/// Keys of the help overlay while its filter is typed.
+pub const HELP_FILTER_KEYS: PromptKeys = &[
+    (&["enter"], P::Confirm, "Keep the filter"),
+    (&["down"], P::Next, "Scroll down a line"),
+    (&["up"], P::Previous, "Scroll up a line"),
+    (&["backspace"], P::DeleteChar, "Delete the last character"),
+    (&["esc"], P::Cancel, "Clear the filter"),
+];
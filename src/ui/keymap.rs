@@ -0,0 +1,698 @@
+// src/ui/keymap.rs
+//! Remappable key bindings: chords with modifiers, multi-key sequences
+//! (`gg`), per-mode maps and a numeric count prefix (`5j`).
+//!
+//! Configured under `[keys]`, e.g. `[keys.browser]` `"ctrl-n" = "next_track"`.
+
+use std::{collections::BTreeMap, fmt};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer};
+
+use super::keybindings::{
+    NavigationAction, PromptAction, PromptKeys, DEFAULT_BROWSER, DEFAULT_CHAPTERS, DEFAULT_QUEUE,
+    DEFAULT_RESUME, DEFAULT_SEARCH, VIM_BROWSER, VIM_SEARCH,
+};
+
+/// Largest count prefix; more digits are ignored.
+pub const MAX_COUNT: usize = 9999;
+
+/// Names of keys that aren't a single character.
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("space", KeyCode::Char(' ')),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+];
+
+/// One key press with its modifiers, normalized so that the same key
+/// compares equal however the terminal reported it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Normalize a key press. Shift is folded into letters and symbols
+    /// (`shift-g` is `G`) but kept on digits, which have no shifted form
+    /// of their own under keyboard enhancements.
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if !c.is_ascii_digit() => {
+                let shifted = modifiers.contains(KeyModifiers::SHIFT);
+                modifiers.remove(KeyModifiers::SHIFT);
+                if shifted {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            other => other,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Chord of a key event.
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Character typed by this chord, if it is a plain character.
+    pub fn char(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c) if (self.modifiers - KeyModifiers::SHIFT).is_empty() => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(flag) {
+                f.write_str(name)?;
+            }
+        }
+        if let Some((name, _)) = NAMED_KEYS.iter().find(|(_, code)| *code == self.code) {
+            return f.write_str(name);
+        }
+        match self.code {
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Parse a key description such as `ctrl-n`, `shift-tab`, `G`, `gg` or
+/// `space p`. Space-separated parts, or a run of plain characters, form a
+/// sequence of key presses.
+pub fn parse_keys(text: &str) -> Result<Vec<KeyChord>, String> {
+    let mut keys = Vec::new();
+    for part in text.split_whitespace() {
+        keys.extend(parse_part(part).ok_or_else(|| format!("unknown key \"{}\"", part))?);
+    }
+    if keys.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(keys)
+}
+
+/// Parse one whitespace-free part of a key description.
+fn parse_part(part: &str) -> Option<Vec<KeyChord>> {
+    let mut rest = part;
+    let mut modifiers = KeyModifiers::NONE;
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        let found = [
+            ("ctrl-", KeyModifiers::CONTROL),
+            ("alt-", KeyModifiers::ALT),
+            ("shift-", KeyModifiers::SHIFT),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| lower.starts_with(prefix) && rest.len() > prefix.len());
+        match found {
+            Some((prefix, flag)) => {
+                modifiers |= flag;
+                rest = &rest[prefix.len()..];
+            }
+            None => break,
+        }
+    }
+
+    let lower = rest.to_ascii_lowercase();
+    if let Some((_, code)) = NAMED_KEYS.iter().find(|(name, _)| *name == lower) {
+        return Some(vec![KeyChord::new(*code, modifiers)]);
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok())
+        && (1..=12).contains(&n)
+    {
+        return Some(vec![KeyChord::new(KeyCode::F(n), modifiers)]);
+    }
+    if lower
+        .strip_prefix('f')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    {
+        // A function key this terminal library doesn't have, not `f` `1` `3`
+        return None;
+    }
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(vec![KeyChord::new(KeyCode::Char(c), modifiers)]),
+        // Several plain characters type a sequence, like `gg`; a dash more
+        // likely means a misspelled modifier
+        (Some(_), Some(_)) if modifiers.is_empty() && !rest.contains('-') => Some(
+            rest.chars()
+                .map(|c| KeyChord::new(KeyCode::Char(c), modifiers))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Format a key sequence the way it is written in the config.
+pub fn format_keys(keys: &[KeyChord]) -> String {
+    let plain = keys.iter().all(|k| k.char().is_some_and(|c| c != ' '));
+    let parts: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+    parts.join(if plain { "" } else { " " })
+}
+
+/// Action of `key` in the fixed keys of a prompt, if it has one.
+pub fn prompt_action(table: PromptKeys, key: &KeyEvent) -> Option<PromptAction> {
+    let chord = KeyChord::from_event(key);
+    table
+        .iter()
+        .find(|(keys, _, _)| keys.iter().any(|k| parse_keys(k) == Ok(vec![chord])))
+        .map(|(_, action, _)| *action)
+}
+
+/// Input context that selects a keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMode {
+    /// File, library and playlist browser
+    Browser,
+    /// Play queue view; unbound keys fall back to the browser map
+    Queue,
+    /// Search prompt; unbound characters are typed into the query
+    Search,
+    /// Chapter list of the playing track
+    Chapters,
+    /// Offer to resume the track that just started, over the browser keys
+    Resume,
+}
+
+impl KeyMode {
+    /// Every mode, in the order the help lists them.
+    pub const ALL: [KeyMode; 5] = [
+        KeyMode::Browser,
+        KeyMode::Queue,
+        KeyMode::Search,
+        KeyMode::Chapters,
+        KeyMode::Resume,
+    ];
+
+    /// Section of `[keys]` that configures this mode.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyMode::Browser => "browser",
+            KeyMode::Queue => "queue",
+            KeyMode::Search => "search",
+            KeyMode::Chapters => "chapters",
+            KeyMode::Resume => "resume",
+        }
+    }
+
+    /// Mode whose bindings apply when this one has none for a key.
+    fn fallback(self) -> Option<Self> {
+        match self {
+            KeyMode::Queue => Some(KeyMode::Browser),
+            _ => None,
+        }
+    }
+}
+
+/// A key sequence bound to an action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub keys: Vec<KeyChord>,
+    pub action: NavigationAction,
+}
+
+/// Built-in starting points for the bindings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    /// Arrow keys and single-letter commands
+    #[default]
+    Default,
+    /// The default keys plus `h`/`j`/`k`/`l` and ctrl scrolling
+    Vim,
+}
+
+/// Key bindings of every mode.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    browser: Vec<Binding>,
+    queue: Vec<Binding>,
+    search: Vec<Binding>,
+    chapters: Vec<Binding>,
+    resume: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(KeyPreset::Default)
+    }
+}
+
+/// Outcome of feeding a key press to the keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyResult {
+    /// A binding matched, with the count typed before it
+    Action(NavigationAction, Option<usize>),
+    /// The key started or continued a count or a sequence
+    Pending,
+    /// Nothing is bound to the key (in the current sequence)
+    Unbound,
+}
+
+/// Keys typed so far towards a count or a multi-key sequence.
+#[derive(Debug, Default, Clone)]
+pub struct KeyInput {
+    pending: Vec<KeyChord>,
+    count: Option<usize>,
+    /// Earlier keys of a sequence the last key press broke off
+    abandoned: Vec<KeyChord>,
+}
+
+impl KeyInput {
+    /// Forget the partial input.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.count = None;
+    }
+
+    /// Keys of a sequence that the last key press broke off, which no
+    /// binding took (e.g. the `g` of `gx`). The search prompt types them.
+    pub fn abandoned(&self) -> &[KeyChord] {
+        &self.abandoned
+    }
+
+    /// Partial input for display, e.g. `5g`; empty when nothing is pending.
+    pub fn text(&self) -> String {
+        let count = self.count.map(|c| c.to_string()).unwrap_or_default();
+        format!("{}{}", count, format_keys(&self.pending))
+    }
+}
+
+impl Keymap {
+    /// Bindings of a built-in preset.
+    pub fn preset(preset: KeyPreset) -> Self {
+        let table = |entries: &[(&str, NavigationAction)]| -> Vec<Binding> {
+            entries
+                .iter()
+                .map(|(keys, action)| Binding {
+                    keys: parse_keys(keys).expect("valid preset key"),
+                    action: *action,
+                })
+                .collect()
+        };
+        let mut keymap = Self {
+            browser: table(DEFAULT_BROWSER),
+            queue: table(DEFAULT_QUEUE),
+            search: table(DEFAULT_SEARCH),
+            chapters: table(DEFAULT_CHAPTERS),
+            resume: table(DEFAULT_RESUME),
+        };
+        if preset == KeyPreset::Vim {
+            for binding in table(VIM_BROWSER) {
+                keymap.bind(KeyMode::Browser, binding);
+            }
+            for binding in table(VIM_SEARCH) {
+                keymap.bind(KeyMode::Search, binding);
+            }
+        }
+        keymap
+    }
+
+    /// Bindings of `mode` alone, without its fallback.
+    pub fn bindings(&self, mode: KeyMode) -> &[Binding] {
+        match mode {
+            KeyMode::Browser => &self.browser,
+            KeyMode::Queue => &self.queue,
+            KeyMode::Search => &self.search,
+            KeyMode::Chapters => &self.chapters,
+            KeyMode::Resume => &self.resume,
+        }
+    }
+
+    fn bindings_mut(&mut self, mode: KeyMode) -> &mut Vec<Binding> {
+        match mode {
+            KeyMode::Browser => &mut self.browser,
+            KeyMode::Queue => &mut self.queue,
+            KeyMode::Search => &mut self.search,
+            KeyMode::Chapters => &mut self.chapters,
+            KeyMode::Resume => &mut self.resume,
+        }
+    }
+
+    /// Bind a key sequence in `mode`, replacing an existing binding of it.
+    pub fn bind(&mut self, mode: KeyMode, binding: Binding) {
+        let bindings = self.bindings_mut(mode);
+        match bindings.iter_mut().find(|b| b.keys == binding.keys) {
+            Some(existing) => existing.action = binding.action,
+            None => bindings.push(binding),
+        }
+    }
+
+    /// Key sequences bound to `action` in `mode` (or its fallback).
+    pub fn keys_for(&self, mode: KeyMode, action: NavigationAction) -> Vec<&[KeyChord]> {
+        self.effective(mode)
+            .into_iter()
+            .filter(|b| b.action == action)
+            .map(|b| b.keys.as_slice())
+            .collect()
+    }
+
+    /// Bindings in effect in `mode`: its own, then unshadowed fallback ones.
+    pub fn effective(&self, mode: KeyMode) -> Vec<&Binding> {
+        let own = self.bindings(mode);
+        let mut all: Vec<&Binding> = own.iter().collect();
+        if let Some(fallback) = mode.fallback() {
+            all.extend(
+                self.bindings(fallback)
+                    .iter()
+                    .filter(|b| !own.iter().any(|o| o.keys == b.keys)),
+            );
+        }
+        all.retain(|b| b.action != NavigationAction::None);
+        all
+    }
+
+    /// Report a sequence that is a prefix of another in the same mode,
+    /// which would make the longer one unreachable.
+    fn check_conflicts(&self) -> Result<(), String> {
+        for mode in KeyMode::ALL {
+            let bindings = self.effective(mode);
+            for short in &bindings {
+                if let Some(long) = bindings
+                    .iter()
+                    .find(|b| b.keys.len() > short.keys.len() && b.keys.starts_with(&short.keys))
+                {
+                    return Err(format!(
+                        "in [keys.{}], \"{}\" ({}) hides \"{}\" ({}); bind one of them to \"none\"",
+                        mode.name(),
+                        format_keys(&short.keys),
+                        short.action.name(),
+                        format_keys(&long.keys),
+                        long.action.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Feed one key press in `mode`, tracking counts and sequences in `input`.
+    pub fn feed(&self, mode: KeyMode, input: &mut KeyInput, key: &KeyEvent) -> KeyResult {
+        self.feed_layered(&[mode], input, key)
+    }
+
+    /// Feed one key press with the bindings of `modes` layered, the first
+    /// taking precedence (e.g. the resume offer over the browser).
+    pub fn feed_layered(
+        &self,
+        modes: &[KeyMode],
+        input: &mut KeyInput,
+        key: &KeyEvent,
+    ) -> KeyResult {
+        input.abandoned.clear();
+        let chord = KeyChord::from_event(key);
+        let mut bindings: Vec<&Binding> = Vec::new();
+        for &mode in modes {
+            for binding in self.effective(mode) {
+                if !bindings.iter().any(|b| b.keys == binding.keys) {
+                    bindings.push(binding);
+                }
+            }
+        }
+
+        // Digits before a command form its count, unless bound themselves
+        if !modes.contains(&KeyMode::Search)
+            && input.pending.is_empty()
+            && let Some(digit) = chord.char().and_then(|c| c.to_digit(10))
+            && (digit > 0 || input.count.is_some())
+            && !bindings.iter().any(|b| b.keys[0] == chord)
+        {
+            let count = input
+                .count
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit as usize);
+            input.count = Some(count.min(MAX_COUNT));
+            return KeyResult::Pending;
+        }
+
+        input.pending.push(chord);
+        if let Some(binding) = bindings.iter().find(|b| b.keys == input.pending) {
+            let result = KeyResult::Action(binding.action, input.count);
+            input.reset();
+            return result;
+        }
+        if bindings.iter().any(|b| b.keys.starts_with(&input.pending)) {
+            return KeyResult::Pending;
+        }
+        // The sequence broke off: give up its earlier keys and try the last
+        // one on its own
+        input.pending.pop();
+        let abandoned = std::mem::take(&mut input.pending);
+        input.reset();
+        let result = if abandoned.is_empty() {
+            KeyResult::Unbound
+        } else {
+            self.feed_layered(modes, input, key)
+        };
+        input.abandoned = abandoned;
+        result
+    }
+}
+
+/// `[keys]` as written in the config file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeysConfig {
+    preset: KeyPreset,
+    browser: BTreeMap<String, String>,
+    queue: BTreeMap<String, String>,
+    search: BTreeMap<String, String>,
+    chapters: BTreeMap<String, String>,
+    resume: BTreeMap<String, String>,
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let raw = KeysConfig::deserialize(d)?;
+        let mut keymap = Keymap::preset(raw.preset);
+        for (mode, table) in [
+            (KeyMode::Browser, &raw.browser),
+            (KeyMode::Queue, &raw.queue),
+            (KeyMode::Search, &raw.search),
+            (KeyMode::Chapters, &raw.chapters),
+            (KeyMode::Resume, &raw.resume),
+        ] {
+            // Different spellings of one key ("G", "shift-g") must agree
+            let mut seen: Vec<(Vec<KeyChord>, &str)> = Vec::new();
+            for (keys, action) in table {
+                let error = |msg: String| {
+                    serde::de::Error::custom(format!("in [keys.{}]: {}", mode.name(), msg))
+                };
+                let chords = parse_keys(keys).map_err(error)?;
+                let action = NavigationAction::from_name(action)
+                    .ok_or_else(|| error(format!("unknown action \"{}\"", action)))?;
+                if let Some((_, other)) = seen.iter().find(|(k, _)| *k == chords) {
+                    return Err(error(format!(
+                        "\"{}\" and \"{}\" are the same key",
+                        other, keys
+                    )));
+                }
+                seen.push((chords.clone(), keys));
+                keymap.bind(
+                    mode,
+                    Binding {
+                        keys: chords,
+                        action,
+                    },
+                );
+            }
+        }
+        keymap.check_conflicts().map_err(serde::de::Error::custom)?;
+        Ok(keymap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord::new(code, modifiers)
+    }
+
+    fn press(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn down() -> KeyEvent {
+        KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn parses_modifiers_and_named_keys() {
+        assert_eq!(
+            parse_keys("ctrl-n"),
+            Ok(vec![chord(KeyCode::Char('n'), KeyModifiers::CONTROL)])
+        );
+        assert_eq!(
+            parse_keys("shift-tab"),
+            Ok(vec![chord(KeyCode::BackTab, KeyModifiers::NONE)])
+        );
+        assert_eq!(
+            parse_keys("f5"),
+            Ok(vec![chord(KeyCode::F(5), KeyModifiers::NONE)])
+        );
+    }
+
+    #[test]
+    fn folds_shift_into_letters() {
+        assert_eq!(parse_keys("G"), parse_keys("shift-g"));
+        assert_ne!(parse_keys("G"), parse_keys("g"));
+    }
+
+    #[test]
+    fn parses_sequences() {
+        let g = chord(KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(parse_keys("gg"), Ok(vec![g, g]));
+        assert_eq!(
+            parse_keys("space p"),
+            Ok(vec![
+                chord(KeyCode::Char(' '), KeyModifiers::NONE),
+                chord(KeyCode::Char('p'), KeyModifiers::NONE),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        for text in ["", "   ", "ctrl-", "f13", "ctrl-ab", "ctlr-n"] {
+            assert!(parse_keys(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn caps_the_count() {
+        let keymap = Keymap::default();
+        let mut input = KeyInput::default();
+        for _ in 0..12 {
+            assert_eq!(
+                keymap.feed(KeyMode::Browser, &mut input, &press('9')),
+                KeyResult::Pending
+            );
+        }
+        assert_eq!(
+            keymap.feed(KeyMode::Browser, &mut input, &down()),
+            KeyResult::Action(NavigationAction::Down, Some(MAX_COUNT))
+        );
+    }
+
+    #[test]
+    fn retries_the_key_that_breaks_a_sequence() {
+        let keymap = Keymap::default();
+        let mut input = KeyInput::default();
+        keymap.feed(KeyMode::Browser, &mut input, &press('g'));
+        assert_eq!(
+            keymap.feed(KeyMode::Browser, &mut input, &down()),
+            KeyResult::Action(NavigationAction::Down, None)
+        );
+        assert_eq!(
+            input.abandoned(),
+            [chord(KeyCode::Char('g'), KeyModifiers::NONE)]
+        );
+        assert!(input.text().is_empty());
+    }
+
+    #[test]
+    fn reports_abandoned_search_keys() {
+        let mut keymap = Keymap::default();
+        keymap.bind(
+            KeyMode::Search,
+            Binding {
+                keys: parse_keys("jk").unwrap(),
+                action: NavigationAction::Cancel,
+            },
+        );
+        let mut input = KeyInput::default();
+        assert_eq!(
+            keymap.feed(KeyMode::Search, &mut input, &press('j')),
+            KeyResult::Pending
+        );
+        assert_eq!(
+            keymap.feed(KeyMode::Search, &mut input, &press('x')),
+            KeyResult::Unbound
+        );
+        let typed: String = input
+            .abandoned()
+            .iter()
+            .filter_map(KeyChord::char)
+            .collect();
+        assert_eq!(typed, "j");
+        assert_eq!(
+            keymap.feed(KeyMode::Search, &mut input, &press('x')),
+            KeyResult::Unbound
+        );
+        assert!(input.abandoned().is_empty());
+    }
+
+    #[test]
+    fn prompt_keys_are_single_distinct_chords() {
+        use crate::ui::keybindings::{
+            COMMAND_LINE_KEYS, CONFIRMED_SEARCH_KEYS, FINDER_KEYS, HELP_FILTER_KEYS, HELP_KEYS,
+            SAVE_PROMPT_KEYS,
+        };
+        for table in [
+            CONFIRMED_SEARCH_KEYS,
+            FINDER_KEYS,
+            COMMAND_LINE_KEYS,
+            SAVE_PROMPT_KEYS,
+            HELP_KEYS,
+            HELP_FILTER_KEYS,
+        ] {
+            let mut seen = Vec::new();
+            for (keys, _, _) in table {
+                for key in keys.iter() {
+                    let chords = parse_keys(key).unwrap();
+                    assert_eq!(chords.len(), 1, "{key}");
+                    assert!(!seen.contains(&chords[0]), "{key} listed twice");
+                    seen.push(chords[0]);
+                }
+            }
+        }
+        let shifted_n = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+        assert_eq!(
+            prompt_action(CONFIRMED_SEARCH_KEYS, &shifted_n),
+            Some(PromptAction::Previous)
+        );
+        let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(
+            prompt_action(FINDER_KEYS, &ctrl_n),
+            Some(PromptAction::Next)
+        );
+        assert_eq!(prompt_action(FINDER_KEYS, &press('n')), None);
+    }
+}
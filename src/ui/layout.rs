@@ -1,10 +1,67 @@
 // src/ui/layout.rs
 //! Layout computation for the UI panels.
+//!
+//! The panels are laid out by a tree from the config: splits place their
+//! children side by side (`columns`) or top to bottom (`rows`), leaves name
+//! a pane. Hidden panes leave their space to their siblings.
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use std::fmt;
+
+use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::config::LayoutConfig;
+
+/// Percent a resize key moves a border by.
+pub const RESIZE_STEP: i32 = 2;
+
+/// Size, min and max cells of a child of a split.
+type Spec = (Size, u16, u16);
+
+/// A panel of the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pane {
+    Files,
+    Player,
+    Artwork,
+    Visualizer,
+}
+
+impl Pane {
+    /// Every pane, in the order of their numbers.
+    pub const ALL: [Pane; 4] = [Pane::Files, Pane::Player, Pane::Artwork, Pane::Visualizer];
+
+    /// Number shown in the pane's title (1-4).
+    pub fn number(self) -> usize {
+        Self::ALL.iter().position(|&p| p == self).unwrap_or(0) + 1
+    }
+
+    /// Pane with the number `n` (1-4).
+    pub fn from_number(n: usize) -> Option<Self> {
+        n.checked_sub(1).and_then(|i| Self::ALL.get(i).copied())
+    }
+
+    /// Name used in the config.
+    pub fn name(self) -> &'static str {
+        match self {
+            Pane::Files => "files",
+            Pane::Player => "player",
+            Pane::Artwork => "artwork",
+            Pane::Visualizer => "visualizer",
+        }
+    }
+}
+
+impl fmt::Display for Pane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
 /// Visibility state for UI sections.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SectionVisibility {
     pub files: bool,
     pub player: bool,
@@ -26,82 +83,610 @@ impl Default for SectionVisibility {
 impl SectionVisibility {
     /// Toggle a section by number (1-4).
     pub fn toggle(&mut self, section: usize) {
-        match section {
-            1 => self.files = !self.files,
-            2 => self.player = !self.player,
-            3 => self.artwork = !self.artwork,
-            4 => self.visualizer = !self.visualizer,
-            _ => {}
+        if let Some(pane) = Pane::from_number(section) {
+            *self.slot(pane) = !self.shows(pane);
+        }
+    }
+
+    /// True if `pane` is shown.
+    pub fn shows(&self, pane: Pane) -> bool {
+        match pane {
+            Pane::Files => self.files,
+            Pane::Player => self.player,
+            Pane::Artwork => self.artwork,
+            Pane::Visualizer => self.visualizer,
+        }
+    }
+
+    fn slot(&mut self, pane: Pane) -> &mut bool {
+        match pane {
+            Pane::Files => &mut self.files,
+            Pane::Player => &mut self.player,
+            Pane::Artwork => &mut self.artwork,
+            Pane::Visualizer => &mut self.visualizer,
+        }
+    }
+}
+
+/// How a split places its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    /// Side by side
+    Columns,
+    /// Top to bottom
+    Rows,
+}
+
+impl From<SplitDirection> for Direction {
+    fn from(direction: SplitDirection) -> Self {
+        match direction {
+            SplitDirection::Columns => Direction::Horizontal,
+            SplitDirection::Rows => Direction::Vertical,
+        }
+    }
+}
+
+/// Size of a node along its split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Size {
+    /// Percent of the split
+    Percent(u16),
+    /// Number of cells
+    Cells(u16),
+    /// An even share of what the other children leave
+    #[default]
+    Fill,
+}
+
+impl<'de> Deserialize<'de> for Size {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Cells(u16),
+            Text(String),
+        }
+        let invalid = || {
+            serde::de::Error::custom(
+                "a size is a percentage like \"30%\" (1-100) or a number of cells",
+            )
+        };
+        match Raw::deserialize(d).map_err(|_| invalid())? {
+            Raw::Cells(n) => Ok(Size::Cells(n)),
+            Raw::Text(text) => match text.trim().strip_suffix('%').map(|p| p.trim().parse()) {
+                Some(Ok(p)) if (1..=100).contains(&p) => Ok(Size::Percent(p)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+}
+
+/// What a layout node holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeContent {
+    Pane(Pane),
+    Split(SplitDirection, Vec<LayoutNode>),
+}
+
+/// A node of the layout tree: a pane or a split, with its size in the
+/// split around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutNode {
+    pub content: NodeContent,
+    pub size: Size,
+    /// Fewest cells the node gets when there is room
+    pub min: u16,
+    /// Most cells the node gets
+    pub max: u16,
+}
+
+impl LayoutNode {
+    /// A pane of `size`.
+    pub fn pane(pane: Pane, size: Size) -> Self {
+        Self::new(NodeContent::Pane(pane), size)
+    }
+
+    /// A split of `size` laying out `children` in `direction`.
+    pub fn split(direction: SplitDirection, size: Size, children: Vec<LayoutNode>) -> Self {
+        Self::new(NodeContent::Split(direction, children), size)
+    }
+
+    fn new(content: NodeContent, size: Size) -> Self {
+        Self {
+            content,
+            size,
+            min: 0,
+            max: u16::MAX,
+        }
+    }
+
+    /// The panes below this node, in order.
+    pub fn panes(&self) -> Vec<Pane> {
+        match &self.content {
+            NodeContent::Pane(pane) => vec![*pane],
+            NodeContent::Split(_, children) => children.iter().flat_map(Self::panes).collect(),
+        }
+    }
+
+    /// True if a pane below this node is shown.
+    fn is_visible(&self, visibility: &SectionVisibility) -> bool {
+        match &self.content {
+            NodeContent::Pane(pane) => visibility.shows(*pane),
+            NodeContent::Split(_, children) => children.iter().any(|c| c.is_visible(visibility)),
+        }
+    }
+
+    /// The shown panes below this node with their sizes when stacked:
+    /// cells and bounds are kept from rows splits (`in_rows`), the rest
+    /// share the height.
+    fn stacked_specs(
+        &self,
+        visibility: &SectionVisibility,
+        in_rows: bool,
+        out: &mut Vec<(Pane, Spec)>,
+    ) {
+        match &self.content {
+            NodeContent::Pane(pane) if visibility.shows(*pane) => {
+                let spec = match self.size {
+                    Size::Cells(_) if in_rows => (self.size, self.min, self.max),
+                    _ if in_rows => (Size::Fill, self.min, self.max),
+                    _ => (Size::Fill, 0, u16::MAX),
+                };
+                out.push((*pane, spec));
+            }
+            NodeContent::Pane(_) => {}
+            NodeContent::Split(direction, children) => {
+                for child in children {
+                    child.stacked_specs(visibility, *direction == SplitDirection::Rows, out);
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LayoutNode {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Raw {
+            pane: Option<Pane>,
+            split: Option<SplitDirection>,
+            #[serde(default)]
+            children: Vec<LayoutNode>,
+            #[serde(default)]
+            size: Size,
+            #[serde(default)]
+            min: u16,
+            max: Option<u16>,
+        }
+        let raw = Raw::deserialize(d)?;
+        let content = match (raw.pane, raw.split) {
+            (Some(pane), None) if raw.children.is_empty() => NodeContent::Pane(pane),
+            (Some(_), None) => {
+                return Err(serde::de::Error::custom("a pane has no children"));
+            }
+            (None, Some(_)) if raw.children.is_empty() => {
+                return Err(serde::de::Error::custom("a split needs children"));
+            }
+            (None, Some(direction)) => NodeContent::Split(direction, raw.children),
+            _ => {
+                return Err(serde::de::Error::custom(
+                    "a layout node is either a `pane` or a `split` with `children`",
+                ));
+            }
+        };
+        let max = raw.max.unwrap_or(u16::MAX);
+        if raw.min > max {
+            return Err(serde::de::Error::custom("min is larger than max"));
         }
+        Ok(Self {
+            content,
+            size: raw.size,
+            min: raw.min,
+            max,
+        })
     }
 }
 
+/// Relative widths of the file list, player and artwork in the default layout.
+pub const DEFAULT_WIDTHS: [u16; 3] = [18, 54, 28];
+
+/// Visualizer height of the default layout, in percent.
+pub const DEFAULT_VISUALIZER_HEIGHT: u16 = 20;
+
+/// The layout of chirpy before it was configurable: the file list, player
+/// and artwork side by side over the visualizer.
+pub fn default_layout() -> LayoutNode {
+    sized_layout(DEFAULT_WIDTHS, DEFAULT_VISUALIZER_HEIGHT)
+}
+
+/// The default arrangement with the panes sized as the old `files_width`,
+/// `player_width`, `artwork_width` and `visualizer_height` settings did:
+/// relative column widths and the visualizer height in percent.
+pub fn sized_layout(widths: [u16; 3], visualizer_height: u16) -> LayoutNode {
+    use SplitDirection::{Columns, Rows};
+    let total = widths.iter().map(|&w| u32::from(w)).sum::<u32>().max(1);
+    let percent = |w: u16| Size::Percent((u32::from(w) * 100 / total) as u16);
+    let height = visualizer_height.min(100);
+    LayoutNode::split(
+        Rows,
+        Size::Fill,
+        vec![
+            LayoutNode::split(
+                Columns,
+                Size::Percent(100 - height),
+                vec![
+                    LayoutNode::pane(Pane::Files, percent(widths[0])),
+                    LayoutNode::pane(Pane::Player, percent(widths[1])),
+                    LayoutNode::pane(Pane::Artwork, percent(widths[2])),
+                ],
+            ),
+            LayoutNode::pane(Pane::Visualizer, Size::Percent(height)),
+        ],
+    )
+}
+
 /// Computed layout areas for rendering.
+#[derive(Debug, Clone, Default)]
 pub struct ComputedLayout {
-    /// Main area (upper portion)
-    #[allow(dead_code)]
-    pub main_area: Rect,
-    /// Bottom visualizer area (if visible)
-    pub visualizer_area: Option<Rect>,
-    /// Column areas within main area
-    pub columns: Vec<Rect>,
-    /// Order of sections in columns
-    pub section_order: Vec<&'static str>,
-}
-
-/// Compute the layout based on total area and section visibility.
-pub fn compute_layout(area: Rect, visibility: &SectionVisibility) -> ComputedLayout {
-    // Reserve bottom 20% of the terminal for the audio visualizer only if
-    // the visualizer is enabled; otherwise the main UI gets 100% of the area.
-    let (main_area, visualizer_area) = if visibility.visualizer {
-        let vertical_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(80), Constraint::Percentage(20)])
-            .split(area);
-        (vertical_chunks[0], Some(vertical_chunks[1]))
-    } else {
-        (area, None)
-    };
+    /// Visible panes and their areas, in layout order
+    pub panes: Vec<(Pane, Rect)>,
+    /// True when the terminal is too narrow and the panes are stacked
+    pub stacked: bool,
+}
+
+impl ComputedLayout {
+    /// Area of `pane`, if shown.
+    pub fn area_of(&self, pane: Pane) -> Option<Rect> {
+        self.panes
+            .iter()
+            .find(|(p, _)| *p == pane)
+            .map(|(_, area)| *area)
+    }
 
-    // Build column weights dynamically based on visible sections
-    let mut section_order = Vec::new();
-    let mut weights = Vec::new();
+    /// The pane at `at`, with its area.
+    pub fn pane_at(&self, at: Position) -> Option<(Pane, Rect)> {
+        self.panes
+            .iter()
+            .find(|(_, area)| area.contains(at))
+            .copied()
+    }
+}
 
-    if visibility.files {
-        section_order.push("files");
-        weights.push(18u16);
+/// Compute the layout based on total area, section visibility and the
+/// configured tree. Below `stack_below` columns the shown panes are
+/// stacked top to bottom instead, keeping sizes given in cells.
+pub fn compute_layout(
+    area: Rect,
+    visibility: &SectionVisibility,
+    config: &LayoutConfig,
+) -> ComputedLayout {
+    let mut layout = ComputedLayout {
+        panes: Vec::new(),
+        stacked: area.width < config.stack_below,
+    };
+    if layout.stacked {
+        let mut leaves = Vec::new();
+        config.root.stacked_specs(visibility, false, &mut leaves);
+        let specs: Vec<Spec> = leaves.iter().map(|(_, spec)| *spec).collect();
+        let areas = split_area(area, SplitDirection::Rows, &specs);
+        for ((pane, _), area) in leaves.into_iter().zip(areas) {
+            layout.panes.push((pane, area));
+        }
+    } else if config.root.is_visible(visibility) {
+        place(&config.root, area, visibility, &mut layout.panes);
     }
-    if visibility.player {
-        section_order.push("player");
-        weights.push(54u16);
+    layout
+}
+
+/// Place the shown panes below `node` in `area`.
+fn place(
+    node: &LayoutNode,
+    area: Rect,
+    visibility: &SectionVisibility,
+    panes: &mut Vec<(Pane, Rect)>,
+) {
+    match &node.content {
+        NodeContent::Pane(pane) => panes.push((*pane, area)),
+        NodeContent::Split(direction, children) => {
+            let shown: Vec<&LayoutNode> = children
+                .iter()
+                .filter(|c| c.is_visible(visibility))
+                .collect();
+            let areas = split_area(area, *direction, &specs(&shown));
+            for (child, area) in shown.into_iter().zip(areas) {
+                place(child, area, visibility, panes);
+            }
+        }
     }
-    if visibility.artwork {
-        section_order.push("artwork");
-        weights.push(28u16);
+}
+
+fn specs(nodes: &[&LayoutNode]) -> Vec<Spec> {
+    nodes.iter().map(|n| (n.size, n.min, n.max)).collect()
+}
+
+/// Split `area` in `direction` among children sized by `specs`.
+fn split_area(area: Rect, direction: SplitDirection, specs: &[Spec]) -> Vec<Rect> {
+    let total = match direction {
+        SplitDirection::Columns => area.width,
+        SplitDirection::Rows => area.height,
+    };
+    let constraints: Vec<Constraint> = lengths(total, specs)
+        .into_iter()
+        .map(Constraint::Length)
+        .collect();
+    Layout::default()
+        .direction(direction.into())
+        .constraints(constraints)
+        .split(area)
+        .to_vec()
+}
+
+/// Cells each child gets out of `total`. Percentages and cell counts are
+/// taken first and fill children share the rest, all within their min and
+/// max. Space left over (by hidden siblings, rounding or max limits) goes
+/// to the children sized in percent, in proportion, or else to the last
+/// child that can grow; when there is too little, the last children shrink.
+fn lengths(total: u16, specs: &[Spec]) -> Vec<u16> {
+    let clamp = |len: u32, (_, min, max): Spec| len.clamp(min as u32, max as u32);
+    let fixed: Vec<Option<u32>> = specs
+        .iter()
+        .map(|&spec| match spec.0 {
+            Size::Percent(p) => Some(clamp(total as u32 * p as u32 / 100, spec)),
+            Size::Cells(n) => Some(clamp(n as u32, spec)),
+            Size::Fill => None,
+        })
+        .collect();
+    let fills = fixed.iter().filter(|l| l.is_none()).count() as u32;
+    let rest = (total as u32).saturating_sub(fixed.iter().flatten().sum());
+    let mut lengths: Vec<u32> = Vec::with_capacity(specs.len());
+    let mut nth_fill = 0;
+    for (len, &spec) in fixed.iter().zip(specs) {
+        lengths.push(len.unwrap_or_else(|| {
+            // The first fill children take the cells that don't divide evenly
+            let share = rest / fills + u32::from(nth_fill < rest % fills);
+            nth_fill += 1;
+            clamp(share, spec)
+        }));
     }
 
-    let columns: Vec<Rect> = if !weights.is_empty() {
-        let sum: u16 = weights.iter().copied().sum();
-        let constraints: Vec<Constraint> = weights
-            .into_iter()
-            .map(|w| Constraint::Percentage((w as u32 * 100 / sum as u32) as u16))
-            .collect();
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(constraints)
-            .split(main_area)
+    let sum: u32 = lengths.iter().sum();
+    if sum > total as u32 {
+        let mut over = sum - total as u32;
+        for len in lengths.iter_mut().rev() {
+            let cut = over.min(*len);
+            *len -= cut;
+            over -= cut;
+        }
+    } else {
+        let room = |i: usize, lengths: &[u32]| (specs[i].2 as u32).saturating_sub(lengths[i]);
+        let mut left = total as u32 - sum;
+        let weights: Vec<u32> = specs
             .iter()
-            .cloned()
-            .collect()
+            .map(|spec| match spec.0 {
+                Size::Percent(p) => p as u32,
+                _ => 0,
+            })
+            .collect();
+        let weight: u32 = weights.iter().sum();
+        if weight > 0 && left > 0 {
+            let start = left;
+            for i in 0..specs.len() {
+                let add = (start * weights[i] / weight)
+                    .min(room(i, &lengths))
+                    .min(left);
+                lengths[i] += add;
+                left -= add;
+            }
+        }
+        // Rounding leftovers, and everything when nothing is in percent
+        for i in (0..specs.len()).rev() {
+            let add = left.min(room(i, &lengths));
+            lengths[i] += add;
+            left -= add;
+        }
+    }
+    lengths.into_iter().map(|l| l as u16).collect()
+}
+
+/// Move the border of `pane` in `direction` by `step` percent (growing it
+/// when positive) in the innermost split of that direction where it has a
+/// shown neighbour, which gives or takes the space. Both sizes become
+/// percentages of the split. Returns false if there was nothing to resize.
+pub fn resize(
+    root: &mut LayoutNode,
+    area: Rect,
+    visibility: &SectionVisibility,
+    pane: Pane,
+    direction: SplitDirection,
+    step: i32,
+) -> bool {
+    let NodeContent::Split(split, children) = &mut root.content else {
+        return false;
+    };
+    if !visibility.shows(pane) {
+        return false;
+    }
+    let shown: Vec<usize> = (0..children.len())
+        .filter(|&i| children[i].is_visible(visibility))
+        .collect();
+    let Some(pos) = shown
+        .iter()
+        .position(|&i| children[i].panes().contains(&pane))
+    else {
+        return false;
+    };
+    let nodes: Vec<&LayoutNode> = shown.iter().map(|&i| &children[i]).collect();
+    let areas = split_area(area, *split, &specs(&nodes));
+    if resize(
+        &mut children[shown[pos]],
+        areas[pos],
+        visibility,
+        pane,
+        direction,
+        step,
+    ) {
+        return true;
+    }
+    if *split != direction || shown.len() < 2 {
+        return false;
+    }
+    let total = match direction {
+        SplitDirection::Columns => area.width,
+        SplitDirection::Rows => area.height,
+    } as i32;
+    if total == 0 {
+        return false;
+    }
+    let percent = |r: Rect| {
+        let len = match direction {
+            SplitDirection::Columns => r.width,
+            SplitDirection::Rows => r.height,
+        } as i32;
+        ((len * 100 + total / 2) / total).max(1)
+    };
+    let neighbour = if pos + 1 < shown.len() {
+        pos + 1
     } else {
-        // If no columns visible, create a single full-width column
-        vec![main_area]
+        pos - 1
     };
+    let (own, other) = (percent(areas[pos]), percent(areas[neighbour]));
+    // Neither side goes below 1%
+    let step = step.clamp(1 - own, other - 1);
+    if step == 0 {
+        return false;
+    }
+    children[shown[pos]].size = Size::Percent((own + step) as u16);
+    children[shown[neighbour]].size = Size::Percent((other - step) as u16);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect::new(0, 0, 100, 50);
+
+    fn spec(size: Size) -> Spec {
+        (size, 0, u16::MAX)
+    }
+
+    fn size_of(root: &LayoutNode, pane: Pane) -> Size {
+        match &root.content {
+            NodeContent::Pane(p) if *p == pane => root.size,
+            NodeContent::Pane(_) => Size::Fill,
+            NodeContent::Split(_, children) => children
+                .iter()
+                .find(|c| c.panes().contains(&pane))
+                .map_or(Size::Fill, |c| size_of(c, pane)),
+        }
+    }
+
+    #[test]
+    fn hidden_siblings_leave_their_space_to_the_rest() {
+        let config = LayoutConfig {
+            root: default_layout(),
+            ..LayoutConfig::default()
+        };
+        let visibility = SectionVisibility {
+            artwork: false,
+            ..SectionVisibility::default()
+        };
+        let layout = compute_layout(AREA, &visibility, &config);
+        assert_eq!(layout.area_of(Pane::Artwork), None);
+        assert_eq!(layout.area_of(Pane::Files), Some(Rect::new(0, 0, 25, 40)));
+        assert_eq!(layout.area_of(Pane::Player), Some(Rect::new(25, 0, 75, 40)));
+        assert_eq!(
+            layout.area_of(Pane::Visualizer),
+            Some(Rect::new(0, 40, 100, 10))
+        );
+    }
+
+    #[test]
+    fn lengths_stay_within_min_and_max() {
+        let specs = [
+            (Size::Cells(5), 10, u16::MAX),
+            (Size::Fill, 0, 30),
+            spec(Size::Fill),
+        ];
+        assert_eq!(lengths(100, &specs), [10, 30, 60]);
+        let specs = [(Size::Percent(50), 0, 20), spec(Size::Percent(50))];
+        assert_eq!(lengths(100, &specs), [20, 80]);
+    }
+
+    #[test]
+    fn leftover_cells_are_handed_out() {
+        let thirds = [spec(Size::Percent(33)); 3];
+        assert_eq!(lengths(10, &thirds), [3, 3, 4]);
+        assert_eq!(lengths(10, &[spec(Size::Fill); 3]), [4, 3, 3]);
+        let specs = [spec(Size::Percent(20)), spec(Size::Percent(60))];
+        assert_eq!(lengths(100, &specs), [25, 75]);
+        assert_eq!(lengths(100, &[spec(Size::Cells(10))]), [100]);
+    }
+
+    #[test]
+    fn overfull_splits_cut_the_last_children() {
+        let specs = [spec(Size::Cells(8)), spec(Size::Cells(6))];
+        assert_eq!(lengths(10, &specs), [8, 2]);
+        let specs = [spec(Size::Cells(6)), spec(Size::Cells(6))];
+        assert_eq!(lengths(5, &specs), [5, 0]);
+        let specs = [(Size::Cells(4), 4, u16::MAX), (Size::Fill, 3, u16::MAX)];
+        assert_eq!(lengths(5, &specs), [4, 1]);
+    }
+
+    #[test]
+    fn resize_stops_at_one_percent_in_nested_splits() {
+        let mut root = default_layout();
+        let shown = SectionVisibility::default();
+        let (files, columns) = (Pane::Files, SplitDirection::Columns);
+
+        assert!(resize(&mut root, AREA, &shown, files, columns, -30));
+        assert_eq!(size_of(&root, Pane::Files), Size::Percent(1));
+        assert_eq!(size_of(&root, Pane::Player), Size::Percent(71));
+        assert!(!resize(&mut root, AREA, &shown, files, columns, -2));
+
+        // Rows resize the outer split against the visualizer
+        assert!(resize(
+            &mut root,
+            AREA,
+            &shown,
+            files,
+            SplitDirection::Rows,
+            100
+        ));
+        assert_eq!(size_of(&root, Pane::Visualizer), Size::Percent(1));
+        let NodeContent::Split(_, children) = &root.content else {
+            unreachable!()
+        };
+        assert_eq!(children[0].size, Size::Percent(99));
+    }
 
-    ComputedLayout {
-        main_area,
-        visualizer_area,
-        columns,
-        section_order,
+    #[test]
+    fn resize_needs_a_shown_neighbour() {
+        let mut root = default_layout();
+        let alone = SectionVisibility {
+            player: false,
+            artwork: false,
+            visualizer: false,
+            ..SectionVisibility::default()
+        };
+        assert!(!resize(
+            &mut root,
+            AREA,
+            &alone,
+            Pane::Files,
+            SplitDirection::Columns,
+            2
+        ));
+        assert!(!resize(
+            &mut root,
+            AREA,
+            &alone,
+            Pane::Player,
+            SplitDirection::Columns,
+            2
+        ));
+        assert_eq!(root, default_layout());
     }
 }
@@ -3,7 +3,9 @@
 
 pub mod icons;
 pub mod keybindings;
+pub mod keymap;
 pub mod layout;
+pub mod theme;
 pub mod tui;
 pub mod widgets;
 
@@ -0,0 +1,466 @@
+// src/ui/theme.rs
+//! Colors, styles, borders and icons of the UI.
+//!
+//! A theme starts from a named palette. Every widget style is derived from
+//! the palette unless a theme or the `[theme]` config section overrides it.
+
+use std::str::FromStr;
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Borders},
+};
+use serde::{Deserialize, Deserializer};
+
+use super::icons::Icons;
+use crate::config::ThemeConfig;
+
+/// Bundled themes, in the order the theme key cycles through them.
+pub const BUNDLED: &[(&str, &str)] = &[
+    ("default", ""),
+    ("gruvbox", include_str!("themes/gruvbox.toml")),
+    ("nord", include_str!("themes/nord.toml")),
+    ("mono", include_str!("themes/mono.toml")),
+];
+
+/// Named colors that widget styles are built from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub foreground: Color,
+    pub background: Color,
+    pub muted: Color,
+    pub accent: Color,
+    pub highlight: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub border: Color,
+    pub progress: Color,
+    pub spectrum: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            foreground: Color::Reset,
+            background: Color::Reset,
+            muted: Color::DarkGray,
+            accent: Color::Cyan,
+            highlight: Color::Yellow,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            border: Color::Reset,
+            progress: Color::Magenta,
+            spectrum: Color::White,
+        }
+    }
+}
+
+impl Palette {
+    /// Names of the palette colors.
+    pub const NAMES: &[&str] = &[
+        "foreground",
+        "background",
+        "muted",
+        "accent",
+        "highlight",
+        "success",
+        "warning",
+        "error",
+        "border",
+        "progress",
+        "spectrum",
+    ];
+
+    /// The color called `name`.
+    pub fn get(&self, name: &str) -> Option<Color> {
+        let mut copy = *self;
+        copy.slot(name).copied()
+    }
+
+    fn slot(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "foreground" => &mut self.foreground,
+            "background" => &mut self.background,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "highlight" => &mut self.highlight,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "border" => &mut self.border,
+            "progress" => &mut self.progress,
+            "spectrum" => &mut self.spectrum,
+            _ => return None,
+        })
+    }
+}
+
+/// Style of every themed UI element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Styles {
+    /// Panel background and plain text
+    pub text: Style,
+    pub border: Style,
+    /// Border of the focused panel
+    pub focus: Style,
+    pub title: Style,
+    /// Highlighted list row
+    pub selection: Style,
+    /// Characters matched by a search
+    pub matched: Style,
+    /// Unplayable entries, detail columns and hints
+    pub muted: Style,
+    pub error: Style,
+    pub link: Style,
+    pub broken_link: Style,
+    /// Remembered playback position marker
+    pub resume: Style,
+    /// Finder prompt
+    pub prompt: Style,
+    /// Chapter start times
+    pub time: Style,
+    /// Marker of the chapter playing
+    pub current: Style,
+    /// Previous/next buttons
+    pub button: Style,
+    pub stop: Style,
+    /// Play button with nothing playing
+    pub idle: Style,
+    pub playing: Style,
+    pub paused: Style,
+    pub volume: Style,
+    /// Repeat and shuffle indicators when on and off
+    pub mode_on: Style,
+    pub mode_off: Style,
+    pub progress: Style,
+    /// Chapter ticks on the progress gauge
+    pub tick: Style,
+    pub spectrum: Style,
+}
+
+impl Styles {
+    /// Names of the styles, as written under `[theme.styles]`.
+    pub const NAMES: &[&str] = &[
+        "text",
+        "border",
+        "focus",
+        "title",
+        "selection",
+        "matched",
+        "muted",
+        "error",
+        "link",
+        "broken_link",
+        "resume",
+        "prompt",
+        "time",
+        "current",
+        "button",
+        "stop",
+        "idle",
+        "playing",
+        "paused",
+        "volume",
+        "mode_on",
+        "mode_off",
+        "progress",
+        "tick",
+        "spectrum",
+    ];
+
+    /// Styles derived from `p`.
+    pub fn from_palette(p: &Palette) -> Self {
+        let fg = |color| Style::default().fg(color);
+        Self {
+            text: fg(p.foreground).bg(p.background),
+            border: fg(p.border),
+            focus: fg(p.accent),
+            title: fg(p.foreground),
+            selection: Style::default().add_modifier(Modifier::REVERSED),
+            matched: fg(p.highlight).add_modifier(Modifier::BOLD),
+            muted: fg(p.muted),
+            error: fg(p.error),
+            link: fg(p.accent),
+            broken_link: fg(p.error),
+            resume: fg(p.warning),
+            prompt: fg(p.accent),
+            time: fg(p.accent),
+            current: fg(p.success),
+            button: fg(p.accent),
+            stop: fg(p.error),
+            idle: fg(p.foreground),
+            playing: fg(p.success),
+            paused: fg(p.warning),
+            volume: fg(p.foreground),
+            mode_on: fg(p.accent),
+            mode_off: fg(p.muted),
+            progress: fg(p.progress).add_modifier(Modifier::ITALIC),
+            tick: fg(p.muted),
+            spectrum: fg(p.spectrum),
+        }
+    }
+
+    fn slot(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "text" => &mut self.text,
+            "border" => &mut self.border,
+            "focus" => &mut self.focus,
+            "title" => &mut self.title,
+            "selection" => &mut self.selection,
+            "matched" => &mut self.matched,
+            "muted" => &mut self.muted,
+            "error" => &mut self.error,
+            "link" => &mut self.link,
+            "broken_link" => &mut self.broken_link,
+            "resume" => &mut self.resume,
+            "prompt" => &mut self.prompt,
+            "time" => &mut self.time,
+            "current" => &mut self.current,
+            "button" => &mut self.button,
+            "stop" => &mut self.stop,
+            "idle" => &mut self.idle,
+            "playing" => &mut self.playing,
+            "paused" => &mut self.paused,
+            "volume" => &mut self.volume,
+            "mode_on" => &mut self.mode_on,
+            "mode_off" => &mut self.mode_off,
+            "progress" => &mut self.progress,
+            "tick" => &mut self.tick,
+            "spectrum" => &mut self.spectrum,
+            _ => return None,
+        })
+    }
+}
+
+/// A color given by palette name or directly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorRef {
+    Palette(String),
+    Color(Color),
+}
+
+impl ColorRef {
+    pub fn resolve(&self, palette: &Palette) -> Color {
+        match self {
+            ColorRef::Palette(name) => palette.get(name).unwrap_or_default(),
+            ColorRef::Color(color) => *color,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorRef {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(d)?;
+        if Palette::NAMES.contains(&name.as_str()) {
+            return Ok(ColorRef::Palette(name));
+        }
+        Color::from_str(&name).map(ColorRef::Color).map_err(|_| {
+            serde::de::Error::custom(format!(
+                "unknown color \"{}\" (use a palette name like \"accent\", a color name, \"#rrggbb\" or 0-255)",
+                name
+            ))
+        })
+    }
+}
+
+/// A style as written in a theme: colors plus text attributes.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleSpec {
+    pub fg: Option<ColorRef>,
+    pub bg: Option<ColorRef>,
+    pub bold: bool,
+    pub italic: bool,
+    pub dim: bool,
+    pub underlined: bool,
+    pub reversed: bool,
+}
+
+impl StyleSpec {
+    pub fn resolve(&self, palette: &Palette) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = &self.fg {
+            style = style.fg(fg.resolve(palette));
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(bg.resolve(palette));
+        }
+        for (on, modifier) in [
+            (self.bold, Modifier::BOLD),
+            (self.italic, Modifier::ITALIC),
+            (self.dim, Modifier::DIM),
+            (self.underlined, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ] {
+            if on {
+                style = style.add_modifier(modifier);
+            }
+        }
+        style
+    }
+}
+
+/// Border drawn around panels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderKind {
+    #[default]
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+}
+
+impl From<BorderKind> for BorderType {
+    fn from(kind: BorderKind) -> Self {
+        match kind {
+            BorderKind::Plain => BorderType::Plain,
+            BorderKind::Rounded => BorderType::Rounded,
+            BorderKind::Double => BorderType::Double,
+            BorderKind::Thick => BorderType::Thick,
+        }
+    }
+}
+
+/// A fully resolved theme.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Name of the bundled theme this is based on
+    pub name: &'static str,
+    pub palette: Palette,
+    pub styles: Styles,
+    pub border: BorderType,
+    pub icons: &'static Icons,
+    /// Prefix of the highlighted list row
+    pub highlight_symbol: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::bundled(0, &ThemeConfig::default())
+    }
+}
+
+impl Theme {
+    /// The configured theme: the bundled theme named in `config` (the
+    /// default one if unknown) with the config's changes on top.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        Self::named(&config.name, config).unwrap_or_else(|| Self::bundled(0, config))
+    }
+
+    /// The bundled theme called `name` with the config's changes on top.
+    pub fn named(name: &str, config: &ThemeConfig) -> Option<Self> {
+        let index = BUNDLED.iter().position(|(n, _)| *n == name)?;
+        Some(Self::bundled(index, config))
+    }
+
+    /// The bundled theme at `index` with the config's changes on top.
+    pub fn bundled(index: usize, config: &ThemeConfig) -> Self {
+        let (name, text) = BUNDLED[index % BUNDLED.len()];
+        let base: ThemeConfig = toml::from_str(text).expect("valid bundled theme");
+        let specs = [&base, config];
+
+        // The config's old top-level colors go between the two palettes
+        let legacy = [("progress", config.progress), ("spectrum", config.spectrum)]
+            .into_iter()
+            .filter_map(|(slot, color)| Some((slot, color?)));
+        let colors = base
+            .palette
+            .iter()
+            .map(|(slot, color)| (slot.as_str(), *color))
+            .chain(legacy)
+            .chain(
+                config
+                    .palette
+                    .iter()
+                    .map(|(slot, color)| (slot.as_str(), *color)),
+            );
+        let mut palette = Palette::default();
+        for (slot, color) in colors {
+            if let Some(c) = palette.slot(slot) {
+                *c = color;
+            }
+        }
+        let mut styles = Styles::from_palette(&palette);
+        for (slot, spec) in specs.iter().flat_map(|s| &s.styles) {
+            if let Some(style) = styles.slot(slot) {
+                *style = spec.resolve(&palette);
+            }
+        }
+        // The config's settings win over the bundled theme's
+        let border = config.border.or(base.border).unwrap_or_default();
+        let icons = config.icons.or(base.icons).unwrap_or_default();
+        let highlight_symbol = config
+            .highlight_symbol
+            .as_ref()
+            .or(base.highlight_symbol.as_ref());
+        Self {
+            name,
+            palette,
+            styles,
+            border: border.into(),
+            icons: icons.icons(),
+            highlight_symbol: highlight_symbol.map_or(">> ", |s| s.as_str()).to_string(),
+        }
+    }
+
+    /// The bundled theme after this one, with the config's changes on top.
+    pub fn next(&self, config: &ThemeConfig) -> Self {
+        let index = BUNDLED
+            .iter()
+            .position(|(name, _)| *name == self.name)
+            .unwrap_or(0);
+        Self::bundled(index + 1, config)
+    }
+
+    /// This theme for drawing the focused panel.
+    pub fn focused(&self) -> Self {
+        let mut theme = self.clone();
+        theme.styles.border = self.styles.focus;
+        theme
+    }
+
+    /// A bordered panel with `title`.
+    pub fn block<'a>(&self, title: impl Into<Line<'a>>) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(self.border)
+            .border_style(self.styles.border)
+            .title_style(self.styles.title)
+            .style(self.styles.text)
+            .title(title)
+    }
+}
+
+/// Names of the bundled themes, for messages.
+pub fn bundled_names() -> Vec<&'static str> {
+    BUNDLED.iter().map(|(name, _)| *name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_themes_build() {
+        let config = ThemeConfig::default();
+        for (index, (name, text)) in BUNDLED.iter().enumerate() {
+            let spec: ThemeConfig = toml::from_str(text)
+                .unwrap_or_else(|e| panic!("bundled theme {name} doesn't parse: {e}"));
+            // Unknown slots would be skipped silently
+            let mut palette = Palette::default();
+            for slot in spec.palette.keys() {
+                assert!(palette.slot(slot).is_some(), "{name}: unknown color {slot}");
+            }
+            let mut styles = Styles::from_palette(&palette);
+            for slot in spec.styles.keys() {
+                assert!(styles.slot(slot).is_some(), "{name}: unknown style {slot}");
+            }
+            assert_eq!(Theme::bundled(index, &config).name, *name);
+            assert_eq!(Theme::named(name, &config).unwrap().name, *name);
+        }
+    }
+}
@@ -2,32 +2,70 @@
 //! Terminal UI event loop and rendering.
 
 use std::{
-    io,
+    io, panic,
     time::{Duration, Instant},
 };
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event as CEvent},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event as CEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use crate::app::App;
+use crate::cli::Startup;
+use crate::config::Config;
+
+/// Run the terminal UI application with the given settings and startup actions.
+pub fn run(config: Config, startup: Startup) -> Result<()> {
+    // Leave raw mode before a panic message is printed, so it stays readable
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
 
-/// Run the terminal UI application.
-pub fn run() -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if config.general.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
+
+    // Always restore the terminal, whether the loop ended normally or with an error
+    let result = run_loop(&mut terminal, config, startup);
+    restore_terminal();
+    result
+}
+
+/// Best-effort return to cooked mode and the main screen.
+fn restore_terminal() {
+    let _ = execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+}
+
+/// Create the app and run the event loop, shutting the app down however
+/// the loop ends.
+fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    config: Config,
+    startup: Startup,
+) -> Result<()> {
     terminal.clear()?;
 
-    let mut app = App::new()?;
+    let mut app = App::new(config, startup)?;
+    let result = event_loop(terminal, &mut app);
+    app.shutdown();
+    result
+}
 
+/// Main event/render loop; returns when the user quits.
+fn event_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
     // High refresh rate for smooth drawing (60 Hz = ~16ms per frame)
     let frame_rate = Duration::from_millis(16);
     let mut last_frame = Instant::now();
@@ -42,6 +80,13 @@ pub fn run() -> Result<()> {
     loop {
         // Pull any ready metadata from background loader
         app.process_metadata();
+        app.process_library_events();
+        app.process_playback();
+        app.process_finder();
+        app.process_fs_events();
+        app.process_dir_load();
+        app.process_preview();
+        app.process_cue();
 
         // Update visualizer at a slower rate (30 Hz)
         if last_visualizer_update.elapsed() >= visualizer_update_rate {
@@ -53,11 +98,11 @@ pub fn run() -> Result<()> {
         let timeout = frame_rate.checked_sub(last_frame.elapsed()).unwrap_or_default();
 
         if event::poll(timeout)? {
-            if let CEvent::Key(key) = event::read()? {
-                if app.on_key(key) {
-                    // Quit requested
-                    break;
-                }
+            match event::read()? {
+                // Quit requested
+                CEvent::Key(key) if app.on_key(key) => break,
+                CEvent::Mouse(mouse) => app.on_mouse(mouse),
+                _ => {}
             }
         }
 
@@ -72,9 +117,5 @@ pub fn run() -> Result<()> {
         }
     }
 
-    // Clean up terminal
-    execute!(io::stdout(), LeaveAlternateScreen)?;
-    disable_raw_mode()?;
-
     Ok(())
 }
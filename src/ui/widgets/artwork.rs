@@ -1,19 +1,28 @@
 // src/ui/widgets/artwork.rs
 //! Album artwork display widget.
 
-use ratatui::{
-    layout::Rect,
-    widgets::{Block, Borders},
-    Frame,
-};
+use ratatui::{layout::Rect, Frame};
+use ratatui_image::{protocol::StatefulProtocol, StatefulImage};
+
+use crate::ui::theme::Theme;
 
 /// Render the artwork panel.
-/// Note: Actual image rendering requires ratatui-image integration.
-pub fn render_artwork(f: &mut Frame<'_>, area: Rect) {
-    let title = "3: Artwork";
-    f.render_widget(
-        Block::default().borders(Borders::ALL).title(title),
-        area,
-    );
-    // TODO: Integrate with ratatui-image for actual artwork display
+/// With `preview` set, the named image file is shown instead of track artwork.
+pub fn render_artwork(
+    f: &mut Frame<'_>,
+    area: Rect,
+    preview: Option<(&str, &mut StatefulProtocol)>,
+    theme: &Theme,
+) {
+    let title = match &preview {
+        Some((name, _)) => format!("3: Preview: {}", name),
+        None => "3: Artwork".to_string(),
+    };
+    let block = theme.block(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if let Some((_, image)) = preview {
+        f.render_stateful_widget(StatefulImage::default(), inner, image);
+    }
 }
@@ -0,0 +1,51 @@
+// src/ui/widgets/chapters.rs
+//! Chapter list popup for the playing track.
+
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use super::file_list::format_duration;
+use super::finder::centered_rect;
+use crate::audio::Chapter;
+use crate::ui::theme::Theme;
+
+/// Render `chapters` as a centered popup over `area`. The chapter playing
+/// (`current`) is marked; `selected` is the highlighted row.
+pub fn render_chapter_list(
+    f: &mut Frame<'_>,
+    area: Rect,
+    chapters: &[Chapter],
+    current: Option<usize>,
+    selected: usize,
+    theme: &Theme,
+) {
+    let popup = centered_rect(area, 60, 60);
+    f.render_widget(Clear, popup);
+
+    let items: Vec<ListItem> = chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            let start = format_duration(chapter.start.as_secs());
+            let marker = if Some(i) == current { theme.icons.current } else { "" };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<2}", marker), theme.styles.current),
+                Span::styled(format!("{:>8}  ", start), theme.styles.time),
+                Span::raw(chapter.title.clone()),
+            ]))
+        })
+        .collect();
+
+    let title = format!("Chapters ({})  Enter: jump  Esc: close", chapters.len());
+    let list = List::new(items)
+        .block(theme.block(title))
+        .highlight_style(theme.styles.selection)
+        .highlight_symbol(theme.highlight_symbol.as_str());
+    let mut state = ListState::default();
+    state.select((!chapters.is_empty()).then_some(selected));
+    f.render_stateful_widget(list, popup, &mut state);
+}
@@ -2,34 +2,263 @@
 //! File browser list widget.
 
 use ratatui::{
-    style::{Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    style::Style,
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph},
     Frame,
     layout::Rect,
 };
 
-use crate::fs::FileCategory;
-use crate::ui::icons::icon_for_entry;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::fs::{DirEntry, FileCategory};
+use crate::ui::theme::Theme;
+
+/// Optional detail columns shown to the right of entry names.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FileColumns {
+    /// Track length
+    pub duration: bool,
+    /// File size
+    pub size: bool,
+    /// Modification date
+    pub modified: bool,
+    /// Audio bitrate
+    pub bitrate: bool,
+}
+
+impl FileColumns {
+    /// Next column set: none → duration → +size → +modified → +bitrate → none.
+    pub fn cycle(self) -> Self {
+        match (self.duration, self.size, self.modified, self.bitrate) {
+            (false, ..) => Self { duration: true, ..Self::default() },
+            (true, false, ..) => Self { size: true, ..self },
+            (true, true, false, _) => Self { modified: true, ..self },
+            (true, true, true, false) => Self { bitrate: true, ..self },
+            _ => Self::default(),
+        }
+    }
+
+    /// Show or hide column `n`: 1 duration, 2 size, 3 modified, 4 bitrate.
+    pub fn toggle(self, n: usize) -> Self {
+        match n {
+            1 => Self { duration: !self.duration, ..self },
+            2 => Self { size: !self.size, ..self },
+            3 => Self { modified: !self.modified, ..self },
+            4 => Self { bitrate: !self.bitrate, ..self },
+            _ => self,
+        }
+    }
+
+    /// Cell texts and widths of the enabled columns for `entry`.
+    fn cells(&self, entry: &DirEntry) -> Vec<(String, usize)> {
+        let mut cells = Vec::new();
+        if self.duration {
+            cells.push((entry.duration_secs().map(format_duration).unwrap_or_default(), 8));
+        }
+        if self.size {
+            cells.push((entry.size.map(format_size).unwrap_or_default(), 7));
+        }
+        if self.modified {
+            cells.push((entry.modified.map(format_date).unwrap_or_default(), 10));
+        }
+        if self.bitrate {
+            cells.push((entry.bitrate().map(|b| format!("{}k", b)).unwrap_or_default(), 5));
+        }
+        cells
+    }
+}
+
+/// What the file list shows.
+#[derive(Debug, Clone, Copy)]
+pub struct FileListView<'a> {
+    pub title: &'a str,
+    pub entries: &'a [DirEntry],
+    pub columns: FileColumns,
+    /// With `error` set, the directory couldn't be read and the error is shown instead
+    pub error: Option<&'a str>,
+    /// With `filter` set, only those rows `(entry index, matched char indices)` are shown
+    pub filter: Option<&'a [(usize, Vec<usize>)]>,
+}
 
 /// Render the file browser list.
 pub fn render_file_list(
     f: &mut Frame<'_>,
     area: Rect,
-    title: &str,
-    entries: &[(String, bool, FileCategory, String)],
+    view: &FileListView<'_>,
     state: &mut ListState,
+    theme: &Theme,
 ) {
-    let items: Vec<ListItem> = entries
-        .iter()
-        .map(|(name, is_dir, category, _)| {
-            ListItem::new(format!("{} {}", icon_for_entry(*is_dir, category), name))
-        })
-        .collect();
+    let FileListView {
+        title,
+        entries,
+        columns,
+        error,
+        filter,
+    } = *view;
+    let block = theme.block(title.to_string());
+
+    if let Some(error) = error {
+        let message = Paragraph::new(vec![
+            Line::styled(format!("{} {}", theme.icons.warning, error), theme.styles.error),
+            Line::styled("← to go back", theme.styles.muted),
+        ])
+        .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+
+    // Borders, highlight symbol and icon take the rest
+    let width = (area.width as usize)
+        .saturating_sub(4 + theme.highlight_symbol.chars().count());
+    let item = |entry: &DirEntry, matched: &[usize]| {
+        let icon = theme.icons.for_entry(entry.is_dir, &entry.category);
+        let mut spans = vec![Span::raw(format!("{} ", icon))];
+        let marker: Vec<Span> = link_marker(entry, theme)
+            .into_iter()
+            .chain(resume_marker(entry, theme))
+            .collect();
+        let cells = columns.cells(entry);
+        if cells.is_empty() {
+            spans.extend(highlight_matches(&entry.name, matched, theme.styles.matched));
+            spans.extend(marker);
+        } else {
+            let cells_width: usize = cells.iter().map(|(_, w)| w + 1).sum();
+            let marker_width: usize = marker.iter().map(|s| s.content.chars().count()).sum();
+            let name_width = width.saturating_sub(cells_width + marker_width);
+            let name = truncate(&entry.name, name_width);
+            let pad = name_width.saturating_sub(name.chars().count());
+            spans.extend(highlight_matches(&name, matched, theme.styles.matched));
+            spans.extend(marker);
+            spans.push(Span::raw(" ".repeat(pad)));
+            for (text, w) in cells {
+                spans.push(Span::styled(format!(" {:>w$}", text, w = w), theme.styles.muted));
+            }
+        }
+        let line = Line::from(spans);
+        // Entries we can't read or play stay visible but dimmed
+        let playable = entry.is_dir || entry.is_audio() || entry.is_playlist();
+        if entry.category == FileCategory::Inaccessible || !playable {
+            ListItem::new(line.style(theme.styles.muted))
+        } else {
+            ListItem::new(line)
+        }
+    };
+
+    let items: Vec<ListItem> = match filter {
+        Some(rows) => rows.iter().map(|(i, m)| item(&entries[*i], m)).collect(),
+        None => entries.iter().map(|e| item(e, &[])).collect(),
+    };
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(title.to_string()))
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
-        .highlight_symbol(">> ");
+        .block(block)
+        .highlight_style(theme.styles.selection)
+        .highlight_symbol(theme.highlight_symbol.as_str());
 
     f.render_stateful_widget(list, area, state);
 }
+
+/// Marker shown after the names of symbolic links; broken links stand out.
+fn link_marker<'a>(entry: &DirEntry, theme: &Theme) -> Option<Span<'a>> {
+    if entry.is_broken_link {
+        Some(Span::styled(format!(" {}", theme.icons.broken_link), theme.styles.broken_link))
+    } else if entry.is_symlink {
+        Some(Span::styled(format!(" {}", theme.icons.link), theme.styles.link))
+    } else {
+        None
+    }
+}
+
+/// Partial-progress marker for files with a remembered playback position.
+fn resume_marker<'a>(entry: &DirEntry, theme: &Theme) -> Option<Span<'a>> {
+    let fraction = entry.resume?.fraction();
+    let [quarter, half, most] = theme.icons.resume;
+    let pie = match fraction {
+        f if f < 0.375 => quarter,
+        f if f < 0.625 => half,
+        _ => most,
+    };
+    Some(Span::styled(
+        format!(" {} {:.0}%", pie, fraction * 100.0),
+        theme.styles.resume,
+    ))
+}
+
+/// Cut `text` to at most `width` chars, ending with an ellipsis when shortened.
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// `m:ss`, or `h:mm:ss` for long tracks.
+pub fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Human-readable size with a binary unit suffix, e.g. `4.2M`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+/// `YYYY-MM-DD` (UTC) for a modification time.
+fn format_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = secs.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Split `text` into spans, giving the chars at `matched` indices style `hl`.
+pub fn highlight_matches<'a>(text: &str, matched: &[usize], hl: Style) -> Vec<Span<'a>> {
+    if matched.is_empty() {
+        return vec![Span::raw(text.to_string())];
+    }
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_hl = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_hl = matched.binary_search(&i).is_ok();
+        if is_hl != run_hl && !run.is_empty() {
+            let s = std::mem::take(&mut run);
+            spans.push(if run_hl { Span::styled(s, hl) } else { Span::raw(s) });
+        }
+        run_hl = is_hl;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(if run_hl { Span::styled(run, hl) } else { Span::raw(run) });
+    }
+    spans
+}
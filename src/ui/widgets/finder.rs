@@ -0,0 +1,79 @@
+// src/ui/widgets/finder.rs
+//! Global fuzzy finder popup.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::file_list::highlight_matches;
+use crate::app::finder::Finder;
+use crate::ui::theme::Theme;
+
+/// Render the finder as a centered popup over `area`.
+pub fn render_finder(f: &mut Frame<'_>, area: Rect, finder: &Finder, theme: &Theme) {
+    let popup = centered_rect(area, 80, 70);
+    f.render_widget(Clear, popup);
+
+    let loading = if finder.is_loading() { " …" } else { "" };
+    let title = format!(
+        "Find ({}/{}{})  Enter: play  Tab: enqueue  Ctrl-o: open folder",
+        finder.results.len(),
+        finder.items.len(),
+        loading
+    );
+    let block = theme.block(title);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    let prompt = Line::from(vec![
+        Span::styled("> ", theme.styles.prompt),
+        Span::raw(format!("{}{}", finder.query, theme.icons.cursor)),
+    ]);
+    f.render_widget(Paragraph::new(prompt), chunks[0]);
+
+    let items: Vec<ListItem> = finder
+        .results
+        .iter()
+        .map(|(i, m)| {
+            ListItem::new(Line::from(highlight_matches(
+                &finder.items[*i].haystack,
+                &m.indices,
+                theme.styles.matched,
+            )))
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(theme.styles.selection)
+        .highlight_symbol(theme.highlight_symbol.as_str());
+    let mut state = ListState::default();
+    state.select((!finder.results.is_empty()).then_some(finder.selected));
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+/// A rectangle of `percent_x` by `percent_y` centered in `area`.
+pub fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
@@ -0,0 +1,77 @@
+// src/ui/widgets/help.rs
+//! Help overlay listing every action and its keys.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::finder::centered_rect;
+use crate::app::help::{HelpLine, HelpView};
+use crate::ui::theme::Theme;
+
+/// Render the help as a centered popup over `area`, scrolled to
+/// `help.scroll` (kept within the lines shown).
+pub fn render_help(f: &mut Frame<'_>, area: Rect, help: &mut HelpView, theme: &Theme) {
+    let popup = centered_rect(area, 70, 80);
+    f.render_widget(Clear, popup);
+
+    let title = "Help  /: search  Esc: close";
+    let block = theme.block(title);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    // The search prompt takes the first row once a search starts
+    let searching = help.editing || !help.query.is_empty();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(searching as u16), Constraint::Min(1)])
+        .split(inner);
+    if searching {
+        let cursor = if help.editing { theme.icons.cursor } else { "" };
+        let prompt = Line::from(vec![
+            Span::styled("/", theme.styles.prompt),
+            Span::raw(format!("{}{}", help.query, cursor)),
+        ]);
+        f.render_widget(Paragraph::new(prompt), chunks[0]);
+    }
+
+    let list = chunks[1];
+    help.height = list.height as usize;
+    help.scroll_by(0);
+    let width = help.keys_width().min(list.width as usize / 2);
+    let lines: Vec<Line> = (help.scroll..help.shown.len())
+        .take(help.height)
+        .map(|i| match help.line(i) {
+            HelpLine::Heading(title) => Line::from(Span::styled(
+                title.clone(),
+                theme.styles.title.add_modifier(Modifier::BOLD),
+            )),
+            HelpLine::Entry {
+                keys,
+                description,
+                name,
+            } => {
+                let keys = if keys.is_empty() {
+                    Span::styled(format!("  {:<width$}", "-"), theme.styles.muted)
+                } else {
+                    Span::styled(format!("  {:<width$}", keys), theme.styles.time)
+                };
+                Line::from(vec![
+                    keys,
+                    Span::raw(format!("  {}", description)),
+                    Span::styled(format!("  {}", name), theme.styles.muted),
+                ])
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        let none = Span::styled("no matching keys", theme.styles.muted);
+        f.render_widget(Paragraph::new(Line::from(none)), list);
+    } else {
+        f.render_widget(Paragraph::new(lines), list);
+    }
+}
@@ -0,0 +1,47 @@
+// src/ui/widgets/library_list.rs
+//! Library browser list widget (artists, albums, tracks...).
+
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState},
+    Frame,
+};
+
+use super::file_list::highlight_matches;
+use crate::app::library_view::LibraryNode;
+use crate::ui::theme::Theme;
+
+/// Render the nodes of the current library browse level.
+/// With `filter` set, only those rows `(node index, matched char indices)` are shown.
+pub fn render_library_list(
+    f: &mut Frame<'_>,
+    area: Rect,
+    title: &str,
+    nodes: &[LibraryNode],
+    filter: Option<&[(usize, Vec<usize>)]>,
+    state: &mut ListState,
+    theme: &Theme,
+) {
+    let item = |node: &LibraryNode, matched: &[usize]| {
+        let icon = match node {
+            LibraryNode::Group { .. } => theme.icons.folder,
+            LibraryNode::Track(_) => theme.icons.audio,
+        };
+        let mut spans = vec![Span::raw(format!("{} ", icon))];
+        spans.extend(highlight_matches(&node.label(), matched, theme.styles.matched));
+        ListItem::new(Line::from(spans))
+    };
+
+    let items: Vec<ListItem> = match filter {
+        Some(rows) => rows.iter().map(|(i, m)| item(&nodes[*i], m)).collect(),
+        None => nodes.iter().map(|n| item(n, &[])).collect(),
+    };
+
+    let list = List::new(items)
+        .block(theme.block(title.to_string()))
+        .highlight_style(theme.styles.selection)
+        .highlight_symbol(theme.highlight_symbol.as_str());
+
+    f.render_stateful_widget(list, area, state);
+}
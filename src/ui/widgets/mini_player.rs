@@ -0,0 +1,78 @@
+// src/ui/widgets/mini_player.rs
+//! Mini-player: the player collapsed to at most three borderless lines.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Gauge, Paragraph},
+    Frame,
+};
+
+use super::player_panel::{PlayerControl, PlayerInfo};
+use crate::audio::Visualizer;
+use crate::ui::theme::Theme;
+
+/// Render the mini-player at the top of `area` and return where the
+/// progress bar was drawn. The first line names the track (or shows
+/// `status` when there is one); with room, a progress bar and a one-row
+/// spectrum follow. A single line also carries the time.
+pub fn render_mini_player(
+    f: &mut Frame<'_>,
+    area: Rect,
+    info: &PlayerInfo<'_>,
+    visualizer: &Visualizer,
+    status: &str,
+    theme: &Theme,
+) -> Vec<(Rect, PlayerControl)> {
+    f.render_widget(Block::default().style(theme.styles.text), area);
+    let lines = area.height.min(3) as usize;
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); lines])
+        .split(area);
+    let Some(&first) = rows.first() else {
+        return Vec::new();
+    };
+
+    // Time (on a single line) or volume on the right of the first line
+    let right = if lines == 1 {
+        Span::styled(info.time_label(), theme.styles.time)
+    } else {
+        Span::styled(
+            format!("vol {:.0}%", info.volume * 100.0),
+            theme.styles.volume,
+        )
+    };
+    let right_width = (right.width() as u16 + 1).min(first.width);
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(right_width)])
+        .split(first);
+    let text = if !status.is_empty() {
+        Span::styled(status.to_string(), theme.styles.prompt)
+    } else {
+        match info.title() {
+            Some(title) => Span::styled(title, theme.styles.title),
+            None => Span::styled("No track playing", theme.styles.muted),
+        }
+    };
+    let title = Line::from(vec![info.play_pause_button(theme), text]);
+    f.render_widget(Paragraph::new(title), chunks[0]);
+    f.render_widget(Paragraph::new(Line::from(right)).right_aligned(), chunks[1]);
+
+    let mut regions = Vec::new();
+    if let Some(&gauge) = rows.get(1) {
+        f.render_widget(
+            Gauge::default()
+                .gauge_style(theme.styles.progress)
+                .ratio(info.ratio())
+                .label(info.time_label()),
+            gauge,
+        );
+        regions.push((gauge, PlayerControl::Progress));
+    }
+    if let Some(&spectrum) = rows.get(2) {
+        visualizer.render_line(f, spectrum, theme);
+    }
+    regions
+}
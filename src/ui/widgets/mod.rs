@@ -2,12 +2,24 @@
 //! Custom widgets for the chirpy UI.
 
 pub mod artwork;
+pub mod chapters;
 pub mod file_list;
+pub mod finder;
+pub mod help;
+pub mod library_list;
+pub mod mini_player;
 pub mod player_panel;
+pub mod playlist;
 pub mod spectrum;
 
 // Re-export widget rendering functions
 pub use artwork::render_artwork;
-pub use file_list::render_file_list;
-pub use player_panel::render_player_panel;
+pub use chapters::render_chapter_list;
+pub use file_list::{render_file_list, FileColumns, FileListView};
+pub use finder::render_finder;
+pub use help::render_help;
+pub use library_list::render_library_list;
+pub use mini_player::render_mini_player;
+pub use player_panel::{render_player_panel, PlayerControl, PlayerInfo};
+pub use playlist::render_playlist;
 pub use spectrum::render_spectrum;
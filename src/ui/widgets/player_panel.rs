@@ -2,30 +2,121 @@
 //! Player information panel widget.
 
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, Paragraph, Wrap},
+    widgets::{Gauge, Paragraph, Wrap},
     Frame,
 };
 
-use crate::audio::TrackMetadata;
+use std::{path::Path, time::Duration};
 
-/// Render the player information panel.
+use crate::app::queue::RepeatMode;
+use crate::audio::{chapters::chapter_at, TrackMetadata};
+use crate::playlist::cue::CueSheet;
+use crate::ui::theme::Theme;
+
+/// A clickable part of the player panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerControl {
+    Previous,
+    Stop,
+    PlayPause,
+    Next,
+    Repeat,
+    Shuffle,
+    /// The progress gauge
+    Progress,
+}
+
+/// What the player shows about playback.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerInfo<'a> {
+    /// File playing
+    pub path: Option<&'a Path>,
+    pub metadata: Option<&'a TrackMetadata>,
+    /// With `cue` set, the file is a CUE album and `(index, sheet)` names the
+    /// cue track playing; `elapsed` and `duration` are then relative to it
+    pub cue: Option<(usize, &'a CueSheet)>,
+    pub elapsed: u64,
+    pub duration: u64,
+    pub is_playing: bool,
+    pub is_paused: bool,
+    pub volume: f32,
+    pub repeat: RepeatMode,
+    pub shuffle: bool,
+}
+
+impl PlayerInfo<'_> {
+    /// "Title – Artist" of the track playing, from its cue track or tags,
+    /// else its file name.
+    pub fn title(&self) -> Option<String> {
+        if let Some((i, sheet)) = self.cue {
+            return Some(sheet.tracks[i].display_title());
+        }
+        match self.metadata.map(|m| (&m.title, &m.artist)) {
+            Some((Some(title), Some(artist))) => Some(format!("{} – {}", title, artist)),
+            Some((Some(title), None)) => Some(title.clone()),
+            _ => self
+                .path
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned()),
+        }
+    }
+
+    /// Share of the track played, from 0 to 1.
+    pub fn ratio(&self) -> f64 {
+        (self.elapsed as f64 / self.duration as f64).clamp(0.0, 1.0)
+    }
+
+    /// Position and length as `mm:ss / mm:ss`.
+    pub fn time_label(&self) -> String {
+        format!(
+            "{:02}:{:02} / {:02}:{:02}",
+            self.elapsed / 60,
+            self.elapsed % 60,
+            self.duration / 60,
+            self.duration % 60
+        )
+    }
+
+    /// The play/pause button for the playback state.
+    pub fn play_pause_button(&self, theme: &Theme) -> Span<'static> {
+        let icons = theme.icons;
+        let (icon, style) = if !self.is_playing {
+            (icons.play, theme.styles.idle)
+        } else if self.is_paused {
+            (icons.play, theme.styles.paused)
+        } else {
+            (icons.pause, theme.styles.playing)
+        };
+        Span::styled(format!(" {} ", icon), style)
+    }
+}
+
+/// Render the player information panel and return where its controls were
+/// drawn. Chapter starts of the track are marked on the progress gauge
+/// unless a cue track is playing. The track details, followed by the
+/// lyrics, start at line `scroll`, kept within them.
 pub fn render_player_panel(
     f: &mut Frame<'_>,
     area: Rect,
-    metadata: Option<&TrackMetadata>,
-    elapsed: u64,
-    duration: u64,
-    is_playing: bool,
-    is_paused: bool,
-) {
+    info: &PlayerInfo<'_>,
+    scroll: &mut usize,
+    theme: &Theme,
+) -> Vec<(Rect, PlayerControl)> {
+    let PlayerInfo {
+        metadata,
+        cue,
+        elapsed,
+        duration,
+        volume,
+        repeat,
+        shuffle,
+        ..
+    } = *info;
     let title = "2: Player";
-    f.render_widget(
-        Block::default().borders(Borders::ALL).title(title),
-        area,
-    );
+    f.render_widget(theme.block(title), area);
 
     let inner = Layout::default()
         .direction(Direction::Vertical)
@@ -41,18 +132,57 @@ pub fn render_player_panel(
         tags,
         properties,
         duration_secs,
+        chapters,
+        lyrics,
         ..
     }) = metadata
     {
-        let mut lines = vec![format!("Duration: {}s", duration_secs)];
+        let mut lines = Vec::new();
+        if let Some((i, sheet)) = cue {
+            let track = &sheet.tracks[i];
+            lines.push(format!(
+                "Cue track {}/{}: {}",
+                i + 1,
+                sheet.tracks.len(),
+                track.display_title()
+            ));
+            if let Some(album) = &sheet.title {
+                lines.push(format!("Album: {}", album));
+            }
+        }
+        if cue.is_none()
+            && let Some(i) = chapter_at(chapters, Duration::from_secs(elapsed))
+        {
+            lines.push(format!(
+                "Chapter {}/{}: {}",
+                i + 1,
+                chapters.len(),
+                chapters[i].title
+            ));
+        }
+        lines.push(format!("Duration: {}s", duration_secs));
         for (k, v) in tags {
             lines.push(format!("{}: {}", k, v));
         }
         for (k, v) in properties {
             lines.push(format!("{}: {}", k, v));
         }
+        if let Some(lyrics) = lyrics.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+            lines.push(String::new());
+            lines.push("Lyrics:".to_string());
+            lines.extend(lyrics.lines().map(str::to_string));
+        }
+        // Lines as wrapped to the panel width
+        let width = inner[0].width.max(1) as usize;
+        let rows: usize = lines
+            .iter()
+            .map(|line| Span::raw(line.as_str()).width().div_ceil(width).max(1))
+            .sum();
+        *scroll = (*scroll).min(rows.saturating_sub(inner[0].height as usize));
         f.render_widget(
-            Paragraph::new(lines.join("\n")).wrap(Wrap { trim: true }),
+            Paragraph::new(lines.join("\n"))
+                .wrap(Wrap { trim: true })
+                .scroll((*scroll as u16, 0)),
             inner[0],
         );
     } else {
@@ -63,45 +193,85 @@ pub fn render_player_panel(
     }
 
     // Playback control buttons
-    let play_pause_icon = if !is_playing {
-        Span::styled(" ⏵ ", Style::default().fg(Color::Gray))
-    } else if is_paused {
-        Span::styled(" ⏵ ", Style::default().fg(Color::Yellow))
-    } else {
-        Span::styled(" ⏸ ", Style::default().fg(Color::Green))
-    };
-
-    let controls = Line::from(vec![
-        Span::styled(" ⏮ ", Style::default().fg(Color::Cyan)),  // Previous (p/<)
-        Span::raw(" "),
-        Span::styled(" ⏹ ", Style::default().fg(Color::Red)),   // Stop (s)
-        Span::raw(" "),
-        play_pause_icon,                                         // Play/Pause (space)
-        Span::raw(" "),
-        Span::styled(" ⏭ ", Style::default().fg(Color::Cyan)),  // Next (n/>)
-    ]);
+    let icons = theme.icons;
+    let button = |icon: &str, style: Style| Span::styled(format!(" {} ", icon), style);
+    let play_pause_icon = info.play_pause_button(theme);
+    // Highlighted when a playback mode is on, dimmed when off
+    let mode_style = |on: bool| if on { theme.styles.mode_on } else { theme.styles.mode_off };
+
+    let parts = [
+        (button(icons.previous, theme.styles.button), Some(PlayerControl::Previous)),
+        (Span::raw(" "), None),
+        (button(icons.stop, theme.styles.stop), Some(PlayerControl::Stop)),
+        (Span::raw(" "), None),
+        (play_pause_icon, Some(PlayerControl::PlayPause)),
+        (Span::raw(" "), None),
+        (button(icons.next, theme.styles.button), Some(PlayerControl::Next)),
+        (Span::raw("   "), None),
+        (Span::styled(format!("vol {:.0}%", volume * 100.0), theme.styles.volume), None),
+        (Span::raw("  "), None),
+        (
+            Span::styled(
+                format!("{} {}", icons.repeat, repeat),
+                mode_style(repeat != RepeatMode::Off),
+            ),
+            Some(PlayerControl::Repeat),
+        ),
+        (Span::raw("  "), None),
+        (Span::styled(icons.shuffle, mode_style(shuffle)), Some(PlayerControl::Shuffle)),
+    ];
 
+    // Centered by hand, so the position of every control is known
+    let row = inner[1];
+    let width: u16 = parts.iter().map(|(span, _)| span.width() as u16).sum();
+    let start = row.x + row.width.saturating_sub(width) / 2;
+    let mut regions = Vec::new();
+    let mut x = start;
+    for (span, control) in &parts {
+        let w = (span.width() as u16).min(row.right().saturating_sub(x));
+        if let Some(control) = control {
+            regions.push((Rect::new(x, row.y, w, row.height), *control));
+        }
+        x += w;
+    }
+    let controls = Line::from(parts.into_iter().map(|(span, _)| span).collect::<Vec<_>>());
     f.render_widget(
-        Paragraph::new(controls).alignment(Alignment::Center),
-        inner[1],
+        Paragraph::new(controls),
+        Rect::new(start, row.y, row.right() - start, row.height),
     );
+    regions.push((inner[2], PlayerControl::Progress));
 
     // Progress bar with time display
-    let ratio = (elapsed as f64 / duration as f64).clamp(0.0, 1.0);
-    let elapsed_min = elapsed / 60;
-    let elapsed_sec = elapsed % 60;
-    let duration_min = duration / 60;
-    let duration_sec = duration % 60;
-    let time_label = format!(
-        "{:02}:{:02} / {:02}:{:02}",
-        elapsed_min, elapsed_sec, duration_min, duration_sec
-    );
-
+    let ratio = info.ratio();
     f.render_widget(
         Gauge::default()
-            .gauge_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC))
+            .gauge_style(theme.styles.progress)
             .ratio(ratio)
-            .label(time_label),
+            .label(info.time_label()),
         inner[2],
     );
+
+    // Chapter ticks on the top and bottom rows of the gauge
+    let chapters = metadata.map(|m| m.chapters.as_slice()).unwrap_or_default();
+    let gauge = inner[2];
+    if cue.is_none() && duration > 0 && !gauge.is_empty() {
+        let filled = (gauge.width as f64 * ratio).round() as u16;
+        for chapter in chapters.iter().filter(|c| !c.start.is_zero()) {
+            let offset = chapter.start.as_secs_f64() / duration as f64;
+            if offset >= 1.0 {
+                continue;
+            }
+            let x = (gauge.width as f64 * offset) as u16;
+            // Over the filled part, the tick sits on the gauge color
+            let style = match theme.styles.progress.fg {
+                Some(color) if x < filled => theme.styles.tick.bg(color),
+                _ => theme.styles.tick,
+            };
+            let buf = f.buffer_mut();
+            for (y, symbol) in [(gauge.top(), icons.tick_top), (gauge.bottom() - 1, icons.tick_bottom)] {
+                buf[(gauge.x + x, y)].set_symbol(symbol).set_style(style);
+            }
+        }
+    }
+    regions
 }
@@ -0,0 +1,53 @@
+// src/ui/widgets/playlist.rs
+//! Widget listing the tracks of an opened playlist.
+
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState},
+    Frame,
+};
+
+use super::file_list::highlight_matches;
+use crate::app::playlist_view::PlaylistView;
+use crate::playlist::PlaylistEntry;
+use crate::ui::theme::Theme;
+
+/// Render the entries of a playlist. Entries whose file is missing are dimmed.
+/// With `filter` set, only those rows `(entry index, matched char indices)` are shown.
+pub fn render_playlist(
+    f: &mut Frame<'_>,
+    area: Rect,
+    title: &str,
+    entries: &[PlaylistEntry],
+    filter: Option<&[(usize, Vec<usize>)]>,
+    state: &mut ListState,
+    theme: &Theme,
+) {
+    let item = |entry: &PlaylistEntry, matched: &[usize]| {
+        let mut spans = vec![Span::raw(format!("{} ", theme.icons.audio))];
+        spans.extend(highlight_matches(
+            &PlaylistView::label(entry),
+            matched,
+            theme.styles.matched,
+        ));
+        let line = Line::from(spans);
+        if entry.missing {
+            ListItem::new(line.style(theme.styles.muted))
+        } else {
+            ListItem::new(line)
+        }
+    };
+
+    let items: Vec<ListItem> = match filter {
+        Some(rows) => rows.iter().map(|(i, m)| item(&entries[*i], m)).collect(),
+        None => entries.iter().map(|e| item(e, &[])).collect(),
+    };
+
+    let list = List::new(items)
+        .block(theme.block(title.to_string()))
+        .highlight_style(theme.styles.selection)
+        .highlight_symbol(theme.highlight_symbol.as_str());
+
+    f.render_stateful_widget(list, area, state);
+}
@@ -4,8 +4,9 @@
 use ratatui::{layout::Rect, Frame};
 
 use crate::audio::Visualizer;
+use crate::ui::theme::Theme;
 
 /// Render the spectrum visualizer.
-pub fn render_spectrum(f: &mut Frame<'_>, area: Rect, visualizer: &Visualizer) {
-    visualizer.render(f, area);
+pub fn render_spectrum(f: &mut Frame<'_>, area: Rect, visualizer: &Visualizer, theme: &Theme) {
+    visualizer.render(f, area, theme);
 }